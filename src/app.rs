@@ -0,0 +1,674 @@
+// app.rs
+
+// Assembles the application from its configuration: repositories for the configured backend, the
+// services over them, the use cases wrapped in their middleware, the buses that dispatch to them
+// and the controllers in front.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+#[cfg(feature = "backup")]
+use crate::backup::{Collection, Entities};
+use crate::bus::command_bus::CommandBus;
+use crate::bus::gate::Gate;
+use crate::bus::query_bus::QueryBus;
+use crate::clock::{Clock, SystemClock};
+use crate::config::{AppConfig, Backend, StorageConfig};
+use crate::controller::estimate_controller::EstimateController;
+use crate::controller::section_controller::SectionController;
+use crate::entity::price_book_item::PriceBookItem;
+use crate::entity::traits::Identifiable;
+use crate::entity::{estimate::Estimate, section::Section};
+use crate::error::Error;
+use crate::id_generator::{IdGenerator, RandomIdGenerator};
+use crate::migration::{self, Target};
+use crate::presenter::formats::OutputFormat;
+#[cfg(feature = "file-backend")]
+use crate::repository::file_repo::FileRepository;
+use crate::repository::in_memory_repo::InMemoryRepository;
+use crate::repository::repository::Repository;
+use crate::service::actor::{ServiceActor, ServiceHandle};
+use crate::service::generic_service::GenericService;
+use crate::telemetry::{self, Level};
+use crate::use_case;
+use crate::use_case::middleware::UseCaseExt;
+
+type Result<T> = std::result::Result<T, Box<Error>>;
+
+type SharedRepository<T> = Arc<Mutex<dyn Repository<T> + Send + Sync>>;
+
+pub struct App {
+    pub config: AppConfig,
+    pub command_bus: Arc<CommandBus>,
+    pub query_bus: Arc<QueryBus>,
+    pub estimate_controller: EstimateController,
+    pub section_controller: SectionController,
+    gate: Arc<Gate>,
+    // Set by the first `begin_shutdown`; running requests must be done by then.
+    shutdown_deadline: OnceLock<Instant>,
+    clock: Arc<dyn Clock>,
+    estimate_service: ServiceActor<Estimate>,
+    section_service: ServiceActor<Section>,
+    price_book_service: ServiceActor<PriceBookItem>,
+}
+
+// What `App::shutdown` got done.
+#[derive(Debug)]
+pub struct ShutdownReport {
+    // Whether every running request finished before the deadline.
+    pub drained: bool,
+    // Requests still running when the deadline passed; their results are lost.
+    pub abandoned: usize,
+    pub snapshot: Option<PathBuf>,
+}
+
+impl App {
+    // Shares the gate the buses admit requests through.
+    pub fn gate(&self) -> Arc<Gate> {
+        Arc::clone(&self.gate)
+    }
+
+    // Refuses new requests and starts the `shutdown.deadline_ms` clock, e.g. from a signal
+    // handler while a request is still running. Later calls, including the one `shutdown` makes,
+    // keep the first deadline, so running requests get it once in total.
+    pub fn begin_shutdown(&self) -> Instant {
+        self.gate.close();
+        *self
+            .shutdown_deadline
+            .get_or_init(|| Instant::now() + self.config.shutdown.deadline())
+    }
+
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        Arc::clone(&self.clock)
+    }
+
+    pub fn estimate_service(&self) -> ServiceHandle<Estimate> {
+        self.estimate_service.handle()
+    }
+
+    pub fn section_service(&self) -> ServiceHandle<Section> {
+        self.section_service.handle()
+    }
+
+    pub fn price_book_service(&self) -> ServiceHandle<PriceBookItem> {
+        self.price_book_service.handle()
+    }
+
+    // Everything `backup` saves and `restore` loads, in restore order.
+    #[cfg(feature = "backup")]
+    pub fn collections(&self) -> Vec<Box<dyn Collection>> {
+        vec![
+            Box::new(Entities::new("estimates", self.estimate_service())),
+            Box::new(Entities::new("sections", self.section_service())),
+            Box::new(Entities::new("price_book", self.price_book_service())),
+        ]
+    }
+
+    // Refuses new requests, gives the running ones until `shutdown.deadline_ms` to finish, flushes
+    // the repositories, writes the configured snapshot and stops the services. Fails if anything
+    // could not be flushed or written; the services are stopped either way.
+    pub async fn shutdown(self) -> Result<ShutdownReport> {
+        let deadline = self.begin_shutdown();
+        let drained = self.gate.drained(deadline).await;
+        let abandoned = self.gate.in_flight();
+        if !drained {
+            tracing::warn!(
+                abandoned,
+                "Shutdown deadline passed with requests still running"
+            );
+        }
+
+        let estimates = self.estimate_service.handle();
+        let sections = self.section_service.handle();
+        let price_book = self.price_book_service.handle();
+        let mut problems = Vec::new();
+        for (collection, flushed) in [
+            ("estimates", estimates.flush().await),
+            ("sections", sections.flush().await),
+            ("price_book", price_book.flush().await),
+        ] {
+            if let Err(e) = flushed {
+                problems.push(format!("Error flushing {}: {}", collection, e));
+            }
+        }
+        let snapshot = match &self.config.shutdown.snapshot_path {
+            Some(path) if problems.is_empty() => {
+                match write_snapshot(path, &estimates, &sections, &price_book).await {
+                    Ok(()) => Some(path.clone()),
+                    Err(e) => {
+                        problems.push(format!("Error writing snapshot: {}", e));
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+
+        // Whatever is still queued runs before the actors stop.
+        if let Err(e) = self.estimate_service.shutdown().await {
+            problems.push(format!("Error stopping services: {}", e));
+        }
+        if let Err(e) = self.section_service.shutdown().await {
+            problems.push(format!("Error stopping services: {}", e));
+        }
+        if let Err(e) = self.price_book_service.shutdown().await {
+            problems.push(format!("Error stopping services: {}", e));
+        }
+
+        if !problems.is_empty() {
+            return Err(Box::new(Error::MainError {
+                message: problems.join("; "),
+            }));
+        }
+        tracing::info!(drained, snapshot = ?snapshot, "Shut down");
+        Ok(ShutdownReport {
+            drained,
+            abandoned,
+            snapshot,
+        })
+    }
+}
+
+// Everything stored at the moment of shutdown.
+#[derive(Serialize)]
+struct Snapshot {
+    taken_at: DateTime<Utc>,
+    estimates: Vec<Estimate>,
+    sections: Vec<Section>,
+    price_book: Vec<PriceBookItem>,
+}
+
+// Written next to the target and renamed, like the file repository does.
+async fn write_snapshot(
+    path: &Path,
+    estimates: &ServiceHandle<Estimate>,
+    sections: &ServiceHandle<Section>,
+    price_book: &ServiceHandle<PriceBookItem>,
+) -> crate::result::Result<()> {
+    let snapshot = Snapshot {
+        taken_at: estimates.now(),
+        estimates: estimates.list().await?,
+        sections: sections.list().await?,
+        price_book: price_book.list().await?,
+    };
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let mut temporary = path.to_path_buf().into_os_string();
+    temporary.push(".tmp");
+    tokio::fs::write(&temporary, serde_json::to_vec_pretty(&snapshot)?).await?;
+    tokio::fs::rename(&temporary, path).await?;
+    Ok(())
+}
+
+pub struct AppBuilder {
+    config: AppConfig,
+    output_format: OutputFormat,
+    clock: Arc<dyn Clock>,
+    ids: Arc<dyn IdGenerator>,
+}
+
+impl AppBuilder {
+    pub fn new(config: AppConfig) -> Self {
+        AppBuilder {
+            config,
+            output_format: OutputFormat::default(),
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+        }
+    }
+
+    // Replaces the system clock everywhere timestamps are taken, e.g. with a `FixedClock`.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    // Replaces random UUIDs for new entities, e.g. with a `SequentialIdGenerator`.
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    pub fn with_output_format(mut self, output_format: OutputFormat) -> Self {
+        self.output_format = output_format;
+        self
+    }
+
+    // Validates the config again before wiring anything. The validation limits go to the services
+    // and the currency to the estimate controller, so apps built from different configs in one
+    // process keep their own. Migrating and opening the repositories run on the blocking pool, as
+    // the file backend reads and writes with blocking calls.
+    pub async fn build(self) -> Result<App> {
+        self.config
+            .validate()
+            .map_err(|e| Box::new(Error::from(e)))?;
+
+        let storage = self.config.storage.clone();
+        let clock = Arc::clone(&self.clock);
+        let (estimate_repo, section_repo, price_book_repo) =
+            tokio::task::spawn_blocking(move || -> Result<_> {
+                migrate(&storage, clock)?;
+                Ok((
+                    repository::<Estimate>(&storage, "estimates")?,
+                    repository::<Section>(&storage, "sections")?,
+                    repository::<PriceBookItem>(&storage, "price_book")?,
+                ))
+            })
+            .await
+            .map_err(|e| {
+                Box::new(Error::MainError {
+                    message: format!("Opening storage stopped abnormally: {}", e),
+                })
+            })??;
+
+        // Start a service actor per aggregate; the use cases share handles to them
+        let estimate_actor = GenericService::<Estimate>::new(estimate_repo)
+            .with_clock(Arc::clone(&self.clock))
+            .with_id_generator(Arc::clone(&self.ids))
+            .with_limits(self.config.validation.clone())
+            .with_policy(self.config.resilience.clone())
+            .spawn();
+        let section_actor = GenericService::<Section>::new(section_repo)
+            .with_clock(Arc::clone(&self.clock))
+            .with_id_generator(Arc::clone(&self.ids))
+            .with_limits(self.config.validation.clone())
+            .with_policy(self.config.resilience.clone())
+            .spawn();
+        let price_book_actor = GenericService::<PriceBookItem>::new(price_book_repo)
+            .with_clock(Arc::clone(&self.clock))
+            .with_id_generator(Arc::clone(&self.ids))
+            .with_limits(self.config.validation.clone())
+            .with_policy(self.config.resilience.clone())
+            .spawn();
+        let estimate_service = estimate_actor.handle();
+        let section_service = section_actor.handle();
+        let price_book_service = price_book_actor.handle();
+
+        // Initialize the use cases with the services
+        let create_estimate = use_case::create_estimate::CreateEstimate::new(
+            estimate_service.clone(),
+            section_service.clone(),
+        )
+        .with_timing("create_estimate")
+        .with_logging("create_estimate");
+        let duplicate_estimate = use_case::duplicate_estimate::DuplicateEstimate::new(
+            estimate_service.clone(),
+            section_service.clone(),
+        )
+        .with_timing("duplicate_estimate")
+        .with_logging("duplicate_estimate");
+        let import_estimate_from_csv =
+            use_case::import_estimate_from_csv::ImportEstimateFromCsv::new(
+                estimate_service.clone(),
+                section_service.clone(),
+            )
+            .with_timing("import_estimate_from_csv")
+            .with_logging("import_estimate_from_csv");
+        let set_estimate_location =
+            use_case::set_estimate_location::SetEstimateLocation::new(estimate_service.clone())
+                .with_timing("set_estimate_location")
+                .with_logging("set_estimate_location");
+        let update_estimate =
+            use_case::update_estimate::UpdateEstimate::new(estimate_service.clone())
+                .with_timing("update_estimate")
+                .with_logging("update_estimate");
+        let delete_estimate = use_case::delete_estimate::DeleteEstimate::new(
+            estimate_service.clone(),
+            section_service.clone(),
+        )
+        .with_timing("delete_estimate")
+        .with_logging("delete_estimate");
+        let get_estimate = use_case::get_estimate::GetEstimate::new(estimate_service.clone())
+            .with_timing("get_estimate")
+            .with_logging("get_estimate");
+        let get_estimate_report = use_case::get_estimate_report::GetEstimateReport::new(
+            estimate_service.clone(),
+            section_service.clone(),
+        )
+        .with_timing("get_estimate_report")
+        .with_logging("get_estimate_report");
+        let list_estimates = use_case::list_estimates::ListEstimates::new(estimate_service.clone())
+            .with_timing("list_estimates")
+            .with_logging("list_estimates");
+        let create_section_add_to_estimate =
+            use_case::create_section_add_to_estimate::CreateSectionAddToEstimate::new(
+                section_service.clone(),
+                estimate_service.clone(),
+            )
+            .with_timing("create_section_add_to_estimate")
+            .with_logging("create_section_add_to_estimate");
+        let update_section = use_case::update_section::UpdateSection::new(section_service.clone())
+            .with_timing("update_section")
+            .with_logging("update_section");
+        let delete_section = use_case::delete_section::DeleteSection::new(section_service.clone())
+            .with_timing("delete_section")
+            .with_logging("delete_section");
+        let move_section = use_case::move_section::MoveSection::new(
+            section_service.clone(),
+            estimate_service.clone(),
+        )
+        .with_timing("move_section")
+        .with_logging("move_section");
+        let reorder_sections =
+            use_case::reorder_sections::ReorderSections::new(section_service.clone())
+                .with_timing("reorder_sections")
+                .with_logging("reorder_sections");
+        let get_section = use_case::get_section::GetSection::new(section_service.clone())
+            .with_timing("get_section")
+            .with_logging("get_section");
+        let list_sections_for_estimate =
+            use_case::list_sections_for_estimate::ListSectionsForEstimate::new(
+                section_service.clone(),
+            )
+            .with_timing("list_sections_for_estimate")
+            .with_logging("list_sections_for_estimate");
+        let add_price_book_item =
+            use_case::add_price_book_item::AddPriceBookItem::new(price_book_service.clone())
+                .with_timing("add_price_book_item")
+                .with_logging("add_price_book_item");
+        let search_price_book =
+            use_case::search_price_book::SearchPriceBook::new(price_book_service.clone())
+                .with_timing("search_price_book")
+                .with_logging("search_price_book");
+        let insert_price_book_item = use_case::insert_price_book_item::InsertPriceBookItem::new(
+            price_book_service.clone(),
+            section_service.clone(),
+        )
+        .with_timing("insert_price_book_item")
+        .with_logging("insert_price_book_item");
+        let reprice_estimate = use_case::reprice_estimate::RepriceEstimate::new(
+            estimate_service.clone(),
+            section_service.clone(),
+            price_book_service.clone(),
+        )
+        .with_timing("reprice_estimate")
+        .with_logging("reprice_estimate");
+
+        // Register the use cases as handlers on the buses
+        // Both buses admit requests through one gate so shutdown can close them together
+        let gate = Arc::new(Gate::new());
+        let mut command_bus = CommandBus::new().with_gate(Arc::clone(&gate));
+        let mut query_bus = QueryBus::new().with_gate(Arc::clone(&gate));
+        let registered = command_bus
+            .register(create_estimate)
+            .and_then(|bus| bus.register(set_estimate_location))
+            .and_then(|bus| bus.register(update_estimate))
+            .and_then(|bus| bus.register(delete_estimate))
+            .and_then(|bus| bus.register(duplicate_estimate))
+            .and_then(|bus| bus.register(import_estimate_from_csv))
+            .and_then(|bus| bus.register(create_section_add_to_estimate))
+            .and_then(|bus| bus.register(update_section))
+            .and_then(|bus| bus.register(delete_section))
+            .and_then(|bus| bus.register(move_section))
+            .and_then(|bus| bus.register(reorder_sections))
+            .and_then(|bus| bus.register(add_price_book_item))
+            .and_then(|bus| bus.register(insert_price_book_item))
+            .and_then(|bus| bus.register(reprice_estimate))
+            .and_then(|_| query_bus.register(get_estimate))
+            .and_then(|bus| bus.register(list_estimates))
+            .and_then(|bus| bus.register(get_estimate_report))
+            .and_then(|bus| bus.register(get_section))
+            .and_then(|bus| bus.register(list_sections_for_estimate))
+            .and_then(|bus| bus.register(search_price_book));
+        if let Err(e) = registered {
+            return Err(Box::new(Error::MainError {
+                message: format!("Error registering handlers: {}", e),
+            }));
+        }
+        let command_bus = Arc::new(command_bus);
+        let query_bus = Arc::new(query_bus);
+
+        // Initialize the controllers with the buses; a missing handler fails here
+        let section_controller =
+            SectionController::new(Arc::clone(&command_bus), Arc::clone(&query_bus))
+                .map_err(|e| {
+                    Box::new(Error::MainError {
+                        message: format!("Error initializing section controller: {}", e),
+                    })
+                })?
+                .with_output_format(self.output_format);
+        let estimate_controller =
+            EstimateController::new(Arc::clone(&command_bus), Arc::clone(&query_bus))
+                .map_err(|e| {
+                    Box::new(Error::MainError {
+                        message: format!("Error initializing estimate controller: {}", e),
+                    })
+                })?
+                .with_output_format(self.output_format)
+                .with_clock(Arc::clone(&self.clock))
+                .with_unit_system(self.config.unit_system)
+                .with_currency(self.config.currency().unwrap_or_default());
+
+        Ok(App {
+            config: self.config,
+            command_bus,
+            query_bus,
+            estimate_controller,
+            section_controller,
+            gate,
+            shutdown_deadline: OnceLock::new(),
+            clock: Arc::clone(&self.clock),
+            estimate_service: estimate_actor,
+            section_service: section_actor,
+            price_book_service: price_book_actor,
+        })
+    }
+}
+
+// Stored data has to match the entities before the repositories load it. Pending migrations
+// are applied when `storage.auto_migrate` is set and only reported otherwise.
+fn migrate(storage: &StorageConfig, clock: Arc<dyn Clock>) -> Result<()> {
+    let migration_error = |e: Box<dyn std::error::Error + Send + Sync>| {
+        Box::new(Error::MainError {
+            message: format!("Error migrating stored data: {}", e),
+        })
+    };
+    let Some(migrator) = migration::for_storage(storage).map_err(migration_error)? else {
+        return Ok(());
+    };
+    let auto_migrate = storage.auto_migrate;
+    let report = migrator
+        .with_clock(clock)
+        .run(Target::Latest, !auto_migrate)
+        .map_err(migration_error)?;
+    if report.steps.is_empty() {
+        return Ok(());
+    }
+    let (level, message) = if auto_migrate {
+        (Level::Info, "Applied migrations")
+    } else {
+        (
+            Level::Warn,
+            "Migrations pending; run `migrate` or set storage.auto_migrate",
+        )
+    };
+    telemetry::event!(level, from = report.from, to = report.to, "{}", message);
+    Ok(())
+}
+
+// The file backend keeps `<data_path>/<collection>.json`.
+fn repository<T>(storage: &StorageConfig, collection: &str) -> Result<SharedRepository<T>>
+where
+    T: Identifiable
+        + Clone
+        + Send
+        + Sync
+        + serde::Serialize
+        + serde::de::DeserializeOwned
+        + 'static,
+{
+    match storage.backend {
+        Backend::Memory => Ok(Arc::new(Mutex::new(InMemoryRepository::<T>::new()))),
+        #[cfg(feature = "file-backend")]
+        Backend::File => {
+            let path = storage.data_path.join(format!("{}.json", collection));
+            let repository = FileRepository::<T>::open(path).map_err(|e| {
+                Box::new(Error::MainError {
+                    message: format!("Error opening {} repository: {}", collection, e),
+                })
+            })?;
+            Ok(Arc::new(Mutex::new(repository)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::estimate_controller::{
+        CreateEstimateRequest, GetEstimateRequest, RenderEstimateRequest, TemplateSource,
+    };
+    use crate::presenter::template_engine::TemplateFormat;
+
+    #[cfg(feature = "file-backend")]
+    #[tokio::test]
+    async fn test_file_backed_app_keeps_estimates() {
+        let data_path = std::env::temp_dir().join(format!("app-{}", uuid::Uuid::new_v4()));
+        let mut config = AppConfig::default();
+        config.storage.backend = Backend::File;
+        config.storage.data_path = data_path.clone();
+
+        let app = AppBuilder::new(config.clone()).build().await.unwrap();
+        let id = app
+            .estimate_controller
+            .create_estimate(CreateEstimateRequest::new(
+                "Library".to_string(),
+                "Renovation of the reading room".to_string(),
+                "Springfield".to_string(),
+            ))
+            .await
+            .unwrap()
+            .estimate_id
+            .unwrap();
+        assert!(data_path.join("estimates.json").exists());
+
+        let reopened = AppBuilder::new(config).build().await.unwrap();
+        let response = reopened
+            .estimate_controller
+            .get_estimate(GetEstimateRequest::new(id))
+            .await
+            .unwrap();
+        assert_eq!(response.estimate.unwrap().name, "Library");
+
+        std::fs::remove_dir_all(data_path).unwrap();
+    }
+
+    fn request(name: &str) -> CreateEstimateRequest {
+        CreateEstimateRequest::new(
+            name.to_string(),
+            "Renovation of the reading room".to_string(),
+            "Springfield".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_refuses_requests_and_writes_snapshot() {
+        let data_path = std::env::temp_dir().join(format!("app-{}", uuid::Uuid::new_v4()));
+        let mut config = AppConfig::default();
+        config.shutdown.snapshot_path = Some(data_path.join("snapshot.json"));
+
+        let app = AppBuilder::new(config).build().await.unwrap();
+        let controller = &app.estimate_controller;
+        controller
+            .create_estimate(request("Library"))
+            .await
+            .unwrap();
+        app.gate().close();
+        let refused = controller.create_estimate(request("Museum")).await;
+        assert!(refused.unwrap_err().to_string().contains("shutting down"));
+
+        let report = app.shutdown().await.unwrap();
+        assert!(report.drained);
+        let snapshot: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(report.snapshot.unwrap()).unwrap()).unwrap();
+        assert_eq!(snapshot["estimates"].as_array().unwrap().len(), 1);
+        assert_eq!(snapshot["estimates"][0]["name"], "Library");
+
+        std::fs::remove_dir_all(data_path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_keeps_the_deadline_begun_earlier() {
+        let mut config = AppConfig::default();
+        config.shutdown.deadline_ms = 300;
+        let app = AppBuilder::new(config).build().await.unwrap();
+        let _running = app.gate().enter().await.unwrap();
+
+        let deadline = app.begin_shutdown();
+        tokio::time::sleep_until(deadline).await;
+        let started = Instant::now();
+        let report = app.shutdown().await.unwrap();
+        assert!(!report.drained);
+        assert_eq!(report.abandoned, 1);
+        assert!(started.elapsed() < std::time::Duration::from_millis(150));
+    }
+
+    // Nothing is shared between apps: each keeps the limits and currency of its own config.
+    #[tokio::test]
+    async fn test_apps_keep_their_own_limits_and_currency() {
+        let mut config = AppConfig::default();
+        config.validation.name_min_length = 10;
+        config.default_currency = "EUR".to_string();
+        let strict = AppBuilder::new(config).build().await.unwrap();
+        let lenient = AppBuilder::new(AppConfig::default()).build().await.unwrap();
+
+        assert!(strict
+            .estimate_controller
+            .create_estimate(request("Library"))
+            .await
+            .is_err());
+        let id = lenient
+            .estimate_controller
+            .create_estimate(request("Library"))
+            .await
+            .unwrap()
+            .estimate_id
+            .unwrap();
+        let id_strict = strict
+            .estimate_controller
+            .create_estimate(request("Public Library"))
+            .await
+            .unwrap()
+            .estimate_id
+            .unwrap();
+
+        let total = |id| {
+            RenderEstimateRequest::new(
+                id,
+                TemplateSource::Inline {
+                    source: "{{ total_display }}".to_string(),
+                    format: TemplateFormat::Markdown,
+                },
+            )
+        };
+        let dollars = lenient.estimate_controller.render_estimate(total(id));
+        assert_eq!(dollars.await.unwrap().content.unwrap(), "$0.00");
+        let euros = strict.estimate_controller.render_estimate(total(id_strict));
+        assert_eq!(euros.await.unwrap().content.unwrap(), "€0.00");
+    }
+
+    #[cfg(feature = "file-backend")]
+    #[tokio::test]
+    async fn test_failed_flush_fails_shutdown() {
+        let data_path = std::env::temp_dir().join(format!("app-{}", uuid::Uuid::new_v4()));
+        let mut config = AppConfig::default();
+        config.storage.backend = Backend::File;
+        config.storage.data_path = data_path.clone();
+
+        let app = AppBuilder::new(config).build().await.unwrap();
+        // Nothing can be written once the data directory is gone.
+        std::fs::remove_dir_all(&data_path).unwrap();
+        let error = app.shutdown().await.unwrap_err();
+        assert!(error.to_string().contains("Error flushing estimates"));
+    }
+}
@@ -0,0 +1,86 @@
+//backup/error.rs
+use crate::error::Error as MainError;
+
+use derive_more::{Display, From};
+
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+#[serde_as]
+#[derive(Debug, Serialize, Display)]
+pub enum Error {
+    #[display("Cannot access backup {}: {}", path, message)]
+    IoError { path: String, message: String },
+    #[display("Backup {} is not a readable archive: {}", path, message)]
+    ArchiveError { path: String, message: String },
+    #[display("Backup {} is missing {}", path, entry)]
+    MissingEntry { path: String, entry: String },
+    #[display(
+        "Backup format {} version {} is not supported (expected {} version {} or older)",
+        format,
+        version,
+        expected_format,
+        supported
+    )]
+    UnsupportedVersion {
+        format: String,
+        version: u32,
+        expected_format: &'static str,
+        supported: u32,
+    },
+    #[display(
+        "Checksum mismatch in {}: expected {:08x}, found {:08x}",
+        collection,
+        expected,
+        actual
+    )]
+    ChecksumMismatch {
+        collection: String,
+        expected: u32,
+        actual: u32,
+    },
+    #[display(
+        "{} holds {} items but the manifest lists {}",
+        collection,
+        actual,
+        expected
+    )]
+    CountMismatch {
+        collection: String,
+        expected: usize,
+        actual: usize,
+    },
+    #[display("Cannot decode {}: {}", collection, message)]
+    DecodeError { collection: String, message: String },
+    #[display(
+        "Backup contains {}, which this application does not store",
+        collection
+    )]
+    UnknownCollection { collection: String },
+    #[display("Cannot restore into {}: it already holds {} items", collection, count)]
+    NotEmpty { collection: String, count: usize },
+    // Everything restored before the failure was put back as it was.
+    #[display("Restoring {} failed, nothing was changed: {}", collection, message)]
+    RestoreFailed { collection: String, message: String },
+    #[display(
+        "Restoring {} failed ({}) and putting back {} failed too, the data is partly restored: {}",
+        collection,
+        message,
+        rollback_collection,
+        rollback_message
+    )]
+    RollbackFailed {
+        collection: String,
+        message: String,
+        rollback_collection: String,
+        rollback_message: String,
+    },
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for MainError {
+    fn from(error: Error) -> Self {
+        MainError::BackupError(error)
+    }
+}
@@ -0,0 +1,620 @@
+// backup/mod.rs
+
+// Point-in-time backups of everything the application stores, as one zip archive:
+//
+//   manifest.json      format, version, when it was taken and, per collection, its file, item
+//                      count and CRC-32
+//   estimates.json     one JSON array per collection, items in id order
+//   sections.json
+//
+// `backup` pauses the buses so no request changes data while the collections are read.
+// `restore` checks the whole archive (version, checksums, counts, decoding) before writing
+// anything, then loads it through the services, so it works with whichever backend is
+// configured. It keeps a copy of what the collections held; if a write fails part way, the
+// collections already written are put back from that copy. Only if that fails too is the target
+// left partly restored.
+//
+// The collections come from `App::collections`; a new entity type is backed up once it is listed
+// there.
+
+pub mod error;
+
+use crate::result::*;
+
+use self::error::Error as BackupError;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::{Cursor, Read, Write};
+use std::path::Path;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::app::App;
+use crate::entity::traits::Identifiable;
+use crate::service::actor::ServiceHandle;
+use crate::telemetry::{self, Level};
+
+// Written into every manifest; an archive with another format is refused.
+pub const FORMAT: &str = "rust_architecture_4-backup";
+
+// The newest archive version this build writes and reads.
+pub const VERSION: u32 = 1;
+
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub format: String,
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub collections: Vec<ManifestEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub name: String,
+    pub file: String,
+    pub count: usize,
+    pub crc32: u32,
+}
+
+// How `restore` treats collections that already hold items.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RestoreMode {
+    // Refuse unless every collection is empty.
+    #[default]
+    IntoEmpty,
+    // Delete what is there first.
+    Replace,
+}
+
+// What a backup holds, or what a restore wrote.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BackupReport {
+    pub created_at: DateTime<Utc>,
+    // Items per collection.
+    pub counts: BTreeMap<String, usize>,
+}
+
+impl fmt::Display for BackupReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Backup of {}", self.created_at.to_rfc3339())?;
+        for (collection, count) in &self.counts {
+            write!(f, "\n  {:<12} {}", collection, count)?;
+        }
+        Ok(())
+    }
+}
+
+// One kind of entity as a backup sees it.
+#[async_trait]
+pub trait Collection: Send + Sync {
+    fn name(&self) -> &'static str;
+    async fn count(&self) -> Result<usize>;
+    // The items as a JSON array, and how many there are.
+    async fn export(&self) -> Result<(usize, Vec<u8>)>;
+    // Decodes `data` without storing it; returns the item count.
+    fn check(&self, data: &[u8]) -> Result<usize>;
+    // Deletes the stored items and adds the ones in `data`; returns how many were added.
+    async fn replace(&self, data: &[u8]) -> Result<usize>;
+}
+
+// A collection kept by a service actor.
+pub struct Entities<T> {
+    name: &'static str,
+    service: ServiceHandle<T>,
+}
+
+impl<T> Entities<T> {
+    pub fn new(name: &'static str, service: ServiceHandle<T>) -> Self {
+        Entities { name, service }
+    }
+
+    fn decode(&self, data: &[u8]) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+    {
+        serde_json::from_slice(data).map_err(|error| {
+            BackupError::DecodeError {
+                collection: self.name.to_string(),
+                message: error.to_string(),
+            }
+            .into()
+        })
+    }
+}
+
+#[async_trait]
+impl<T> Collection for Entities<T>
+where
+    T: Identifiable + Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn count(&self) -> Result<usize> {
+        Ok(self.service.list().await?.len())
+    }
+
+    async fn export(&self) -> Result<(usize, Vec<u8>)> {
+        let mut items = self.service.list().await?;
+        items.sort_by_key(|item| item.id());
+        Ok((items.len(), serde_json::to_vec_pretty(&items)?))
+    }
+
+    fn check(&self, data: &[u8]) -> Result<usize> {
+        Ok(self.decode(data)?.len())
+    }
+
+    async fn replace(&self, data: &[u8]) -> Result<usize> {
+        let items = self.decode(data)?;
+        for existing in self.service.list().await? {
+            self.service.delete(existing.id()).await?;
+        }
+        for item in &items {
+            self.service.add(item.clone()).await?;
+        }
+        self.service.flush().await?;
+        Ok(items.len())
+    }
+}
+
+// Writes every collection of `app` to `path`, replacing the file atomically.
+pub async fn backup(app: &App, path: &Path) -> Result<BackupReport> {
+    let collections = app.collections();
+    let created_at = app.clock().now();
+    let exported = {
+        let _paused = app.gate().pause().await;
+        let mut exported = Vec::with_capacity(collections.len());
+        for collection in &collections {
+            let (count, data) = collection.export().await?;
+            exported.push((collection.name(), count, data));
+        }
+        exported
+    };
+
+    let archive = write_archive(created_at, &exported).map_err(|e| archive_error(path, e))?;
+    let mut temporary = path.to_path_buf().into_os_string();
+    temporary.push(".tmp");
+    let io_error = |error: std::io::Error| io_error(path, error);
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        tokio::fs::create_dir_all(parent).await.map_err(io_error)?;
+    }
+    tokio::fs::write(&temporary, archive)
+        .await
+        .map_err(io_error)?;
+    tokio::fs::rename(&temporary, path)
+        .await
+        .map_err(io_error)?;
+
+    let report = BackupReport {
+        created_at,
+        counts: exported
+            .iter()
+            .map(|(name, count, _)| (name.to_string(), *count))
+            .collect(),
+    };
+    log("Backup written", path, &report);
+    Ok(report)
+}
+
+// Reads and checks `path` without restoring anything.
+pub async fn verify(app: &App, path: &Path) -> Result<BackupReport> {
+    let (manifest, files) = read(path).await?;
+    check(&app.collections(), &manifest, &files)
+}
+
+pub async fn restore(app: &App, path: &Path, mode: RestoreMode) -> Result<BackupReport> {
+    let (manifest, files) = read(path).await?;
+    let collections = app.collections();
+    check(&collections, &manifest, &files)?;
+
+    let _paused = app.gate().pause().await;
+    if mode == RestoreMode::IntoEmpty {
+        for collection in &collections {
+            let count = collection.count().await?;
+            if count > 0 {
+                return Err(Box::new(BackupError::NotEmpty {
+                    collection: collection.name().to_string(),
+                    count,
+                }));
+            }
+        }
+    }
+    let report = BackupReport {
+        created_at: manifest.created_at,
+        counts: load(&collections, &files).await?,
+    };
+    log("Backup restored", path, &report);
+    Ok(report)
+}
+
+// Replaces every collection with its data in `files`, or leaves them all as they were.
+async fn load(
+    collections: &[Box<dyn Collection>],
+    files: &BTreeMap<String, Vec<u8>>,
+) -> Result<BTreeMap<String, usize>> {
+    let mut previous = Vec::with_capacity(collections.len());
+    for collection in collections {
+        previous.push(collection.export().await?.1);
+    }
+
+    let mut counts = BTreeMap::new();
+    for (index, collection) in collections.iter().enumerate() {
+        // A collection the archive predates is emptied, so the target matches the backup.
+        let data = files
+            .get(collection.name())
+            .map(Vec::as_slice)
+            .unwrap_or(b"[]");
+        match collection.replace(data).await {
+            Ok(count) => {
+                counts.insert(collection.name().to_string(), count);
+            }
+            Err(error) => {
+                return Err(roll_back(&collections[..=index], &previous, error)
+                    .await
+                    .into())
+            }
+        }
+    }
+    Ok(counts)
+}
+
+// Puts back what `collections` held before the restore; the last of them is the one whose
+// `error` stopped it.
+async fn roll_back(
+    collections: &[Box<dyn Collection>],
+    previous: &[Vec<u8>],
+    error: Box<dyn std::error::Error + Send + Sync>,
+) -> BackupError {
+    let collection = collections
+        .last()
+        .map(|collection| collection.name().to_string())
+        .unwrap_or_default();
+    for (restored, data) in collections.iter().zip(previous) {
+        if let Err(rollback) = restored.replace(data).await {
+            return BackupError::RollbackFailed {
+                collection,
+                message: error.to_string(),
+                rollback_collection: restored.name().to_string(),
+                rollback_message: rollback.to_string(),
+            };
+        }
+    }
+    tracing::warn!(
+        collection = %collection,
+        "Restore failed; the previous contents were put back"
+    );
+    BackupError::RestoreFailed {
+        collection,
+        message: error.to_string(),
+    }
+}
+
+fn write_archive(
+    created_at: DateTime<Utc>,
+    collections: &[(&'static str, usize, Vec<u8>)],
+) -> std::result::Result<Vec<u8>, zip::result::ZipError> {
+    let manifest = Manifest {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        created_at,
+        collections: collections
+            .iter()
+            .map(|(name, count, data)| ManifestEntry {
+                name: name.to_string(),
+                file: format!("{}.json", name),
+                count: *count,
+                crc32: crc32fast::hash(data),
+            })
+            .collect(),
+    };
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    writer.start_file(MANIFEST, options)?;
+    writer.write_all(&serde_json::to_vec_pretty(&manifest).map_err(std::io::Error::from)?)?;
+    for (entry, (_, _, data)) in manifest.collections.iter().zip(collections) {
+        writer.start_file(entry.file.as_str(), options)?;
+        writer.write_all(data)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+// The manifest and each collection's data, keyed by collection name.
+async fn read(path: &Path) -> Result<(Manifest, BTreeMap<String, Vec<u8>>)> {
+    let bytes = tokio::fs::read(path).await.map_err(|e| io_error(path, e))?;
+    let mut archive =
+        zip::ZipArchive::new(Cursor::new(bytes)).map_err(|e| archive_error(path, e))?;
+    let manifest: Manifest = serde_json::from_slice(&entry(&mut archive, path, MANIFEST)?)
+        .map_err(|e| BackupError::DecodeError {
+            collection: MANIFEST.to_string(),
+            message: e.to_string(),
+        })?;
+    if manifest.format != FORMAT || manifest.version > VERSION {
+        return Err(Box::new(BackupError::UnsupportedVersion {
+            format: manifest.format,
+            version: manifest.version,
+            expected_format: FORMAT,
+            supported: VERSION,
+        }));
+    }
+    let mut files = BTreeMap::new();
+    for collection in &manifest.collections {
+        let data = entry(&mut archive, path, &collection.file)?;
+        let actual = crc32fast::hash(&data);
+        if actual != collection.crc32 {
+            return Err(Box::new(BackupError::ChecksumMismatch {
+                collection: collection.name.clone(),
+                expected: collection.crc32,
+                actual,
+            }));
+        }
+        files.insert(collection.name.clone(), data);
+    }
+    Ok((manifest, files))
+}
+
+fn entry(
+    archive: &mut zip::ZipArchive<Cursor<Vec<u8>>>,
+    path: &Path,
+    name: &str,
+) -> Result<Vec<u8>> {
+    let mut file = archive.by_name(name).map_err(|e| match e {
+        zip::result::ZipError::FileNotFound => BackupError::MissingEntry {
+            path: path.display().to_string(),
+            entry: name.to_string(),
+        },
+        e => archive_error(path, e),
+    })?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)
+        .map_err(|e| archive_error(path, e))?;
+    Ok(data)
+}
+
+// Every collection in the archive must be one the application stores and decode to as many
+// items as the manifest lists.
+fn check(
+    collections: &[Box<dyn Collection>],
+    manifest: &Manifest,
+    files: &BTreeMap<String, Vec<u8>>,
+) -> Result<BackupReport> {
+    let mut counts = BTreeMap::new();
+    for entry in &manifest.collections {
+        let collection = collections
+            .iter()
+            .find(|collection| collection.name() == entry.name)
+            .ok_or_else(|| BackupError::UnknownCollection {
+                collection: entry.name.clone(),
+            })?;
+        let actual = collection.check(&files[&entry.name])?;
+        if actual != entry.count {
+            return Err(Box::new(BackupError::CountMismatch {
+                collection: entry.name.clone(),
+                expected: entry.count,
+                actual,
+            }));
+        }
+        counts.insert(entry.name.clone(), actual);
+    }
+    Ok(BackupReport {
+        created_at: manifest.created_at,
+        counts,
+    })
+}
+
+fn log(message: &str, path: &Path, report: &BackupReport) {
+    tracing::info!(
+        path = %path.display(),
+        counts = %json!(report.counts),
+        "{}",
+        message
+    );
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> BackupError {
+    BackupError::IoError {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+fn archive_error(path: &Path, error: impl fmt::Display) -> BackupError {
+    BackupError::ArchiveError {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+    use crate::entity::estimate::Estimate;
+    use crate::entity::section::Section;
+    use crate::repository::conformance::Sample;
+    use crate::AppBuilder;
+
+    fn temp_path() -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("backup-{}.zip", uuid::Uuid::new_v4()))
+    }
+
+    async fn seeded_app() -> App {
+        let app = AppBuilder::new(AppConfig::default()).build().await.unwrap();
+        let estimates = app.estimate_service();
+        for n in 0..3 {
+            estimates.add(Estimate::sample(n)).await.unwrap();
+        }
+        app.section_service().add(Section::sample(0)).await.unwrap();
+        app
+    }
+
+    #[tokio::test]
+    async fn test_backup_restores_into_another_app() {
+        let path = temp_path();
+        let source = seeded_app().await;
+        let written = backup(&source, &path).await.unwrap();
+        assert_eq!(written.counts["estimates"], 3);
+        assert_eq!(written.counts["sections"], 1);
+
+        let target = AppBuilder::new(AppConfig::default()).build().await.unwrap();
+        assert_eq!(verify(&target, &path).await.unwrap(), written);
+        let restored = restore(&target, &path, RestoreMode::IntoEmpty)
+            .await
+            .unwrap();
+        assert_eq!(restored, written);
+        let ids = |estimates: Vec<Estimate>| {
+            let mut ids: Vec<_> = estimates.iter().map(|estimate| estimate.id).collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(
+            ids(target.estimate_service().list().await.unwrap()),
+            ids(source.estimate_service().list().await.unwrap())
+        );
+
+        // A second restore needs `Replace`.
+        let error = restore(&target, &path, RestoreMode::IntoEmpty)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BackupError>(),
+            Some(BackupError::NotEmpty { .. })
+        ));
+        restore(&target, &path, RestoreMode::Replace).await.unwrap();
+        assert_eq!(target.estimate_service().list().await.unwrap().len(), 3);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    // A collection whose first write fails.
+    #[derive(Default)]
+    struct FailsOnce(std::sync::atomic::AtomicBool);
+
+    #[async_trait]
+    impl Collection for FailsOnce {
+        fn name(&self) -> &'static str {
+            "sections"
+        }
+
+        async fn count(&self) -> Result<usize> {
+            Ok(0)
+        }
+
+        async fn export(&self) -> Result<(usize, Vec<u8>)> {
+            Ok((0, b"[]".to_vec()))
+        }
+
+        fn check(&self, _data: &[u8]) -> Result<usize> {
+            Ok(0)
+        }
+
+        async fn replace(&self, _data: &[u8]) -> Result<usize> {
+            if self.0.swap(true, std::sync::atomic::Ordering::SeqCst) {
+                Ok(0)
+            } else {
+                Err(std::io::Error::other("disk full").into())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_failed_restore_puts_back_what_was_there() {
+        let app = seeded_app().await;
+        let ids = |estimates: Vec<Estimate>| {
+            let mut ids: Vec<_> = estimates.iter().map(|estimate| estimate.id).collect();
+            ids.sort();
+            ids
+        };
+        let before = ids(app.estimate_service().list().await.unwrap());
+        let collections: Vec<Box<dyn Collection>> = vec![
+            Box::new(Entities::new("estimates", app.estimate_service())),
+            Box::new(FailsOnce::default()),
+        ];
+        let files = BTreeMap::from([(
+            "estimates".to_string(),
+            serde_json::to_vec(&[Estimate::sample(7)]).unwrap(),
+        )]);
+
+        let error = load(&collections, &files).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BackupError>(),
+            Some(BackupError::RestoreFailed { collection, .. }) if collection == "sections"
+        ));
+        assert_eq!(ids(app.estimate_service().list().await.unwrap()), before);
+    }
+
+    // An archive with the given manifest and files, checksums as they are.
+    fn archive_with(path: &Path, manifest: &Manifest, files: &[(&str, &[u8])]) {
+        let options = zip::write::SimpleFileOptions::default();
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        writer.start_file(MANIFEST, options).unwrap();
+        writer
+            .write_all(&serde_json::to_vec(manifest).unwrap())
+            .unwrap();
+        for (name, data) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(data).unwrap();
+        }
+        std::fs::write(path, writer.finish().unwrap().into_inner()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_tampered_archive_is_refused() {
+        let path = temp_path();
+        let app = seeded_app().await;
+        backup(&app, &path).await.unwrap();
+
+        // Empty the estimates but keep the manifest, and with it the old checksum.
+        let (manifest, files) = read(&path).await.unwrap();
+        archive_with(
+            &path,
+            &manifest,
+            &[
+                ("estimates.json", b"[]"),
+                ("sections.json", &files["sections"]),
+            ],
+        );
+
+        let error = verify(&app, &path).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BackupError>(),
+            Some(BackupError::ChecksumMismatch { collection, .. }) if collection == "estimates"
+        ));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_newer_version_is_refused() {
+        let path = temp_path();
+        let manifest = Manifest {
+            format: FORMAT.to_string(),
+            version: VERSION + 1,
+            created_at: Utc::now(),
+            collections: Vec::new(),
+        };
+        archive_with(&path, &manifest, &[]);
+
+        let app = AppBuilder::new(AppConfig::default()).build().await.unwrap();
+        let error = restore(&app, &path, RestoreMode::IntoEmpty)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<BackupError>(),
+            Some(BackupError::UnsupportedVersion { version: 2, .. })
+        ));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+// bus/command_bus.rs
+
+use crate::result::*;
+
+use super::gate::Gate;
+use super::registry::HandlerRegistry;
+use super::Command;
+
+use crate::use_case::traits::UseCase;
+
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct CommandBus {
+    registry: HandlerRegistry,
+    gate: Arc<Gate>,
+}
+
+impl CommandBus {
+    pub fn new() -> Self {
+        CommandBus::default()
+    }
+
+    // Shares admission with other buses, so closing the gate stops every front door at once.
+    pub fn with_gate(mut self, gate: Arc<Gate>) -> Self {
+        self.gate = gate;
+        self
+    }
+
+    // Fails if the command already has a handler, so wiring mistakes surface at startup.
+    pub fn register<C: Command>(
+        &mut self,
+        handler: impl UseCase<C, C::Output> + 'static,
+    ) -> Result<&mut Self> {
+        self.registry.register(handler)?;
+        Ok(self)
+    }
+
+    // Called by controllers and front-ends while they are being built, so a missing
+    // handler fails the application before the first request instead of on dispatch.
+    pub fn require<C: Command>(&self) -> Result<()> {
+        self.registry.require::<C>()
+    }
+
+    pub async fn dispatch<C: Command>(&self, command: C) -> Result<C::Output> {
+        let _pass = self.gate.enter().await?;
+        let handler = self.registry.handler::<C, C::Output>()?;
+        handler.execute(command).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+
+    struct Add(i32, i32);
+
+    impl Command for Add {
+        type Output = i32;
+    }
+
+    struct Negate(i32);
+
+    impl Command for Negate {
+        type Output = i32;
+    }
+
+    struct AddHandler;
+
+    #[async_trait]
+    impl UseCase<Add, i32> for AddHandler {
+        async fn execute(&self, input: Add) -> Result<i32> {
+            Ok(input.0 + input.1)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_routes_to_registered_handler() {
+        let mut bus = CommandBus::new();
+        bus.register::<Add>(AddHandler).unwrap();
+
+        assert!(bus.require::<Add>().is_ok());
+        assert_eq!(bus.dispatch(Add(2, 3)).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_missing_handler_is_reported() {
+        let bus = CommandBus::new();
+
+        let err = bus.require::<Negate>().unwrap_err();
+        assert!(err.to_string().starts_with("No handler registered for"));
+        assert!(bus.dispatch(Negate(1)).await.is_err());
+    }
+
+    #[test]
+    fn test_duplicate_registration_fails() {
+        let mut bus = CommandBus::new();
+        bus.register::<Add>(AddHandler).unwrap();
+
+        assert!(bus.register::<Add>(AddHandler).is_err());
+    }
+}
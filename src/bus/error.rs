@@ -0,0 +1,28 @@
+//bus/error.rs
+use crate::error::Error as MainError;
+
+use derive_more::{Display, From};
+
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+#[serde_as]
+#[derive(Debug, Serialize, Display)]
+pub enum Error {
+    #[display("No handler registered for {}", message_type)]
+    MissingHandler { message_type: &'static str },
+
+    #[display("A handler is already registered for {}", message_type)]
+    DuplicateHandler { message_type: &'static str },
+
+    #[display("Not accepting requests: shutting down")]
+    ShuttingDown,
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for MainError {
+    fn from(error: Error) -> Self {
+        MainError::BusError(error)
+    }
+}
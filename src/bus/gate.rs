@@ -0,0 +1,154 @@
+// bus/gate.rs
+
+// Admission control shared by the buses. Each dispatch holds a `Pass` while its handler runs;
+// once the gate is closed new dispatches are refused with `BusError::ShuttingDown` and `drained`
+// waits for the passes still out, which is how shutdown lets in-flight use cases finish.
+// `pause` instead holds new dispatches back until released, for work such as a backup that
+// needs the data to stand still.
+
+use crate::result::*;
+
+use super::error::Error as BusError;
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Notify, OwnedRwLockReadGuard, OwnedRwLockWriteGuard, RwLock};
+use tokio::time::Instant;
+
+#[derive(Default)]
+pub struct Gate {
+    closed: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+    // Dispatches share it; a pause holds it exclusively.
+    quiet: Arc<RwLock<()>>,
+}
+
+impl Gate {
+    pub fn new() -> Self {
+        Gate::default()
+    }
+
+    // Waits while the gate is paused.
+    pub async fn enter(self: &Arc<Self>) -> Result<Pass> {
+        let quiet = Arc::clone(&self.quiet).read_owned().await;
+        // Count first, then look: `close` followed by `drained` can never miss a request that
+        // got in.
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let pass = Pass {
+            gate: Arc::clone(self),
+            _quiet: quiet,
+        };
+        if self.closed.load(Ordering::SeqCst) {
+            return Err(Box::new(BusError::ShuttingDown));
+        }
+        Ok(pass)
+    }
+
+    // Waits for the running dispatches to finish and holds new ones back until the returned
+    // guard is dropped.
+    pub async fn pause(&self) -> Paused {
+        Paused {
+            _quiet: Arc::clone(&self.quiet).write_owned().await,
+        }
+    }
+
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    // Waits until no dispatch is running or `deadline` passes. Returns whether it drained.
+    pub async fn drained(&self, deadline: Instant) -> bool {
+        let wait = async {
+            loop {
+                let idle = self.idle.notified();
+                tokio::pin!(idle);
+                idle.as_mut().enable();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        };
+        tokio::time::timeout_at(deadline, wait).await.is_ok()
+    }
+}
+
+// Held for the length of one dispatch.
+pub struct Pass {
+    gate: Arc<Gate>,
+    _quiet: OwnedRwLockReadGuard<()>,
+}
+
+// Held while the gate is paused.
+pub struct Paused {
+    _quiet: OwnedRwLockWriteGuard<()>,
+}
+
+impl Drop for Pass {
+    fn drop(&mut self) {
+        if self.gate.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.gate.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_closed_gate_refuses_and_drains() {
+        let gate = Arc::new(Gate::new());
+        let running = gate.enter().await.unwrap();
+        gate.close();
+
+        let error = gate.enter().await.err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<BusError>(),
+            Some(BusError::ShuttingDown)
+        ));
+        assert_eq!(gate.in_flight(), 1);
+        assert!(
+            !gate
+                .drained(Instant::now() + Duration::from_millis(10))
+                .await
+        );
+
+        let finish = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(running);
+        });
+        assert!(gate.drained(Instant::now() + Duration::from_secs(5)).await);
+        finish.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_pause_waits_for_running_and_holds_new() {
+        let gate = Arc::new(Gate::new());
+        let running = gate.enter().await.unwrap();
+        let pausing = tokio::spawn({
+            let gate = Arc::clone(&gate);
+            async move { gate.pause().await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!pausing.is_finished());
+
+        drop(running);
+        let paused = pausing.await.unwrap();
+        let held = tokio::time::timeout(Duration::from_millis(10), gate.enter()).await;
+        assert!(held.is_err());
+
+        drop(paused);
+        assert!(gate.enter().await.is_ok());
+    }
+}
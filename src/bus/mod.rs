@@ -0,0 +1,17 @@
+// bus/mod.rs
+
+pub mod command_bus;
+pub mod error;
+pub mod gate;
+pub mod query_bus;
+mod registry;
+
+// A request to change state, handled by exactly one use case registered on the `CommandBus`.
+pub trait Command: Send + 'static {
+    type Output: Send + 'static;
+}
+
+// A request to read state, handled by exactly one use case registered on the `QueryBus`.
+pub trait Query: Send + 'static {
+    type Output: Send + 'static;
+}
@@ -0,0 +1,102 @@
+// bus/query_bus.rs
+
+use crate::result::*;
+
+use super::gate::Gate;
+use super::registry::HandlerRegistry;
+use super::Query;
+
+use crate::use_case::traits::UseCase;
+
+use std::sync::Arc;
+
+#[derive(Default)]
+pub struct QueryBus {
+    registry: HandlerRegistry,
+    gate: Arc<Gate>,
+}
+
+impl QueryBus {
+    pub fn new() -> Self {
+        QueryBus::default()
+    }
+
+    pub fn with_gate(mut self, gate: Arc<Gate>) -> Self {
+        self.gate = gate;
+        self
+    }
+
+    // Fails if the query already has a handler, so wiring mistakes surface at startup.
+    pub fn register<Q: Query>(
+        &mut self,
+        handler: impl UseCase<Q, Q::Output> + 'static,
+    ) -> Result<&mut Self> {
+        self.registry.register(handler)?;
+        Ok(self)
+    }
+
+    pub fn require<Q: Query>(&self) -> Result<()> {
+        self.registry.require::<Q>()
+    }
+
+    pub async fn dispatch<Q: Query>(&self, query: Q) -> Result<Q::Output> {
+        let _pass = self.gate.enter().await?;
+        let handler = self.registry.handler::<Q, Q::Output>()?;
+        handler.execute(query).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+
+    struct Square(i32);
+
+    impl Query for Square {
+        type Output = i32;
+    }
+
+    struct Describe(i32);
+
+    impl Query for Describe {
+        type Output = String;
+    }
+
+    struct SquareHandler;
+
+    #[async_trait]
+    impl UseCase<Square, i32> for SquareHandler {
+        async fn execute(&self, input: Square) -> Result<i32> {
+            Ok(input.0 * input.0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_routes_to_registered_handler() {
+        let mut bus = QueryBus::new();
+        bus.register::<Square>(SquareHandler).unwrap();
+
+        assert!(bus.require::<Square>().is_ok());
+        assert_eq!(bus.dispatch(Square(4)).await.unwrap(), 16);
+    }
+
+    #[tokio::test]
+    async fn test_missing_handler_is_reported() {
+        let bus = QueryBus::new();
+
+        let err = bus.require::<Describe>().unwrap_err();
+        assert!(err.to_string().starts_with("No handler registered for"));
+        let err = bus.dispatch(Describe(1)).await.unwrap_err();
+        assert!(err.to_string().starts_with("No handler registered for"));
+    }
+
+    #[test]
+    fn test_duplicate_registration_fails() {
+        let mut bus = QueryBus::new();
+        bus.register::<Square>(SquareHandler).unwrap();
+
+        assert!(bus.register::<Square>(SquareHandler).is_err());
+    }
+}
@@ -0,0 +1,64 @@
+// bus/registry.rs
+
+use crate::result::*;
+
+use super::error::Error as BusError;
+
+use std::any::{type_name, Any, TypeId};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::use_case::traits::UseCase;
+
+// Handlers keyed by the type of message they accept. Each entry holds an
+// `Arc<dyn UseCase<M, O>>` erased to `Any` so handlers for different messages can share one map.
+#[derive(Default)]
+pub(crate) struct HandlerRegistry {
+    handlers: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl HandlerRegistry {
+    pub fn register<M, O>(&mut self, handler: impl UseCase<M, O> + 'static) -> Result<()>
+    where
+        M: Send + 'static,
+        O: Send + 'static,
+    {
+        let handler: Arc<dyn UseCase<M, O>> = Arc::new(handler);
+        match self.handlers.entry(TypeId::of::<M>()) {
+            Entry::Occupied(_) => Err(Box::new(BusError::DuplicateHandler {
+                message_type: type_name::<M>(),
+            })),
+            Entry::Vacant(entry) => {
+                entry.insert(Box::new(handler));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn handler<M, O>(&self) -> Result<Arc<dyn UseCase<M, O>>>
+    where
+        M: Send + 'static,
+        O: Send + 'static,
+    {
+        self.handlers
+            .get(&TypeId::of::<M>())
+            .and_then(|handler| handler.downcast_ref::<Arc<dyn UseCase<M, O>>>())
+            .cloned()
+            .ok_or_else(|| Self::missing::<M>())
+    }
+
+    pub fn require<M: 'static>(&self) -> Result<()> {
+        if self.handlers.contains_key(&TypeId::of::<M>()) {
+            Ok(())
+        } else {
+            Err(Self::missing::<M>())
+        }
+    }
+
+    fn missing<M>() -> Box<dyn std::error::Error + Send + Sync> {
+        Box::new(BusError::MissingHandler {
+            message_type: type_name::<M>(),
+        })
+    }
+}
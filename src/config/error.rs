@@ -0,0 +1,27 @@
+//config/error.rs
+use crate::error::Error as MainError;
+
+use derive_more::{Display, From};
+
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+#[serde_as]
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Serialize, From, Display)]
+pub enum Error {
+    #[display("Cannot read config file {}: {}", path, message)]
+    ReadError { path: String, message: String },
+    #[display("Config syntax error on line {}: {}", line, message)]
+    Syntax { line: usize, message: String },
+    #[display("Invalid configuration: {}", problems.join("; "))]
+    Invalid { problems: Vec<String> },
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for MainError {
+    fn from(error: Error) -> Self {
+        MainError::ConfigError(error)
+    }
+}
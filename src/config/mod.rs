@@ -0,0 +1,436 @@
+// config/mod.rs
+
+// Application settings. They are resolved in layers: built-in defaults, then the TOML file, then
+// `APP__<TABLE>__<KEY>` environment variables, and the result is validated before anything is
+// built from it so a bad setting stops the process at startup with every problem listed.
+
+pub mod error;
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+use self::error::Error as ConfigError;
+use crate::entity::limits::ValidationLimits;
+use crate::entity::unit::UnitSystem;
+use crate::presenter::currency::{Currency, CURRENCIES};
+use crate::service::policy::Policy;
+use crate::telemetry::{LogOutput, TelemetryConfig};
+
+// Read from the working directory when no path is given and the file exists.
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+// `APP__STORAGE__BACKEND=file` overrides `backend` in `[storage]`.
+pub const ENV_PREFIX: &str = "APP__";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    // Nothing outlives the process.
+    #[default]
+    #[display("memory")]
+    Memory,
+    // One JSON file per entity type under `data_path`.
+    #[cfg(feature = "file-backend")]
+    #[display("file")]
+    File,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    pub backend: Backend,
+    pub data_path: PathBuf,
+    // Bring the stored data up to the latest migration when the app is built.
+    pub auto_migrate: bool,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        StorageConfig {
+            backend: Backend::Memory,
+            data_path: PathBuf::from("data"),
+            auto_migrate: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LoggingConfig {
+    // pretty or json
+    pub format: String,
+    // debug, info, warn or error
+    pub level: String,
+    // Diagnostics go to stderr unless a file is given.
+    pub file: Option<PathBuf>,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        LoggingConfig {
+            format: "pretty".to_string(),
+            level: "info".to_string(),
+            file: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    pub bind_address: SocketAddr,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            bind_address: SocketAddr::from(([127, 0, 0, 1], 8080)),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ShutdownConfig {
+    // How long requests already running may take to finish once shutdown starts.
+    pub deadline_ms: u64,
+    // Where to write every estimate and section as one JSON document after the final flush.
+    pub snapshot_path: Option<PathBuf>,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        ShutdownConfig {
+            deadline_ms: 10_000,
+            snapshot_path: None,
+        }
+    }
+}
+
+impl ShutdownConfig {
+    pub fn deadline(&self) -> Duration {
+        Duration::from_millis(self.deadline_ms)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AppConfig {
+    // ISO 4217 code amounts are shown in.
+    pub default_currency: String,
+    // imperial or metric; None shows quantities in the units they were entered in.
+    pub unit_system: Option<UnitSystem>,
+    pub storage: StorageConfig,
+    pub validation: ValidationLimits,
+    pub logging: LoggingConfig,
+    pub server: ServerConfig,
+    // Timeout, retries and circuit breaking around repository calls.
+    pub resilience: Policy,
+    pub shutdown: ShutdownConfig,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        AppConfig {
+            default_currency: "USD".to_string(),
+            unit_system: None,
+            storage: StorageConfig::default(),
+            validation: ValidationLimits::default(),
+            logging: LoggingConfig::default(),
+            server: ServerConfig::default(),
+            resilience: Policy::default(),
+            shutdown: ShutdownConfig::default(),
+        }
+    }
+}
+
+impl AppConfig {
+    // Reads `path`, or `config.toml` if present, applies the environment and validates.
+    pub fn load(path: Option<&Path>) -> Result<Self, ConfigError> {
+        let default_path = Path::new(DEFAULT_CONFIG_FILE);
+        let path = path.or_else(|| default_path.exists().then_some(default_path));
+        let mut table = match path {
+            Some(path) => {
+                let source =
+                    std::fs::read_to_string(path).map_err(|error| ConfigError::ReadError {
+                        path: path.display().to_string(),
+                        message: error.to_string(),
+                    })?;
+                parse(&source)?
+            }
+            None => Table::new(),
+        };
+        apply_env(&mut table, std::env::vars())?;
+        let config = AppConfig::from_table(table)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // Parses and validates a document without looking at the environment.
+    pub fn from_toml(source: &str) -> Result<Self, ConfigError> {
+        let config = AppConfig::from_table(parse(source)?)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    // A problem names the setting it is in, e.g. `storage.backend: unknown variant ...`.
+    fn from_table(table: Table) -> Result<Self, ConfigError> {
+        serde_path_to_error::deserialize(Value::Table(table)).map_err(|error| {
+            ConfigError::Invalid {
+                problems: vec![format!(
+                    "{}: {}",
+                    error.path(),
+                    error.inner().message().trim()
+                )],
+            }
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut problems = Vec::new();
+
+        if self.currency().is_none() {
+            let known: Vec<&str> = CURRENCIES.iter().map(|currency| currency.code).collect();
+            problems.push(format!(
+                "default_currency: unknown currency `{}` (expected one of {})",
+                self.default_currency,
+                known.join(", ")
+            ));
+        }
+        #[cfg(feature = "file-backend")]
+        if self.storage.backend == Backend::File && self.storage.data_path.as_os_str().is_empty() {
+            problems.push("storage.data_path: must be set for the file backend".into());
+        }
+        problems.extend(
+            self.validation
+                .problems()
+                .into_iter()
+                .map(|problem| format!("validation.{}", problem)),
+        );
+        problems.extend(
+            self.resilience
+                .problems()
+                .into_iter()
+                .map(|problem| format!("resilience.{}", problem)),
+        );
+        if self
+            .shutdown
+            .snapshot_path
+            .as_ref()
+            .is_some_and(|path| path.as_os_str().is_empty())
+        {
+            problems.push("shutdown.snapshot_path: must not be empty".into());
+        }
+        if let Err(message) = self.logging.format.parse::<crate::telemetry::LogFormat>() {
+            problems.push(format!("logging.format: {}", message));
+        }
+        if let Err(message) = self.logging.level.parse::<crate::telemetry::Level>() {
+            problems.push(format!("logging.level: {}", message));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid { problems })
+        }
+    }
+
+    pub fn currency(&self) -> Option<Currency> {
+        Currency::from_code(&self.default_currency)
+    }
+
+    pub fn telemetry(&self) -> Result<TelemetryConfig, ConfigError> {
+        let invalid = |problem: String| ConfigError::Invalid {
+            problems: vec![problem],
+        };
+        Ok(TelemetryConfig {
+            format: self
+                .logging
+                .format
+                .parse()
+                .map_err(|message| invalid(format!("logging.format: {}", message)))?,
+            output: match &self.logging.file {
+                Some(path) => LogOutput::File(path.clone()),
+                None => LogOutput::default(),
+            },
+            level: self
+                .logging
+                .level
+                .parse()
+                .map_err(|message| invalid(format!("logging.level: {}", message)))?,
+        })
+    }
+}
+
+fn parse(source: &str) -> Result<Table, ConfigError> {
+    source.parse().map_err(|error: toml::de::Error| {
+        let offset = error.span().map_or(0, |span| span.start);
+        ConfigError::Syntax {
+            line: source[..offset].matches('\n').count() + 1,
+            message: error.message().trim().to_string(),
+        }
+    })
+}
+
+// Variables are matched case-insensitively; values are read as TOML and fall back to plain
+// strings, so both `APP__SERVER__BIND_ADDRESS=0.0.0.0:80` and `...="0.0.0.0:80"` work.
+pub fn apply_env(
+    table: &mut Table,
+    vars: impl IntoIterator<Item = (String, String)>,
+) -> Result<(), ConfigError> {
+    let mut problems = Vec::new();
+    for (name, raw) in vars {
+        let Some(key) = name
+            .get(..ENV_PREFIX.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(ENV_PREFIX))
+            .map(|_| &name[ENV_PREFIX.len()..])
+        else {
+            continue;
+        };
+        let path: Vec<String> = key.split("__").map(str::to_ascii_lowercase).collect();
+        if path.iter().any(String::is_empty) {
+            problems.push(format!("{}: malformed variable name", name));
+            continue;
+        }
+        let parsed = format!("value = {}", raw)
+            .parse::<Table>()
+            .ok()
+            .and_then(|mut parsed| parsed.remove("value"))
+            .unwrap_or(Value::String(raw));
+        if let Err(message) = set_path(table, &path, parsed) {
+            problems.push(format!("{}: {}", name, message));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigError::Invalid { problems })
+    }
+}
+
+fn set_path(table: &mut Table, path: &[String], new: Value) -> Result<(), String> {
+    let (name, parents) = path.split_last().expect("split always yields a key");
+    let mut current = table;
+    for parent in parents {
+        let Value::Table(child) = current
+            .entry(parent.clone())
+            .or_insert_with(|| Value::Table(Table::new()))
+        else {
+            return Err(format!("`{}` is a value, not a table", parent));
+        };
+        current = child;
+    }
+    current.insert(name.clone(), new);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::telemetry::{Level, LogFormat};
+
+    #[cfg(feature = "file-backend")]
+    #[test]
+    fn test_file_and_environment_layers() {
+        let source = r#"
+            default_currency = "EUR"
+            unit_system = "metric"
+
+            [storage]
+            backend = "file"
+            data_path = "/var/lib/estimates"
+
+            [validation]
+            name_max_length = 60
+
+            [logging]
+            format = "json"
+        "#;
+        let mut table = parse(source).unwrap();
+        apply_env(
+            &mut table,
+            vec![
+                ("APP__LOGGING__LEVEL".to_string(), "debug".to_string()),
+                (
+                    "app__server__bind_address".to_string(),
+                    "0.0.0.0:9000".to_string(),
+                ),
+                ("HOME".to_string(), "/root".to_string()),
+            ],
+        )
+        .unwrap();
+        let config = AppConfig::from_table(table).unwrap();
+        config.validate().unwrap();
+
+        assert_eq!(config.currency().unwrap().code, "EUR");
+        assert_eq!(config.unit_system, Some(UnitSystem::Metric));
+        assert_eq!(config.storage.backend, Backend::File);
+        assert_eq!(config.validation.name_max_length, 60);
+        assert_eq!(config.validation.name_min_length, 3);
+        assert_eq!(config.server.bind_address.port(), 9000);
+        let telemetry = config.telemetry().unwrap();
+        assert_eq!(telemetry.format, LogFormat::Json);
+        assert_eq!(telemetry.level, Level::Debug);
+    }
+
+    #[test]
+    fn test_every_problem_is_reported() {
+        let error = AppConfig::from_toml(
+            r#"
+            default_currency = "XYZ"
+
+            [validation]
+            name_min_length = 50
+            name_max_length = 10
+
+            [logging]
+            level = "loud"
+            "#,
+        )
+        .unwrap_err();
+        let ConfigError::Invalid { problems } = error else {
+            panic!("expected validation problems");
+        };
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].starts_with("default_currency: unknown currency `XYZ`"));
+        assert_eq!(
+            problems[1],
+            "validation.name_min_length (50) must not exceed name_max_length (10)"
+        );
+        assert!(problems[2].starts_with("logging.level: Unknown log level: loud"));
+
+        let error = AppConfig::from_toml("[storage]\nbackend = \"sql\"\nmode = 1\n").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Invalid configuration: storage.backend: unknown variant `sql`"));
+    }
+
+    #[test]
+    fn test_full_toml_syntax_and_error_lines() {
+        let config = AppConfig::from_toml(
+            r#"
+            storage.auto_migrate = true
+            logging = { format = "json", file = "C:\\logs\\app.log" }
+            shutdown.deadline_ms = 2_500
+            "#,
+        )
+        .unwrap();
+        assert!(config.storage.auto_migrate);
+        assert_eq!(config.logging.file, Some(PathBuf::from(r"C:\logs\app.log")));
+        assert_eq!(config.shutdown.deadline_ms, 2500);
+
+        let error =
+            AppConfig::from_toml("[server]\n\nbind_address = \"unterminated\n").unwrap_err();
+        assert!(
+            matches!(error, ConfigError::Syntax { line: 3, .. }),
+            "{}",
+            error
+        );
+    }
+}
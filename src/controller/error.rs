@@ -21,10 +21,6 @@ impl std::error::Error for Error {}
 
 impl From<Error> for MainError {
     fn from(error: Error) -> Self {
-        match error {
-            Error::BasicError { message } => {
-                MainError::ControllerError(Error::BasicError { message }.into())
-            }
-        }
+        MainError::ControllerError(error)
     }
 }
@@ -2,46 +2,429 @@
 
 use crate::result::*;
 
+use super::traced;
+
+use crate::bus::command_bus::CommandBus;
+use crate::bus::query_bus::QueryBus;
+use crate::presenter::csv_export::CsvEstimateExporter;
+use crate::presenter::currency::Currency;
+use crate::presenter::error::Error as PresenterError;
 use crate::presenter::estimate_presenter::EstimatePresenter;
-use crate::use_case::create_estimate::CreateEstimate;
+use crate::presenter::formats::OutputFormat;
+use crate::presenter::proposal_presenter::{ProposalOptions, ProposalPresenter};
+use crate::presenter::template_engine::TemplateFormat;
+use crate::presenter::template_presenter::{BuiltInTemplate, TemplatePresenter};
+#[cfg(feature = "xlsx")]
+use crate::presenter::xlsx_export::XlsxEstimateExporter;
+use crate::use_case::create_estimate::CreateEstimateCommand;
+use crate::use_case::delete_estimate::DeleteEstimateCommand;
+use crate::use_case::duplicate_estimate::DuplicateEstimateCommand;
+use crate::use_case::error::Error as UseCaseError;
+use crate::use_case::get_estimate::GetEstimateQuery;
+use crate::use_case::get_estimate_report::GetEstimateReportQuery;
+use crate::use_case::import_estimate_from_csv::{
+    CsvColumnMapping, ImportEstimateFromCsvCommand, ImportSummary, ImportTarget, RowError,
+};
+use crate::use_case::list_estimates::ListEstimatesQuery;
+use crate::use_case::set_estimate_location::SetEstimateLocationCommand;
+use crate::use_case::update_estimate::UpdateEstimateCommand;
+use std::path::PathBuf;
+use std::sync::Arc;
 use uuid::Uuid;
 
+use crate::clock::{Clock, SystemClock};
+use crate::dto::estimate_report_dto::EstimateReportDTO;
+use crate::entity::markup::Markup;
+use crate::entity::unit::UnitSystem;
 use crate::{dto::estimate_dto::EstimateDTO, entity::estimate::Estimate};
 
 use crate::error::Error::ControllerError;
 
 pub struct EstimateController {
-    create_estimate_use_case: CreateEstimate,
+    command_bus: Arc<CommandBus>,
+    query_bus: Arc<QueryBus>,
+    presenter: EstimatePresenter,
+    // Dates proposals and renders when the request does not give one.
+    clock: Arc<dyn Clock>,
+    // Units exports and proposals show quantities in; None keeps them as entered.
+    unit_system: Option<UnitSystem>,
+    // Currency proposals and renders show amounts in.
+    currency: Currency,
 }
 
 impl EstimateController {
-    pub fn new(create_estimate_use_case: CreateEstimate) -> Self {
-        EstimateController {
-            create_estimate_use_case,
+    pub fn new(command_bus: Arc<CommandBus>, query_bus: Arc<QueryBus>) -> Result<Self> {
+        command_bus.require::<CreateEstimateCommand>()?;
+        command_bus.require::<UpdateEstimateCommand>()?;
+        command_bus.require::<SetEstimateLocationCommand>()?;
+        command_bus.require::<DeleteEstimateCommand>()?;
+        command_bus.require::<DuplicateEstimateCommand>()?;
+        command_bus.require::<ImportEstimateFromCsvCommand>()?;
+        query_bus.require::<GetEstimateQuery>()?;
+        query_bus.require::<ListEstimatesQuery>()?;
+        query_bus.require::<GetEstimateReportQuery>()?;
+
+        Ok(EstimateController {
+            command_bus,
+            query_bus,
+            presenter: EstimatePresenter::default(),
+            clock: Arc::new(SystemClock),
+            unit_system: None,
+            currency: Currency::default(),
+        })
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_output_format(mut self, format: OutputFormat) -> Self {
+        self.presenter = EstimatePresenter::new(format);
+        self
+    }
+
+    pub fn with_unit_system(mut self, unit_system: Option<UnitSystem>) -> Self {
+        self.unit_system = unit_system;
+        self
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    fn displayed(&self, report: EstimateReportDTO) -> Result<EstimateReportDTO> {
+        match self.unit_system {
+            Some(system) => report.in_units(system),
+            None => Ok(report),
         }
     }
 
-    pub async fn create_estimate(&self, request: CreateEstimateRequest) -> Result<Uuid> {
-        let mut dto = EstimateDTO::new();
-        dto.name = request.name;
-        dto.description = request.description;
+    pub fn presenter(&self) -> &EstimatePresenter {
+        &self.presenter
+    }
+
+    pub async fn create_estimate(
+        &self,
+        request: CreateEstimateRequest,
+    ) -> Result<CreateEstimateResponse> {
+        traced("create_estimate", None, async move {
+            let command = CreateEstimateCommand {
+                name: request.name,
+                description: request.description,
+                location: request.location,
+                template_id: request.template_id,
+            };
 
-        // Ensure the execute method on the use case is async and await its result
-        let result = self.create_estimate_use_case.execute(dto).await;
+            let estimate_id = self.command_bus.dispatch(command).await?;
 
-        // Match on the async result
-        let response = match result {
-            Ok(estimate_id) => CreateEstimateResponse::new(
+            Ok(CreateEstimateResponse::new(
                 200,
                 "Estimate created successfully".to_string(),
                 Some(estimate_id),
-            ),
-            Err(_) => CreateEstimateResponse::new(500, "Error creating estimate".to_string(), None),
-        };
+            ))
+        })
+        .await
+    }
+
+    pub async fn get_estimate(&self, request: GetEstimateRequest) -> Result<GetEstimateResponse> {
+        traced("get_estimate", Some(request.estimate_id), async move {
+            let query = GetEstimateQuery {
+                estimate_id: request.estimate_id,
+            };
+
+            let response = match self.query_bus.dispatch(query).await? {
+                Some(estimate) => GetEstimateResponse::new(
+                    200,
+                    "Estimate retrieved successfully".to_string(),
+                    Some(estimate),
+                ),
+                None => GetEstimateResponse::new(404, "Estimate does not exist".to_string(), None),
+            };
+
+            Ok(response)
+        })
+        .await
+    }
+
+    pub async fn list_estimates(
+        &self,
+        _request: ListEstimatesRequest,
+    ) -> Result<ListEstimatesResponse> {
+        let estimates = self.query_bus.dispatch(ListEstimatesQuery).await?;
+
+        Ok(ListEstimatesResponse::new(
+            200,
+            format!("{} estimate(s) found", estimates.len()),
+            estimates,
+        ))
+    }
+
+    pub async fn update_estimate(
+        &self,
+        request: UpdateEstimateRequest,
+    ) -> Result<UpdateEstimateResponse> {
+        traced("update_estimate", Some(request.estimate_id), async move {
+            let command = UpdateEstimateCommand {
+                estimate_id: request.estimate_id,
+                name: request.name,
+                description: request.description,
+                price_guess: request.price_guess,
+                location: request.location,
+                markups: request.markups,
+                exclusions: request.exclusions,
+            };
+
+            let estimate = self.command_bus.dispatch(command).await?;
+
+            Ok(UpdateEstimateResponse::new(
+                200,
+                "Estimate updated successfully".to_string(),
+                Some(estimate),
+            ))
+        })
+        .await
+    }
+
+    pub async fn set_estimate_location(
+        &self,
+        request: SetEstimateLocationRequest,
+    ) -> Result<SetEstimateLocationResponse> {
+        traced(
+            "set_estimate_location",
+            Some(request.estimate_id),
+            async move {
+                let command = SetEstimateLocationCommand {
+                    estimate_id: request.estimate_id,
+                    location: request.location,
+                };
+
+                self.command_bus.dispatch(command).await?;
+
+                Ok(SetEstimateLocationResponse::new(
+                    200,
+                    "Estimate location set successfully".to_string(),
+                ))
+            },
+        )
+        .await
+    }
+
+    pub async fn duplicate_estimate(
+        &self,
+        request: DuplicateEstimateRequest,
+    ) -> Result<DuplicateEstimateResponse> {
+        traced(
+            "duplicate_estimate",
+            Some(request.estimate_id),
+            async move {
+                let command = DuplicateEstimateCommand {
+                    estimate_id: request.estimate_id,
+                    name: request.name,
+                    reset_status: request.reset_status,
+                    as_template: request.as_template,
+                };
 
-        EstimatePresenter::present(response); // Modify this line based on the actual behavior of your presenter
+                let estimate_id = self.command_bus.dispatch(command).await?;
 
-        result
+                Ok(DuplicateEstimateResponse::new(
+                    200,
+                    "Estimate duplicated successfully".to_string(),
+                    Some(estimate_id),
+                ))
+            },
+        )
+        .await
+    }
+
+    // Invalid rows come back as a 422 response listing every row error; nothing is imported.
+    pub async fn import_estimate_from_csv(
+        &self,
+        request: ImportEstimateFromCsvRequest,
+    ) -> Result<ImportEstimateFromCsvResponse> {
+        traced("import_estimate_from_csv", None, async move {
+            let command = ImportEstimateFromCsvCommand {
+                csv: request.csv,
+                mapping: request.mapping,
+                target: request.target,
+            };
+
+            match self.command_bus.dispatch(command).await {
+                Ok(summary) => Ok(ImportEstimateFromCsvResponse::new(
+                    200,
+                    format!(
+                        "Imported {} section(s) and {} line item(s)",
+                        summary.sections_created, summary.line_items_created
+                    ),
+                    Some(summary),
+                    vec![],
+                )),
+                Err(e) => match e.downcast_ref::<UseCaseError>() {
+                    Some(UseCaseError::ImportError(report)) => {
+                        Ok(ImportEstimateFromCsvResponse::new(
+                            422,
+                            e.to_string(),
+                            None,
+                            report.errors.clone(),
+                        ))
+                    }
+                    _ => Err(e),
+                },
+            }
+        })
+        .await
+    }
+
+    pub async fn export_estimate(
+        &self,
+        request: ExportEstimateRequest,
+    ) -> Result<ExportEstimateResponse> {
+        traced("export_estimate", Some(request.estimate_id), async move {
+            let query = GetEstimateReportQuery {
+                estimate_id: request.estimate_id,
+            };
+
+            let report = match self.query_bus.dispatch(query).await? {
+                Some(report) => self.displayed(report)?,
+                None => {
+                    return Ok(ExportEstimateResponse::new(
+                        404,
+                        "Estimate does not exist".to_string(),
+                        request.format,
+                        None,
+                    ))
+                }
+            };
+
+            let content = match request.format {
+                ExportFormat::Csv => CsvEstimateExporter::present(&report)?.into_bytes(),
+                #[cfg(feature = "xlsx")]
+                ExportFormat::Xlsx => XlsxEstimateExporter::present(&report)?,
+            };
+
+            Ok(ExportEstimateResponse::new(
+                200,
+                "Estimate exported successfully".to_string(),
+                request.format,
+                Some(content),
+            ))
+        })
+        .await
+    }
+
+    pub async fn generate_proposal(
+        &self,
+        request: GenerateProposalRequest,
+    ) -> Result<GenerateProposalResponse> {
+        traced("generate_proposal", Some(request.estimate_id), async move {
+            let query = GetEstimateReportQuery {
+                estimate_id: request.estimate_id,
+            };
+
+            let report = match self.query_bus.dispatch(query).await? {
+                Some(report) => self.displayed(report)?,
+                None => {
+                    return Ok(GenerateProposalResponse::new(
+                        404,
+                        "Estimate does not exist".to_string(),
+                        None,
+                    ))
+                }
+            };
+
+            let options = ProposalOptions {
+                date: request
+                    .date
+                    .unwrap_or_else(|| self.clock.now().date_naive()),
+                include_line_items: request.include_line_items,
+                prepared_by: request.prepared_by,
+                currency: self.currency,
+            };
+
+            Ok(GenerateProposalResponse::new(
+                200,
+                "Proposal generated successfully".to_string(),
+                Some(ProposalPresenter::present(&report, &options)),
+            ))
+        })
+        .await
+    }
+
+    // A template that refers to fields the view model lacks comes back as a 422 response
+    // listing each of them; nothing is rendered.
+    pub async fn render_estimate(
+        &self,
+        request: RenderEstimateRequest,
+    ) -> Result<RenderEstimateResponse> {
+        traced("render_estimate", Some(request.estimate_id), async move {
+            let presenter = match request.template {
+                TemplateSource::BuiltIn(template) => TemplatePresenter::built_in(template),
+                TemplateSource::File(path) => TemplatePresenter::from_file(&path),
+                TemplateSource::Inline { source, format } => {
+                    TemplatePresenter::new(&source, format)
+                }
+            };
+            let presenter = match presenter {
+                Ok(presenter) => presenter.with_currency(self.currency),
+                Err(e) => match e.downcast_ref::<PresenterError>() {
+                    Some(PresenterError::InvalidTemplate { issues }) => {
+                        return Ok(RenderEstimateResponse::new(
+                            422,
+                            e.to_string(),
+                            None,
+                            issues.clone(),
+                        ))
+                    }
+                    _ => return Err(e),
+                },
+            };
+
+            let query = GetEstimateReportQuery {
+                estimate_id: request.estimate_id,
+            };
+            let report = match self.query_bus.dispatch(query).await? {
+                Some(report) => self.displayed(report)?,
+                None => {
+                    return Ok(RenderEstimateResponse::new(
+                        404,
+                        "Estimate does not exist".to_string(),
+                        None,
+                        vec![],
+                    ))
+                }
+            };
+
+            let date = request
+                .date
+                .unwrap_or_else(|| self.clock.now().date_naive());
+            Ok(RenderEstimateResponse::new(
+                200,
+                "Estimate rendered successfully".to_string(),
+                Some(presenter.present(&report, date)?),
+                vec![],
+            ))
+        })
+        .await
+    }
+
+    pub async fn delete_estimate(
+        &self,
+        request: DeleteEstimateRequest,
+    ) -> Result<DeleteEstimateResponse> {
+        traced("delete_estimate", Some(request.estimate_id), async move {
+            let command = DeleteEstimateCommand {
+                estimate_id: request.estimate_id,
+            };
+
+            self.command_bus.dispatch(command).await?;
+
+            Ok(DeleteEstimateResponse::new(
+                200,
+                "Estimate deleted successfully".to_string(),
+            ))
+        })
+        .await
     }
 }
 
@@ -49,6 +432,7 @@ pub struct CreateEstimateRequest {
     pub name: String,
     pub description: String,
     pub location: String,
+    pub template_id: Option<Uuid>,
 }
 
 impl CreateEstimateRequest {
@@ -57,6 +441,19 @@ impl CreateEstimateRequest {
             name,
             description,
             location,
+            template_id: None,
+        }
+    }
+
+    pub fn from_template(
+        template_id: Uuid,
+        name: String,
+        description: String,
+        location: String,
+    ) -> Self {
+        CreateEstimateRequest {
+            template_id: Some(template_id),
+            ..CreateEstimateRequest::new(name, description, location)
         }
     }
 }
@@ -77,3 +474,373 @@ impl CreateEstimateResponse {
         }
     }
 }
+
+pub struct GetEstimateRequest {
+    pub estimate_id: Uuid,
+}
+
+impl GetEstimateRequest {
+    pub fn new(estimate_id: Uuid) -> Self {
+        GetEstimateRequest { estimate_id }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetEstimateResponse {
+    pub status_code: u16,
+    pub message: String,
+    pub estimate: Option<EstimateDTO>,
+}
+
+impl GetEstimateResponse {
+    pub fn new(status_code: u16, message: String, estimate: Option<EstimateDTO>) -> Self {
+        GetEstimateResponse {
+            status_code,
+            message,
+            estimate,
+        }
+    }
+}
+
+pub struct ListEstimatesRequest;
+
+#[derive(Debug)]
+pub struct ListEstimatesResponse {
+    pub status_code: u16,
+    pub message: String,
+    pub estimates: Vec<EstimateDTO>,
+}
+
+impl ListEstimatesResponse {
+    pub fn new(status_code: u16, message: String, estimates: Vec<EstimateDTO>) -> Self {
+        ListEstimatesResponse {
+            status_code,
+            message,
+            estimates,
+        }
+    }
+}
+
+// Only the fields that are `Some` are changed.
+#[derive(Default)]
+pub struct UpdateEstimateRequest {
+    pub estimate_id: Uuid,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub price_guess: Option<f64>,
+    pub location: Option<String>,
+    pub markups: Option<Vec<Markup>>,
+    pub exclusions: Option<Vec<String>>,
+}
+
+impl UpdateEstimateRequest {
+    pub fn new(estimate_id: Uuid) -> Self {
+        UpdateEstimateRequest {
+            estimate_id,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateEstimateResponse {
+    pub status_code: u16,
+    pub message: String,
+    pub estimate: Option<EstimateDTO>,
+}
+
+impl UpdateEstimateResponse {
+    pub fn new(status_code: u16, message: String, estimate: Option<EstimateDTO>) -> Self {
+        UpdateEstimateResponse {
+            status_code,
+            message,
+            estimate,
+        }
+    }
+}
+
+pub struct SetEstimateLocationRequest {
+    pub estimate_id: Uuid,
+    pub location: String,
+}
+
+impl SetEstimateLocationRequest {
+    pub fn new(estimate_id: Uuid, location: String) -> Self {
+        SetEstimateLocationRequest {
+            estimate_id,
+            location,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SetEstimateLocationResponse {
+    pub status_code: u16,
+    pub message: String,
+}
+
+impl SetEstimateLocationResponse {
+    pub fn new(status_code: u16, message: String) -> Self {
+        SetEstimateLocationResponse {
+            status_code,
+            message,
+        }
+    }
+}
+
+pub struct DuplicateEstimateRequest {
+    pub estimate_id: Uuid,
+    pub name: Option<String>,
+    pub reset_status: bool,
+    pub as_template: bool,
+}
+
+impl DuplicateEstimateRequest {
+    pub fn new(estimate_id: Uuid, name: Option<String>) -> Self {
+        DuplicateEstimateRequest {
+            estimate_id,
+            name,
+            reset_status: true,
+            as_template: false,
+        }
+    }
+
+    pub fn as_template(estimate_id: Uuid, name: Option<String>) -> Self {
+        DuplicateEstimateRequest {
+            as_template: true,
+            ..DuplicateEstimateRequest::new(estimate_id, name)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DuplicateEstimateResponse {
+    pub status_code: u16,
+    pub message: String,
+    pub estimate_id: Option<Uuid>,
+}
+
+impl DuplicateEstimateResponse {
+    pub fn new(status_code: u16, message: String, estimate_id: Option<Uuid>) -> Self {
+        DuplicateEstimateResponse {
+            status_code,
+            message,
+            estimate_id,
+        }
+    }
+}
+
+pub struct ImportEstimateFromCsvRequest {
+    pub csv: String,
+    pub mapping: CsvColumnMapping,
+    pub target: ImportTarget,
+}
+
+impl ImportEstimateFromCsvRequest {
+    pub fn new(csv: String, target: ImportTarget) -> Self {
+        ImportEstimateFromCsvRequest {
+            csv,
+            mapping: CsvColumnMapping::default(),
+            target,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportEstimateFromCsvResponse {
+    pub status_code: u16,
+    pub message: String,
+    pub summary: Option<ImportSummary>,
+    pub errors: Vec<RowError>,
+}
+
+impl ImportEstimateFromCsvResponse {
+    pub fn new(
+        status_code: u16,
+        message: String,
+        summary: Option<ImportSummary>,
+        errors: Vec<RowError>,
+    ) -> Self {
+        ImportEstimateFromCsvResponse {
+            status_code,
+            message,
+            summary,
+            errors,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    #[cfg(feature = "xlsx")]
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            #[cfg(feature = "xlsx")]
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            #[cfg(feature = "xlsx")]
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+pub struct ExportEstimateRequest {
+    pub estimate_id: Uuid,
+    pub format: ExportFormat,
+}
+
+impl ExportEstimateRequest {
+    pub fn new(estimate_id: Uuid, format: ExportFormat) -> Self {
+        ExportEstimateRequest {
+            estimate_id,
+            format,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ExportEstimateResponse {
+    pub status_code: u16,
+    pub message: String,
+    pub format: ExportFormat,
+    pub content: Option<Vec<u8>>,
+}
+
+impl ExportEstimateResponse {
+    pub fn new(
+        status_code: u16,
+        message: String,
+        format: ExportFormat,
+        content: Option<Vec<u8>>,
+    ) -> Self {
+        ExportEstimateResponse {
+            status_code,
+            message,
+            format,
+            content,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct GenerateProposalRequest {
+    pub estimate_id: Uuid,
+    pub include_line_items: bool,
+    pub prepared_by: Option<String>,
+    // Defaults to today.
+    pub date: Option<chrono::NaiveDate>,
+}
+
+impl GenerateProposalRequest {
+    pub fn new(estimate_id: Uuid) -> Self {
+        GenerateProposalRequest {
+            estimate_id,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GenerateProposalResponse {
+    pub status_code: u16,
+    pub message: String,
+    pub pdf: Option<Vec<u8>>,
+}
+
+impl GenerateProposalResponse {
+    pub fn new(status_code: u16, message: String, pdf: Option<Vec<u8>>) -> Self {
+        GenerateProposalResponse {
+            status_code,
+            message,
+            pdf,
+        }
+    }
+}
+
+pub enum TemplateSource {
+    BuiltIn(BuiltInTemplate),
+    File(PathBuf),
+    Inline {
+        source: String,
+        format: TemplateFormat,
+    },
+}
+
+pub struct RenderEstimateRequest {
+    pub estimate_id: Uuid,
+    pub template: TemplateSource,
+    // Defaults to today.
+    pub date: Option<chrono::NaiveDate>,
+}
+
+impl RenderEstimateRequest {
+    pub fn new(estimate_id: Uuid, template: TemplateSource) -> Self {
+        RenderEstimateRequest {
+            estimate_id,
+            template,
+            date: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RenderEstimateResponse {
+    pub status_code: u16,
+    pub message: String,
+    pub content: Option<String>,
+    pub issues: Vec<String>,
+}
+
+impl RenderEstimateResponse {
+    pub fn new(
+        status_code: u16,
+        message: String,
+        content: Option<String>,
+        issues: Vec<String>,
+    ) -> Self {
+        RenderEstimateResponse {
+            status_code,
+            message,
+            content,
+            issues,
+        }
+    }
+}
+
+pub struct DeleteEstimateRequest {
+    pub estimate_id: Uuid,
+}
+
+impl DeleteEstimateRequest {
+    pub fn new(estimate_id: Uuid) -> Self {
+        DeleteEstimateRequest { estimate_id }
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteEstimateResponse {
+    pub status_code: u16,
+    pub message: String,
+}
+
+impl DeleteEstimateResponse {
+    pub fn new(status_code: u16, message: String) -> Self {
+        DeleteEstimateResponse {
+            status_code,
+            message,
+        }
+    }
+}
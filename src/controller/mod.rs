@@ -2,12 +2,33 @@
 pub mod error;
 pub mod estimate_controller;
 pub mod section_controller;
-use crate::Result;
+use crate::result::*;
 
-pub trait Executable<T> {
-    fn execute(&mut self, input: T);
+use std::future::Future;
+use uuid::Uuid;
+
+use crate::telemetry::{self, Span};
+use crate::use_case::traits::UseCase;
+
+// Controllers are where requests enter, so this joins the caller's request id (or starts one)
+// and records the request as a controller span.
+pub(crate) async fn traced<T, F>(
+    operation: &'static str,
+    entity_id: Option<Uuid>,
+    future: F,
+) -> Result<T>
+where
+    F: Future<Output = Result<T>>,
+{
+    telemetry::in_request(
+        Span::new("controller", operation)
+            .with_entity(entity_id)
+            .instrument(future),
+    )
+    .await
 }
 
+// Exposes any use case (or middleware pipeline) as a controller.
 pub struct GenericController<U> {
     use_case: U,
 }
@@ -16,13 +37,13 @@ impl<U> GenericController<U> {
     pub fn new(use_case: U) -> Self {
         Self { use_case }
     }
-}
 
-impl<U, T> Executable<T> for GenericController<U>
-where
-    U: Executable<T>,
-{
-    fn execute(&mut self, input: T) {
-        self.use_case.execute(input);
+    pub async fn execute<I, O>(&self, input: I) -> Result<O>
+    where
+        U: UseCase<I, O>,
+        I: Send + 'static,
+        O: Send + 'static,
+    {
+        traced("execute", None, self.use_case.execute(input)).await
     }
 }
@@ -1,55 +1,230 @@
 //controller/section_controller.rs
 
-use crate::dto::estimate_dto::EstimateDTO;
 use crate::dto::section_dto::SectionDTO;
 use crate::entity::section::Section;
+use crate::presenter::formats::OutputFormat;
+use crate::presenter::section_presenter::SectionPresenter;
 use crate::result::*;
+
+use super::traced;
+use std::sync::Arc;
 use uuid::Uuid;
 
-use crate::use_case::create_section_add_to_estimate::CreateSectionAddToEstimate;
+use crate::bus::command_bus::CommandBus;
+use crate::bus::query_bus::QueryBus;
+use crate::use_case::create_section_add_to_estimate::CreateSectionAddToEstimateCommand;
+use crate::use_case::delete_section::DeleteSectionCommand;
 use crate::use_case::error::Error;
+use crate::use_case::get_section::GetSectionQuery;
+use crate::use_case::list_sections_for_estimate::ListSectionsForEstimateQuery;
+use crate::use_case::move_section::MoveSectionCommand;
+use crate::use_case::reorder_sections::ReorderSectionsCommand;
+use crate::use_case::update_section::UpdateSectionCommand;
 
 pub struct SectionController {
-    create_section_add_to_estimate: CreateSectionAddToEstimate,
+    command_bus: Arc<CommandBus>,
+    query_bus: Arc<QueryBus>,
+    presenter: SectionPresenter,
 }
 
 impl SectionController {
-    pub fn new(create_section_add_to_estimate: CreateSectionAddToEstimate) -> SectionController {
-        SectionController {
-            create_section_add_to_estimate,
-        }
+    pub fn new(
+        command_bus: Arc<CommandBus>,
+        query_bus: Arc<QueryBus>,
+    ) -> Result<SectionController> {
+        command_bus.require::<CreateSectionAddToEstimateCommand>()?;
+        command_bus.require::<UpdateSectionCommand>()?;
+        command_bus.require::<DeleteSectionCommand>()?;
+        command_bus.require::<MoveSectionCommand>()?;
+        command_bus.require::<ReorderSectionsCommand>()?;
+        query_bus.require::<GetSectionQuery>()?;
+        query_bus.require::<ListSectionsForEstimateQuery>()?;
+
+        Ok(SectionController {
+            command_bus,
+            query_bus,
+            presenter: SectionPresenter::default(),
+        })
+    }
+
+    pub fn with_output_format(mut self, format: OutputFormat) -> Self {
+        self.presenter = SectionPresenter::new(format);
+        self
+    }
+
+    pub fn presenter(&self) -> &SectionPresenter {
+        &self.presenter
     }
 
     pub async fn create_section_add_to_estimate(
         &self,
         request: CreateSectionAddToEstimateRequest,
     ) -> Result<Uuid> {
-        let result = self
-            .create_section_add_to_estimate
-            .execute(request.section, request.estimate)
-            .await;
+        traced(
+            "create_section_add_to_estimate",
+            Some(request.estimate_id),
+            async move {
+                let command = CreateSectionAddToEstimateCommand {
+                    section: request.section,
+                    estimate_id: request.estimate_id,
+                };
+
+                let result = self.command_bus.dispatch(command).await;
+
+                match result {
+                    Ok(section) => CreateSectionAddToEstimateResponse::new(
+                        200,
+                        "Section created successfully".to_string(),
+                        Some(section),
+                    ),
+                    Err(_) => CreateSectionAddToEstimateResponse::new(500, "E".to_string(), None),
+                };
+
+                result
+            },
+        )
+        .await
+    }
+
+    pub async fn get_section(&self, request: GetSectionRequest) -> Result<GetSectionResponse> {
+        traced("get_section", Some(request.section_id), async move {
+            let query = GetSectionQuery {
+                section_id: request.section_id,
+            };
+
+            let response = match self.query_bus.dispatch(query).await? {
+                Some(section) => GetSectionResponse::new(
+                    200,
+                    "Section retrieved successfully".to_string(),
+                    Some(section),
+                ),
+                None => GetSectionResponse::new(404, "Section does not exist".to_string(), None),
+            };
+
+            Ok(response)
+        })
+        .await
+    }
+
+    pub async fn list_sections_for_estimate(
+        &self,
+        request: ListSectionsForEstimateRequest,
+    ) -> Result<ListSectionsForEstimateResponse> {
+        traced(
+            "list_sections_for_estimate",
+            Some(request.estimate_id),
+            async move {
+                let query = ListSectionsForEstimateQuery {
+                    estimate_id: request.estimate_id,
+                };
 
-        match result {
-            Ok(section) => CreateSectionAddToEstimateResponse::new(
+                let sections = self.query_bus.dispatch(query).await?;
+
+                Ok(ListSectionsForEstimateResponse::new(
+                    200,
+                    format!("{} section(s) found", sections.len()),
+                    sections,
+                ))
+            },
+        )
+        .await
+    }
+
+    pub async fn update_section(
+        &self,
+        request: UpdateSectionRequest,
+    ) -> Result<UpdateSectionResponse> {
+        traced("update_section", Some(request.section_id), async move {
+            let command = UpdateSectionCommand {
+                section_id: request.section_id,
+                code: request.code,
+                name: request.name,
+                description: request.description,
+            };
+
+            let section = self.command_bus.dispatch(command).await?;
+
+            Ok(UpdateSectionResponse::new(
                 200,
-                "Section created successfully".to_string(),
+                "Section updated successfully".to_string(),
                 Some(section),
-            ),
-            Err(_) => CreateSectionAddToEstimateResponse::new(500, "E".to_string(), None),
-        };
+            ))
+        })
+        .await
+    }
+
+    pub async fn delete_section(
+        &self,
+        request: DeleteSectionRequest,
+    ) -> Result<DeleteSectionResponse> {
+        traced("delete_section", Some(request.section_id), async move {
+            let command = DeleteSectionCommand {
+                section_id: request.section_id,
+            };
 
-        result
+            self.command_bus.dispatch(command).await?;
+
+            Ok(DeleteSectionResponse::new(
+                200,
+                "Section deleted successfully".to_string(),
+            ))
+        })
+        .await
+    }
+
+    pub async fn move_section(&self, request: MoveSectionRequest) -> Result<MoveSectionResponse> {
+        traced("move_section", Some(request.section_id), async move {
+            let command = MoveSectionCommand {
+                section_id: request.section_id,
+                estimate_id: request.estimate_id,
+                parent_id: request.parent_id,
+                position: request.position,
+            };
+
+            let section = self.command_bus.dispatch(command).await?;
+
+            Ok(MoveSectionResponse::new(
+                200,
+                "Section moved successfully".to_string(),
+                Some(section),
+            ))
+        })
+        .await
+    }
+
+    pub async fn reorder_sections(
+        &self,
+        request: ReorderSectionsRequest,
+    ) -> Result<ReorderSectionsResponse> {
+        traced("reorder_sections", Some(request.estimate_id), async move {
+            let command = ReorderSectionsCommand {
+                estimate_id: request.estimate_id,
+                parent_id: request.parent_id,
+                section_ids: request.section_ids,
+            };
+
+            self.command_bus.dispatch(command).await?;
+
+            Ok(ReorderSectionsResponse::new(
+                200,
+                "Sections reordered successfully".to_string(),
+            ))
+        })
+        .await
     }
 }
 
 pub struct CreateSectionAddToEstimateRequest {
     pub section: SectionDTO,
-    pub estimate: EstimateDTO,
+    pub estimate_id: Uuid,
 }
 
 impl CreateSectionAddToEstimateRequest {
-    pub fn new(section: SectionDTO, estimate: EstimateDTO) -> CreateSectionAddToEstimateRequest {
-        CreateSectionAddToEstimateRequest { section, estimate }
+    pub fn new(section: SectionDTO, estimate_id: Uuid) -> CreateSectionAddToEstimateRequest {
+        CreateSectionAddToEstimateRequest {
+            section,
+            estimate_id,
+        }
     }
 }
 
@@ -72,3 +247,190 @@ impl CreateSectionAddToEstimateResponse {
         }
     }
 }
+
+pub struct GetSectionRequest {
+    pub section_id: Uuid,
+}
+
+impl GetSectionRequest {
+    pub fn new(section_id: Uuid) -> GetSectionRequest {
+        GetSectionRequest { section_id }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetSectionResponse {
+    pub status: u16,
+    pub message: String,
+    pub section: Option<SectionDTO>,
+}
+
+impl GetSectionResponse {
+    pub fn new(status: u16, message: String, section: Option<SectionDTO>) -> GetSectionResponse {
+        GetSectionResponse {
+            status,
+            message,
+            section,
+        }
+    }
+}
+
+pub struct ListSectionsForEstimateRequest {
+    pub estimate_id: Uuid,
+}
+
+impl ListSectionsForEstimateRequest {
+    pub fn new(estimate_id: Uuid) -> ListSectionsForEstimateRequest {
+        ListSectionsForEstimateRequest { estimate_id }
+    }
+}
+
+#[derive(Debug)]
+pub struct ListSectionsForEstimateResponse {
+    pub status: u16,
+    pub message: String,
+    pub sections: Vec<SectionDTO>,
+}
+
+impl ListSectionsForEstimateResponse {
+    pub fn new(
+        status: u16,
+        message: String,
+        sections: Vec<SectionDTO>,
+    ) -> ListSectionsForEstimateResponse {
+        ListSectionsForEstimateResponse {
+            status,
+            message,
+            sections,
+        }
+    }
+}
+
+// Only the fields that are `Some` are changed.
+#[derive(Default)]
+pub struct UpdateSectionRequest {
+    pub section_id: Uuid,
+    pub code: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl UpdateSectionRequest {
+    pub fn new(section_id: Uuid) -> UpdateSectionRequest {
+        UpdateSectionRequest {
+            section_id,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct UpdateSectionResponse {
+    pub status: u16,
+    pub message: String,
+    pub section: Option<SectionDTO>,
+}
+
+impl UpdateSectionResponse {
+    pub fn new(status: u16, message: String, section: Option<SectionDTO>) -> UpdateSectionResponse {
+        UpdateSectionResponse {
+            status,
+            message,
+            section,
+        }
+    }
+}
+
+pub struct DeleteSectionRequest {
+    pub section_id: Uuid,
+}
+
+impl DeleteSectionRequest {
+    pub fn new(section_id: Uuid) -> DeleteSectionRequest {
+        DeleteSectionRequest { section_id }
+    }
+}
+
+#[derive(Debug)]
+pub struct DeleteSectionResponse {
+    pub status: u16,
+    pub message: String,
+}
+
+impl DeleteSectionResponse {
+    pub fn new(status: u16, message: String) -> DeleteSectionResponse {
+        DeleteSectionResponse { status, message }
+    }
+}
+
+pub struct MoveSectionRequest {
+    pub section_id: Uuid,
+    pub estimate_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub position: Option<u32>,
+}
+
+impl MoveSectionRequest {
+    pub fn new(
+        section_id: Uuid,
+        estimate_id: Option<Uuid>,
+        parent_id: Option<Uuid>,
+        position: Option<u32>,
+    ) -> MoveSectionRequest {
+        MoveSectionRequest {
+            section_id,
+            estimate_id,
+            parent_id,
+            position,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MoveSectionResponse {
+    pub status: u16,
+    pub message: String,
+    pub section: Option<SectionDTO>,
+}
+
+impl MoveSectionResponse {
+    pub fn new(status: u16, message: String, section: Option<SectionDTO>) -> MoveSectionResponse {
+        MoveSectionResponse {
+            status,
+            message,
+            section,
+        }
+    }
+}
+
+pub struct ReorderSectionsRequest {
+    pub estimate_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub section_ids: Vec<Uuid>,
+}
+
+impl ReorderSectionsRequest {
+    pub fn new(
+        estimate_id: Uuid,
+        parent_id: Option<Uuid>,
+        section_ids: Vec<Uuid>,
+    ) -> ReorderSectionsRequest {
+        ReorderSectionsRequest {
+            estimate_id,
+            parent_id,
+            section_ids,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ReorderSectionsResponse {
+    pub status: u16,
+    pub message: String,
+}
+
+impl ReorderSectionsResponse {
+    pub fn new(status: u16, message: String) -> ReorderSectionsResponse {
+        ReorderSectionsResponse { status, message }
+    }
+}
@@ -2,7 +2,10 @@
 
 use uuid::Uuid;
 
-use crate::entity::estimate::Estimate;
+use crate::clock::{Clock, SystemClock};
+use crate::entity::estimate::{Estimate, EstimateStatus};
+use crate::entity::markup::Markup;
+use crate::id_generator::{IdGenerator, RandomIdGenerator};
 
 #[derive(Debug, Clone)]
 pub struct EstimateDTO {
@@ -10,19 +13,39 @@ pub struct EstimateDTO {
     pub name: String,
     pub description: String,
     pub location: String,
+    pub price_guess: f64,
+    pub status: EstimateStatus,
+    pub is_template: bool,
+    pub markups: Vec<Markup>,
+    pub exclusions: Vec<String>,
 }
 
 impl EstimateDTO {
     pub fn new() -> Self {
+        EstimateDTO::new_with(&RandomIdGenerator)
+    }
+
+    pub fn new_with(ids: &dyn IdGenerator) -> Self {
         EstimateDTO {
-            id: Uuid::new_v4(),
+            id: ids.next_id(),
             name: "".to_string(),
             description: "".to_string(),
             location: "".to_string(),
+            price_guess: 0.0,
+            status: EstimateStatus::Draft,
+            is_template: false,
+            markups: vec![],
+            exclusions: vec![],
         }
     }
 }
 
+impl Default for EstimateDTO {
+    fn default() -> Self {
+        EstimateDTO::new()
+    }
+}
+
 impl From<Estimate> for EstimateDTO {
     fn from(estimate: Estimate) -> Self {
         EstimateDTO {
@@ -30,25 +53,43 @@ impl From<Estimate> for EstimateDTO {
             name: estimate.name,
             description: estimate.description,
             location: estimate.location,
+            price_guess: estimate.price_guess,
+            status: estimate.status,
+            is_template: estimate.is_template,
+            markups: estimate.markups,
+            exclusions: estimate.exclusions,
         }
     }
 }
 
-impl From<EstimateDTO> for Estimate {
-    fn from(estimate_dto: EstimateDTO) -> Self {
+impl EstimateDTO {
+    // The new entity is stamped with `clock`; `Estimate::from` uses the system clock.
+    pub fn into_entity(self, clock: &dyn Clock) -> Estimate {
+        let estimate_dto = self;
+        let now = clock.now();
         Estimate {
             id: estimate_dto.id,
             name: estimate_dto.name,
             description: estimate_dto.description,
             price: 0.0,
-            location: "".to_string(),
-            price_guess: 0.0,
-            created_at: chrono::Utc::now(),
-            updated_at: chrono::Utc::now(),
+            location: estimate_dto.location,
+            price_guess: estimate_dto.price_guess,
+            status: estimate_dto.status,
+            is_template: estimate_dto.is_template,
+            markups: estimate_dto.markups,
+            exclusions: estimate_dto.exclusions,
+            created_at: now,
+            updated_at: now,
         }
     }
 }
 
+impl From<EstimateDTO> for Estimate {
+    fn from(estimate_dto: EstimateDTO) -> Self {
+        estimate_dto.into_entity(&SystemClock)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,6 +104,10 @@ mod tests {
             price: 10.0,
             location: "Location".to_string(),
             price_guess: 10.0,
+            status: EstimateStatus::Submitted,
+            is_template: false,
+            markups: vec![Markup::new("Overhead".to_string(), 10.0)],
+            exclusions: vec!["Permits".to_string()],
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
         };
@@ -75,6 +120,11 @@ mod tests {
         assert_eq!(estimate_dto.name, estimate.name);
         assert_eq!(estimate_dto.description, estimate.description);
         assert_eq!(estimate_dto.location, estimate.location);
+        assert_eq!(estimate_dto.price_guess, estimate.price_guess);
+        assert_eq!(estimate_dto.status, estimate.status);
+        assert_eq!(estimate_dto.is_template, estimate.is_template);
+        assert_eq!(estimate_dto.markups, estimate.markups);
+        assert_eq!(estimate_dto.exclusions, estimate.exclusions);
     }
 
     #[test]
@@ -93,4 +143,16 @@ mod tests {
         assert_eq!(estimate.location, "".to_string()); // Location defaults to an empty string
         assert_eq!(estimate.price_guess, 0.0); // Price guess defaults to 0.0 in the conversion
     }
+
+    #[test]
+    fn test_estimate_dto_to_estimate_keeps_location_and_price_guess() {
+        let mut estimate_dto = EstimateDTO::new();
+        estimate_dto.location = "New York".to_string();
+        estimate_dto.price_guess = 1500.0;
+
+        let estimate = Estimate::from(estimate_dto);
+
+        assert_eq!(estimate.location, "New York");
+        assert_eq!(estimate.price_guess, 1500.0);
+    }
 }
@@ -0,0 +1,268 @@
+// dto/estimate_report_dto.rs
+
+use crate::result::*;
+
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use super::estimate_dto::EstimateDTO;
+use crate::entity::estimate::Estimate;
+use crate::entity::section::Section;
+use crate::entity::unit::{Unit, UnitSystem};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReportRowKind {
+    Section,
+    LineItem,
+}
+
+// One line of a flattened estimate. Section rows carry a WBS number ("2.1.3") and the rolled-up
+// total of everything below them; line item rows sit one level deeper than their section.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportRowDTO {
+    pub kind: ReportRowKind,
+    pub wbs: String,
+    pub depth: usize,
+    pub code: String,
+    pub description: String,
+    pub quantity: Option<f64>,
+    pub unit: Option<Unit>,
+    pub unit_cost: Option<f64>,
+    pub total: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarkupLineDTO {
+    pub name: String,
+    pub rate: f64,
+    pub amount: f64,
+}
+
+// An estimate flattened into rows, ready for the export and proposal presenters.
+#[derive(Debug, Clone)]
+pub struct EstimateReportDTO {
+    pub estimate: EstimateDTO,
+    pub rows: Vec<ReportRowDTO>,
+    pub subtotal: f64,
+    pub markups: Vec<MarkupLineDTO>,
+    pub total: f64,
+}
+
+impl EstimateReportDTO {
+    // `sections` must be in tree order, as returned by `list_sections_for_estimate`.
+    pub fn new(estimate: Estimate, sections: Vec<Section>) -> Self {
+        let parents: HashMap<Uuid, Option<Uuid>> = sections
+            .iter()
+            .map(|section| (section.id, section.parent_id))
+            .collect();
+
+        // Roll every section's own line items up into all of its ancestors.
+        let mut totals: HashMap<Uuid, f64> = HashMap::new();
+        for section in &sections {
+            let own = section.line_items_total();
+            let mut current = Some(section.id);
+            while let Some(id) = current {
+                *totals.entry(id).or_insert(0.0) += own;
+                current = parents.get(&id).copied().flatten();
+            }
+        }
+
+        let mut rows = Vec::new();
+        let mut wbs_by_id: HashMap<Uuid, (String, usize)> = HashMap::new();
+        let mut child_counts: HashMap<Option<Uuid>, usize> = HashMap::new();
+        for section in &sections {
+            // Sections whose parent is not in the estimate are shown at the top level.
+            let parent = section.parent_id.filter(|id| wbs_by_id.contains_key(id));
+            let number = {
+                let count = child_counts.entry(parent).or_insert(0);
+                *count += 1;
+                *count
+            };
+            let (wbs, depth) = match parent.and_then(|id| wbs_by_id.get(&id)) {
+                Some((parent_wbs, parent_depth)) => {
+                    (format!("{}.{}", parent_wbs, number), parent_depth + 1)
+                }
+                None => (number.to_string(), 0),
+            };
+            wbs_by_id.insert(section.id, (wbs.clone(), depth));
+
+            rows.push(ReportRowDTO {
+                kind: ReportRowKind::Section,
+                wbs,
+                depth,
+                code: section.code.clone(),
+                description: section.name.clone(),
+                quantity: None,
+                unit: None,
+                unit_cost: None,
+                total: totals.get(&section.id).copied().unwrap_or(0.0),
+            });
+            for item in &section.line_items {
+                rows.push(ReportRowDTO {
+                    kind: ReportRowKind::LineItem,
+                    wbs: String::new(),
+                    depth: depth + 1,
+                    code: String::new(),
+                    description: item.description.clone(),
+                    quantity: Some(item.quantity),
+                    unit: Some(item.unit),
+                    unit_cost: Some(item.unit_cost),
+                    total: item.total(),
+                });
+            }
+        }
+
+        let subtotal = sections
+            .iter()
+            .fold(0.0, |total, section| total + section.line_items_total());
+        let markups: Vec<MarkupLineDTO> = estimate
+            .markups
+            .iter()
+            .map(|markup| MarkupLineDTO {
+                name: markup.name.clone(),
+                rate: markup.rate,
+                amount: markup.amount(subtotal),
+            })
+            .collect();
+        let total = markups
+            .iter()
+            .fold(subtotal, |total, markup| total + markup.amount);
+
+        EstimateReportDTO {
+            estimate: estimate.into(),
+            rows,
+            subtotal,
+            markups,
+            total,
+        }
+    }
+
+    // Restates every line item in `system`'s units, e.g. SF as M2. Totals stay as they are: the
+    // unit cost is scaled against the quantity.
+    pub fn in_units(mut self, system: UnitSystem) -> Result<Self> {
+        for row in &mut self.rows {
+            let Some(unit) = row.unit else { continue };
+            let target = unit.counterpart(system);
+            let factor = unit.conversion_factor(target)?;
+            row.quantity = row.quantity.map(|quantity| quantity * factor);
+            row.unit_cost = row.unit_cost.map(|unit_cost| unit_cost / factor);
+            row.unit = Some(target);
+        }
+        Ok(self)
+    }
+
+    // Splits the rows into one group per top-level section, each starting with that section.
+    pub fn top_level_groups(&self) -> Vec<&[ReportRowDTO]> {
+        let starts: Vec<usize> = self
+            .rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.kind == ReportRowKind::Section && row.depth == 0)
+            .map(|(index, _)| index)
+            .collect();
+        starts
+            .iter()
+            .enumerate()
+            .map(|(i, &start)| {
+                let end = starts.get(i + 1).copied().unwrap_or(self.rows.len());
+                &self.rows[start..end]
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::section_dto::SectionDTO;
+    use crate::entity::line_item::LineItem;
+    use crate::entity::markup::Markup;
+
+    fn section(name: &str, code: &str, parent: Option<&Section>, items: Vec<LineItem>) -> Section {
+        let mut dto = SectionDTO::new(name.to_string(), code.to_string());
+        dto.parent_id = parent.map(|parent| parent.id);
+        let mut section = Section::from(dto);
+        section.line_items = items;
+        section
+    }
+
+    #[test]
+    fn test_report_numbers_sections_and_rolls_up_totals() {
+        let mut estimate_dto = EstimateDTO::new();
+        estimate_dto.name = "Retail Shell".to_string();
+        estimate_dto.markups = vec![Markup::new("Overhead".to_string(), 10.0)];
+
+        let site = section("Sitework", "02", None, vec![]);
+        let paving = section(
+            "Paving",
+            "02-700",
+            Some(&site),
+            vec![LineItem::new(
+                "Asphalt".to_string(),
+                100.0,
+                Unit::SquareYard,
+                20.0,
+            )],
+        );
+        let concrete = section(
+            "Concrete",
+            "03",
+            None,
+            vec![LineItem::new(
+                "Slab".to_string(),
+                10.0,
+                Unit::CubicYard,
+                100.0,
+            )],
+        );
+
+        let report =
+            EstimateReportDTO::new(Estimate::from(estimate_dto), vec![site, paving, concrete]);
+
+        let summary: Vec<(&str, usize, f64)> = report
+            .rows
+            .iter()
+            .map(|row| (row.wbs.as_str(), row.depth, row.total))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("1", 0, 2000.0),
+                ("1.1", 1, 2000.0),
+                ("", 2, 2000.0),
+                ("2", 0, 1000.0),
+                ("", 1, 1000.0),
+            ]
+        );
+        assert_eq!(report.subtotal, 3000.0);
+        assert_eq!(report.markups[0].amount, 300.0);
+        assert_eq!(report.total, 3300.0);
+        assert_eq!(report.top_level_groups().len(), 2);
+    }
+
+    #[test]
+    fn test_in_units_restates_line_items() {
+        let slab = section(
+            "Concrete",
+            "03",
+            None,
+            vec![LineItem::new(
+                "Slab".to_string(),
+                100.0,
+                Unit::SquareFoot,
+                8.0,
+            )],
+        );
+        let report = EstimateReportDTO::new(Estimate::from(EstimateDTO::new()), vec![slab])
+            .in_units(UnitSystem::Metric)
+            .unwrap();
+
+        let row = &report.rows[1];
+        assert_eq!(row.unit, Some(Unit::SquareMetre));
+        assert!((row.quantity.unwrap() - 9.290304).abs() < 1e-9);
+        assert!((row.quantity.unwrap() * row.unit_cost.unwrap() - 800.0).abs() < 1e-9);
+        assert_eq!(row.total, 800.0);
+        assert_eq!(report.rows[0].unit, None);
+    }
+}
@@ -1,4 +1,6 @@
 // dto/mod.rs
 
 pub mod estimate_dto;
+pub mod estimate_report_dto;
+pub mod price_book_item_dto;
 pub mod section_dto;
@@ -0,0 +1,35 @@
+// dto/price_book_item_dto.rs
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::entity::price_book_item::{PriceBookItem, UnitCosts};
+use crate::entity::unit::Unit;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceBookItemDTO {
+    pub id: Uuid,
+    pub code: String,
+    pub description: String,
+    pub unit: Unit,
+    pub costs: UnitCosts,
+    // Material, labor and equipment together.
+    pub unit_cost: f64,
+    pub effective_from: NaiveDate,
+    pub effective_to: Option<NaiveDate>,
+}
+
+impl From<PriceBookItem> for PriceBookItemDTO {
+    fn from(item: PriceBookItem) -> Self {
+        PriceBookItemDTO {
+            id: item.id,
+            unit_cost: item.unit_cost(),
+            code: item.code,
+            description: item.description,
+            unit: item.unit,
+            costs: item.costs,
+            effective_from: item.effective_from,
+            effective_to: item.effective_to,
+        }
+    }
+}
@@ -1,7 +1,10 @@
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+use crate::clock::{Clock, SystemClock};
+use crate::entity::line_item::LineItem;
 use crate::entity::section::Section;
+use crate::id_generator::{IdGenerator, RandomIdGenerator};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct SectionDTO {
@@ -11,10 +14,13 @@ pub struct SectionDTO {
 
     pub description: Option<String>,
     pub sections: Option<Vec<Section>>,
+    pub line_items: Option<Vec<LineItem>>,
     created_at: Option<DateTime<Utc>>,
     updated_at: Option<DateTime<Utc>>,
 
     pub estimate_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub position: u32,
 }
 
 impl PartialEq for Section {
@@ -24,21 +30,31 @@ impl PartialEq for Section {
             && self.name == other.name
             && self.description == other.description
             && self.sections == other.sections
+            && self.line_items == other.line_items
             && self.created_at == other.created_at
             && self.updated_at == other.updated_at
             && self.estimate_id == other.estimate_id
+            && self.parent_id == other.parent_id
+            && self.position == other.position
     }
 }
 
 impl SectionDTO {
     pub fn new(name: String, code: String) -> Self {
+        SectionDTO::new_with(name, code, &RandomIdGenerator)
+    }
+
+    pub fn new_with(name: String, code: String, ids: &dyn IdGenerator) -> Self {
         SectionDTO {
-            id: Uuid::new_v4(),
-            code: code,
-            name: name,
+            id: ids.next_id(),
+            code,
+            name,
             description: None,
             sections: None,
+            line_items: None,
             estimate_id: None,
+            parent_id: None,
+            position: 0,
             created_at: None,
             updated_at: None,
         }
@@ -65,30 +81,44 @@ impl From<Section> for SectionDTO {
             name: section.name,
             description: Some(section.description),
             sections: Some(section.sections),
+            line_items: Some(section.line_items),
             estimate_id: section.estimate_id,
+            parent_id: section.parent_id,
+            position: section.position,
             created_at: Some(section.created_at),
             updated_at: Some(section.updated_at),
         }
     }
 }
 
-impl From<SectionDTO> for Section {
-    fn from(section_dto: SectionDTO) -> Self {
+impl SectionDTO {
+    // Missing timestamps are filled in from `clock`; `Section::from` uses the system clock.
+    pub fn into_entity(self, clock: &dyn Clock) -> Section {
+        let section_dto = self;
+        let now = clock.now();
         Section {
             id: section_dto.id,
             code: section_dto.code,
             name: section_dto.name,
             description: section_dto.description.unwrap_or("".to_string()),
             sections: section_dto.sections.unwrap_or(vec![]),
-            created_at: section_dto.created_at.unwrap_or(chrono::Utc::now()),
-            updated_at: section_dto.created_at.unwrap_or(chrono::Utc::now()),
-            estimate_id: None,
+            line_items: section_dto.line_items.unwrap_or(vec![]),
+            created_at: section_dto.created_at.unwrap_or(now),
+            updated_at: section_dto.updated_at.unwrap_or(now),
+            estimate_id: section_dto.estimate_id,
+            parent_id: section_dto.parent_id,
+            position: section_dto.position,
         }
     }
 }
 
-#[cfg(test)]
+impl From<SectionDTO> for Section {
+    fn from(section_dto: SectionDTO) -> Self {
+        section_dto.into_entity(&SystemClock)
+    }
+}
 
+#[cfg(test)]
 mod tests {
     use super::*;
 
@@ -101,9 +131,12 @@ mod tests {
             name: "Name".to_string(),
             description: "Description".to_string(),
             sections: vec![],
+            line_items: vec![],
             created_at: chrono::Utc::now(),
             updated_at: chrono::Utc::now(),
             estimate_id: None,
+            parent_id: None,
+            position: 0,
         };
 
         // Convert the Section object to a SectionDTO
@@ -132,4 +165,20 @@ mod tests {
         assert_eq!(section_dto.code, section.code);
         assert_eq!(section_dto.name, section.name);
     }
+
+    #[test]
+    fn test_dto_to_section_keeps_tree_links() {
+        let estimate_id = Uuid::new_v4();
+        let parent_id = Uuid::new_v4();
+        let mut section_dto = SectionDTO::new("Framing".to_string(), "06-100".to_string());
+        section_dto.estimate_id = Some(estimate_id);
+        section_dto.parent_id = Some(parent_id);
+        section_dto.position = 3;
+
+        let section = Section::from(section_dto);
+
+        assert_eq!(section.estimate_id, Some(estimate_id));
+        assert_eq!(section.parent_id, Some(parent_id));
+        assert_eq!(section.position, 3);
+    }
 }
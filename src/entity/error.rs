@@ -1,4 +1,5 @@
 //entity/error.rs
+use super::unit::Unit;
 use crate::error::Error as MainError;
 
 use derive_more::{Display, From};
@@ -16,16 +17,22 @@ pub enum Error {
         entity: &'static str,
         message: String,
     },
+    #[display(
+        "Cannot convert {} ({}) to {} ({})",
+        from,
+        from.dimension(),
+        to,
+        to.dimension()
+    )]
+    IncompatibleUnits { from: Unit, to: Unit },
+    #[display("Unknown unit of measure: {}", unit)]
+    UnknownUnit { unit: String },
 }
 
 impl std::error::Error for Error {}
 
 impl From<Error> for MainError {
     fn from(error: Error) -> Self {
-        match error {
-            Error::ValidationError { entity, message } => {
-                MainError::EntityError(Error::ValidationError { entity, message }.into())
-            }
-        }
+        MainError::EntityError(error)
     }
 }
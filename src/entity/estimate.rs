@@ -2,14 +2,29 @@
 
 use crate::result::*;
 
+use crate::metrics;
+
 use super::error::Error;
 use super::error::Error as EntityError;
+use super::limits::ValidationLimits;
+use super::markup::Markup;
 use super::traits::Identifiable;
 
 use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display, Serialize, Deserialize)]
+pub enum EstimateStatus {
+    #[default]
+    Draft,
+    Submitted,
+    Accepted,
+    Rejected,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Estimate {
     pub id: Uuid,
     pub name: String,
@@ -17,6 +32,13 @@ pub struct Estimate {
     pub price: f64,
     pub location: String,
     pub price_guess: f64,
+    pub status: EstimateStatus,
+    // Templates are starting points for new estimates rather than bids themselves.
+    pub is_template: bool,
+    // Each one is a percentage of the direct cost, the sum of every line item.
+    pub markups: Vec<Markup>,
+    // Work the bid does not cover, listed on the proposal.
+    pub exclusions: Vec<String>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -30,28 +52,38 @@ impl Identifiable for Estimate {
 // region:    --- Basic Estimate Validation Rules
 
 impl Estimate {
-    pub fn is_valid_name(name: &str) -> Result<()> {
-        let validated = name.len() >= 50 && name.len() <= 100;
+    pub fn is_valid_name(name: &str, limits: &ValidationLimits) -> Result<()> {
+        let validated =
+            name.len() >= limits.name_min_length && name.len() <= limits.name_max_length;
 
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Estimate", "is_valid_name");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Estimate",
-                message: "Name must be between 3 and 100 characters".into(),
+                message: format!(
+                    "Name must be between {} and {} characters",
+                    limits.name_min_length, limits.name_max_length
+                ),
             }))
         }
     }
 
-    pub fn is_valid_description(description: &str) -> Result<()> {
-        let validated = description.len() >= 1 && description.len() <= 1000;
+    pub fn is_valid_description(description: &str, limits: &ValidationLimits) -> Result<()> {
+        let validated = description.len() >= limits.description_min_length
+            && description.len() <= limits.description_max_length;
 
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Estimate", "is_valid_description");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Estimate",
-                message: "Description must be between 1 and 1000 characters".into(),
+                message: format!(
+                    "Description must be between {} and {} characters",
+                    limits.description_min_length, limits.description_max_length
+                ),
             }))
         }
     }
@@ -62,6 +94,7 @@ impl Estimate {
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Estimate", "is_valid_price");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Estimate",
                 message: "Price must be greater than 0".into(),
@@ -69,15 +102,38 @@ impl Estimate {
         }
     }
 
-    pub fn is_valid_location(location: &str) -> Result<()> {
-        let validated = location.len() >= 3 && location.len() <= 100;
+    pub fn is_valid_location(location: &str, limits: &ValidationLimits) -> Result<()> {
+        let validated = location.len() >= limits.location_min_length
+            && location.len() <= limits.location_max_length;
 
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Estimate", "is_valid_location");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Estimate",
-                message: "Location must be between 3 and 100 characters".into(),
+                message: format!(
+                    "Location must be between {} and {} characters",
+                    limits.location_min_length, limits.location_max_length
+                ),
+            }))
+        }
+    }
+
+    pub fn is_valid_exclusion(exclusion: &str, limits: &ValidationLimits) -> Result<()> {
+        let validated =
+            !exclusion.trim().is_empty() && exclusion.len() <= limits.exclusion_max_length;
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("Estimate", "is_valid_exclusion");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "Estimate",
+                message: format!(
+                    "Exclusion must be between 1 and {} characters",
+                    limits.exclusion_max_length
+                ),
             }))
         }
     }
@@ -88,6 +144,7 @@ impl Estimate {
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Estimate", "is_valid_price_guess");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Estimate",
                 message: "Price guess must be greater than 0".into(),
@@ -104,28 +161,40 @@ mod tests {
 
     #[test]
     fn test_valid_name() {
+        let limits = ValidationLimits::default();
         // Test a valid name
-        assert!(Estimate::is_valid_name("Valid Name").is_ok());
+        assert!(Estimate::is_valid_name("Valid Name", &limits).is_ok());
 
         // Test a name shorter than 3 characters
-        assert!(Estimate::is_valid_name("A").is_err());
+        assert!(Estimate::is_valid_name("A", &limits).is_err());
 
         // Test a name longer than 100 characters
         let long_name = "a".repeat(101);
-        assert!(Estimate::is_valid_name(&long_name).is_err());
+        assert!(Estimate::is_valid_name(&long_name, &limits).is_err());
     }
 
     #[test]
     fn test_valid_description() {
+        let limits = ValidationLimits::default();
         // Test a valid description
-        assert!(Estimate::is_valid_description("Valid Description").is_ok());
+        assert!(Estimate::is_valid_description("Valid Description", &limits).is_ok());
 
         // Test a description shorter than 10 characters
-        assert!(Estimate::is_valid_description("Short").is_err());
+        assert!(Estimate::is_valid_description("Short", &limits).is_err());
 
         // Test a description longer than 1000 characters
         let long_description = "a".repeat(1001);
-        assert!(Estimate::is_valid_description(&long_description).is_err());
+        assert!(Estimate::is_valid_description(&long_description, &limits).is_err());
+    }
+
+    #[test]
+    fn test_name_and_description_bounds() {
+        let limits = ValidationLimits::default();
+        assert!(Estimate::is_valid_name("Gym", &limits).is_ok());
+        assert!(Estimate::is_valid_name("Go", &limits).is_err());
+        assert!(Estimate::is_valid_name(&"a".repeat(100), &limits).is_ok());
+        assert!(Estimate::is_valid_description("Roof patch", &limits).is_ok());
+        assert!(Estimate::is_valid_description("Re-roofed", &limits).is_err());
     }
 
     #[test]
@@ -142,15 +211,30 @@ mod tests {
 
     #[test]
     fn test_valid_location() {
+        let limits = ValidationLimits::default();
         // Test a valid location
-        assert!(Estimate::is_valid_location("Valid Location").is_ok());
+        assert!(Estimate::is_valid_location("Valid Location", &limits).is_ok());
 
         // Test a location shorter than 3 characters
-        assert!(Estimate::is_valid_location("A").is_err());
+        assert!(Estimate::is_valid_location("A", &limits).is_err());
 
         // Test a location longer than 100 characters
         let long_location = "a".repeat(101);
-        assert!(Estimate::is_valid_location(&long_location).is_err());
+        assert!(Estimate::is_valid_location(&long_location, &limits).is_err());
+    }
+
+    #[test]
+    fn test_valid_exclusion() {
+        let limits = ValidationLimits::default();
+        // Test a valid exclusion
+        assert!(Estimate::is_valid_exclusion("Permits and fees", &limits).is_ok());
+
+        // Test a blank exclusion
+        assert!(Estimate::is_valid_exclusion("   ", &limits).is_err());
+
+        // Test an exclusion longer than 500 characters
+        let long_exclusion = "a".repeat(501);
+        assert!(Estimate::is_valid_exclusion(&long_exclusion, &limits).is_err());
     }
 
     #[test]
@@ -0,0 +1,72 @@
+// entity/limits.rs
+
+// Bounds used by the entity validation rules. The defaults are the limits the rules have always
+// enforced; each service carries the limits it was configured with and passes them to the rules.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ValidationLimits {
+    pub name_min_length: usize,
+    pub name_max_length: usize,
+    pub description_min_length: usize,
+    pub description_max_length: usize,
+    pub location_min_length: usize,
+    pub location_max_length: usize,
+    pub exclusion_max_length: usize,
+    pub max_sections: usize,
+}
+
+impl Default for ValidationLimits {
+    fn default() -> Self {
+        ValidationLimits {
+            name_min_length: 3,
+            name_max_length: 100,
+            description_min_length: 10,
+            description_max_length: 1000,
+            location_min_length: 3,
+            location_max_length: 100,
+            exclusion_max_length: 500,
+            max_sections: 100,
+        }
+    }
+}
+
+impl ValidationLimits {
+    // Describes every inconsistent bound, e.g. a minimum above its maximum.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let ranges = [
+            ("name", self.name_min_length, self.name_max_length),
+            (
+                "description",
+                self.description_min_length,
+                self.description_max_length,
+            ),
+            (
+                "location",
+                self.location_min_length,
+                self.location_max_length,
+            ),
+        ];
+        for (field, min, max) in ranges {
+            if min == 0 {
+                problems.push(format!("{}_min_length must be at least 1", field));
+            }
+            if min > max {
+                problems.push(format!(
+                    "{}_min_length ({}) must not exceed {}_max_length ({})",
+                    field, min, field, max
+                ));
+            }
+        }
+        if self.exclusion_max_length == 0 {
+            problems.push("exclusion_max_length must be at least 1".into());
+        }
+        if self.max_sections == 0 {
+            problems.push("max_sections must be at least 1".into());
+        }
+        problems
+    }
+}
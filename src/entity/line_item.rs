@@ -0,0 +1,200 @@
+// entity/line_item.rs
+
+use super::error::Error as EntityError;
+use crate::result::*;
+
+use crate::metrics;
+
+use super::traits::Identifiable;
+use super::unit::{Quantity, Unit};
+use crate::clock::{Clock, SystemClock};
+use crate::id_generator::{IdGenerator, RandomIdGenerator};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// A priced quantity inside a section, e.g. 120 SF of drywall at 2.35 per SF.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LineItem {
+    pub id: Uuid,
+    pub description: String,
+    pub quantity: f64,
+    pub unit: Unit,
+    pub unit_cost: f64,
+    // The price book item the cost came from, for re-pricing; None when typed in.
+    pub price_reference: Option<PriceReference>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+// Re-pricing looks the code up again; `item_id` is the version that set the current cost.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriceReference {
+    pub code: String,
+    pub item_id: Uuid,
+}
+
+impl Identifiable for LineItem {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl LineItem {
+    pub fn new(description: String, quantity: f64, unit: Unit, unit_cost: f64) -> Self {
+        LineItem::new_with(
+            description,
+            quantity,
+            unit,
+            unit_cost,
+            &SystemClock,
+            &RandomIdGenerator,
+        )
+    }
+
+    pub fn new_with(
+        description: String,
+        quantity: f64,
+        unit: Unit,
+        unit_cost: f64,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+    ) -> Self {
+        let now = clock.now();
+        LineItem {
+            id: ids.next_id(),
+            description,
+            quantity,
+            unit,
+            unit_cost,
+            price_reference: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn total(&self) -> f64 {
+        self.quantity * self.unit_cost
+    }
+
+    pub fn measured(&self) -> Quantity {
+        Quantity::new(self.quantity, self.unit)
+    }
+
+    // Restates the item in `unit` without changing its total: the quantity is converted and the
+    // unit cost scaled the other way. Fails if `unit` measures something else.
+    pub fn convert_to(&mut self, unit: Unit) -> Result<()> {
+        let factor = self.unit.conversion_factor(unit)?;
+        self.quantity *= factor;
+        self.unit_cost /= factor;
+        self.unit = unit;
+        Ok(())
+    }
+}
+
+// region:    --- Basic LineItem Validation Rules
+
+impl LineItem {
+    pub fn is_valid_description(description: &str) -> Result<()> {
+        let validated = !description.is_empty() && description.len() <= 1000;
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("LineItem", "is_valid_description");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "LineItem",
+                message: "Description must be between 1 and 1000 characters".into(),
+            }))
+        }
+    }
+
+    pub fn is_valid_quantity(quantity: f64) -> Result<()> {
+        let validated = quantity.is_finite() && quantity >= 0.0;
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("LineItem", "is_valid_quantity");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "LineItem",
+                message: "Quantity must be 0 or greater".into(),
+            }))
+        }
+    }
+
+    // Parses a unit as entered, e.g. "sq ft", into the catalog's unit.
+    pub fn is_valid_unit(unit: &str) -> Result<Unit> {
+        match unit.parse::<Unit>() {
+            Ok(unit) => Ok(unit),
+            Err(_) => {
+                metrics::record_validation_failure("LineItem", "is_valid_unit");
+                Err(Box::new(EntityError::ValidationError {
+                    entity: "LineItem",
+                    message: format!("Unit {:?} is not a known unit of measure", unit),
+                }))
+            }
+        }
+    }
+
+    pub fn is_valid_unit_cost(unit_cost: f64) -> Result<()> {
+        let validated = unit_cost.is_finite() && unit_cost >= 0.0;
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("LineItem", "is_valid_unit_cost");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "LineItem",
+                message: "Unit cost must be 0 or greater".into(),
+            }))
+        }
+    }
+}
+
+// endregion: --- Basic LineItem Validation Rules
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_total() {
+        let item = LineItem::new("Drywall".to_string(), 120.0, Unit::SquareFoot, 2.5);
+        assert_eq!(item.total(), 300.0);
+    }
+
+    #[test]
+    fn test_convert_to_keeps_total() {
+        let mut item = LineItem::new("Carpet".to_string(), 90.0, Unit::SquareFoot, 3.0);
+        item.convert_to(Unit::SquareYard).unwrap();
+        assert_eq!(item.unit, Unit::SquareYard);
+        assert!((item.quantity - 10.0).abs() < 1e-9);
+        assert!((item.total() - 270.0).abs() < 1e-9);
+        assert!(item.convert_to(Unit::CubicYard).is_err());
+        assert_eq!(item.unit, Unit::SquareYard);
+    }
+
+    #[test]
+    fn test_valid_quantity() {
+        assert!(LineItem::is_valid_quantity(0.0).is_ok());
+        assert!(LineItem::is_valid_quantity(12.5).is_ok());
+        assert!(LineItem::is_valid_quantity(-1.0).is_err());
+        assert!(LineItem::is_valid_quantity(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_valid_unit() {
+        assert_eq!(LineItem::is_valid_unit("SF").unwrap(), Unit::SquareFoot);
+        assert_eq!(LineItem::is_valid_unit("sq yd").unwrap(), Unit::SquareYard);
+        assert!(LineItem::is_valid_unit("").is_err());
+        assert!(LineItem::is_valid_unit("bundle").is_err());
+    }
+
+    #[test]
+    fn test_valid_unit_cost() {
+        assert!(LineItem::is_valid_unit_cost(2.35).is_ok());
+        assert!(LineItem::is_valid_unit_cost(-0.01).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+// entity/markup.rs
+
+use super::error::Error as EntityError;
+use crate::result::*;
+
+use crate::metrics;
+
+use serde::{Deserialize, Serialize};
+
+// A percentage added on top of the direct cost of an estimate, e.g. overhead or profit.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Markup {
+    pub name: String,
+    // Percent of the estimate subtotal, so 10.0 means 10%.
+    pub rate: f64,
+}
+
+impl Markup {
+    pub fn new(name: String, rate: f64) -> Self {
+        Markup { name, rate }
+    }
+
+    pub fn amount(&self, subtotal: f64) -> f64 {
+        subtotal * self.rate / 100.0
+    }
+}
+
+// region:    --- Basic Markup Validation Rules
+
+impl Markup {
+    pub fn is_valid_name(name: &str) -> Result<()> {
+        let validated = !name.is_empty() && name.len() <= 100;
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("Markup", "is_valid_name");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "Markup",
+                message: "Name must be between 1 and 100 characters".into(),
+            }))
+        }
+    }
+
+    pub fn is_valid_rate(rate: f64) -> Result<()> {
+        let validated = rate.is_finite() && (0.0..=100.0).contains(&rate);
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("Markup", "is_valid_rate");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "Markup",
+                message: "Rate must be between 0 and 100 percent".into(),
+            }))
+        }
+    }
+}
+
+// endregion: --- Basic Markup Validation Rules
@@ -2,5 +2,10 @@
 
 pub mod error;
 pub mod estimate;
+pub mod limits;
+pub mod line_item;
+pub mod markup;
+pub mod price_book_item;
 pub mod section;
 pub mod traits;
+pub mod unit;
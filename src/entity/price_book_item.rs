@@ -0,0 +1,272 @@
+// entity/price_book_item.rs
+
+use super::error::Error as EntityError;
+use crate::result::*;
+
+use crate::metrics;
+
+use super::traits::Identifiable;
+use super::unit::Unit;
+use crate::clock::{Clock, SystemClock};
+use crate::id_generator::{IdGenerator, RandomIdGenerator};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+// What one unit of an item costs, split the way estimators carry it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct UnitCosts {
+    pub material: f64,
+    pub labor: f64,
+    pub equipment: f64,
+}
+
+impl UnitCosts {
+    pub fn total(&self) -> f64 {
+        self.material + self.labor + self.equipment
+    }
+}
+
+// A reusable priced item, e.g. "09-2116 5/8in gypsum board, SF". A code can have several
+// versions as prices change; each covers `effective_from` up to and including `effective_to`,
+// or onwards when that is None.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PriceBookItem {
+    pub id: Uuid,
+    pub code: String,
+    pub description: String,
+    pub unit: Unit,
+    pub costs: UnitCosts,
+    pub effective_from: NaiveDate,
+    pub effective_to: Option<NaiveDate>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Identifiable for PriceBookItem {
+    fn id(&self) -> Uuid {
+        self.id
+    }
+}
+
+impl PriceBookItem {
+    pub fn new(
+        code: String,
+        description: String,
+        unit: Unit,
+        costs: UnitCosts,
+        effective_from: NaiveDate,
+    ) -> Self {
+        PriceBookItem::new_with(
+            code,
+            description,
+            unit,
+            costs,
+            effective_from,
+            &SystemClock,
+            &RandomIdGenerator,
+        )
+    }
+
+    pub fn new_with(
+        code: String,
+        description: String,
+        unit: Unit,
+        costs: UnitCosts,
+        effective_from: NaiveDate,
+        clock: &dyn Clock,
+        ids: &dyn IdGenerator,
+    ) -> Self {
+        let now = clock.now();
+        PriceBookItem {
+            id: ids.next_id(),
+            code,
+            description,
+            unit,
+            costs,
+            effective_from,
+            effective_to: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    pub fn unit_cost(&self) -> f64 {
+        self.costs.total()
+    }
+
+    // The cost of one `unit`, e.g. per SY for an item priced per SF. Fails if `unit` measures
+    // something else.
+    pub fn unit_cost_in(&self, unit: Unit) -> Result<f64> {
+        Ok(self.unit_cost() * unit.conversion_factor(self.unit)?)
+    }
+
+    pub fn is_effective_on(&self, date: NaiveDate) -> bool {
+        self.effective_from <= date && self.effective_to.is_none_or(|to| date <= to)
+    }
+
+    // Whether both are versions of the same code covering a common day.
+    pub fn overlaps(&self, other: &PriceBookItem) -> bool {
+        self.code.eq_ignore_ascii_case(&other.code)
+            && self
+                .effective_to
+                .is_none_or(|to| other.effective_from <= to)
+            && other
+                .effective_to
+                .is_none_or(|to| self.effective_from <= to)
+    }
+}
+
+// What `search_price_book` matches on. Every word of `text` must appear in the code or the
+// description, ignoring case; the other criteria apply when set.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PriceBookSearch {
+    pub text: String,
+    pub unit: Option<Unit>,
+    pub effective_on: Option<NaiveDate>,
+}
+
+impl PriceBookSearch {
+    pub fn matches(&self, item: &PriceBookItem) -> bool {
+        let code = item.code.to_lowercase();
+        let description = item.description.to_lowercase();
+        self.text
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .all(|word| code.contains(&word) || description.contains(&word))
+            && self.unit.is_none_or(|unit| item.unit == unit)
+            && self
+                .effective_on
+                .is_none_or(|date| item.is_effective_on(date))
+    }
+}
+
+// region:    --- Basic PriceBookItem Validation Rules
+
+impl PriceBookItem {
+    pub fn is_valid_code(code: &str) -> Result<()> {
+        let validated = !code.trim().is_empty() && code.len() <= 30;
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("PriceBookItem", "is_valid_code");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "PriceBookItem",
+                message: "Code must be between 1 and 30 characters".into(),
+            }))
+        }
+    }
+
+    pub fn is_valid_description(description: &str) -> Result<()> {
+        let validated = !description.is_empty() && description.len() <= 1000;
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("PriceBookItem", "is_valid_description");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "PriceBookItem",
+                message: "Description must be between 1 and 1000 characters".into(),
+            }))
+        }
+    }
+
+    pub fn is_valid_costs(costs: &UnitCosts) -> Result<()> {
+        let validated = [costs.material, costs.labor, costs.equipment]
+            .iter()
+            .all(|cost| cost.is_finite() && *cost >= 0.0);
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("PriceBookItem", "is_valid_costs");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "PriceBookItem",
+                message: "Material, labor and equipment costs must be 0 or greater".into(),
+            }))
+        }
+    }
+
+    pub fn is_valid_effective_dates(from: NaiveDate, to: Option<NaiveDate>) -> Result<()> {
+        let validated = to.is_none_or(|to| from <= to);
+
+        if validated {
+            Ok(())
+        } else {
+            metrics::record_validation_failure("PriceBookItem", "is_valid_effective_dates");
+            Err(Box::new(EntityError::ValidationError {
+                entity: "PriceBookItem",
+                message: "Effective dates must not end before they start".into(),
+            }))
+        }
+    }
+}
+
+// endregion: --- Basic PriceBookItem Validation Rules
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn board(from: NaiveDate, to: Option<NaiveDate>) -> PriceBookItem {
+        let costs = UnitCosts {
+            material: 0.55,
+            labor: 1.2,
+            equipment: 0.05,
+        };
+        let mut item = PriceBookItem::new(
+            "09-2116".to_string(),
+            "5/8in type X gypsum board".to_string(),
+            Unit::SquareFoot,
+            costs,
+            from,
+        );
+        item.effective_to = to;
+        item
+    }
+
+    #[test]
+    fn test_effective_dates() {
+        let item = board(date(1, 1), Some(date(6, 30)));
+        assert!((item.unit_cost() - 1.8).abs() < 1e-9);
+        assert!((item.unit_cost_in(Unit::SquareYard).unwrap() - 16.2).abs() < 1e-9);
+        assert!(item.unit_cost_in(Unit::Each).is_err());
+        assert!(item.is_effective_on(date(1, 1)));
+        assert!(item.is_effective_on(date(6, 30)));
+        assert!(!item.is_effective_on(date(7, 1)));
+
+        assert!(item.overlaps(&board(date(6, 30), None)));
+        assert!(!item.overlaps(&board(date(7, 1), None)));
+        assert!(PriceBookItem::is_valid_effective_dates(date(2, 1), Some(date(1, 1))).is_err());
+    }
+
+    #[test]
+    fn test_search_matches_every_word() {
+        let item = board(date(1, 1), None);
+        let search = |text: &str| PriceBookSearch {
+            text: text.to_string(),
+            ..PriceBookSearch::default()
+        };
+        assert!(search("").matches(&item));
+        assert!(search("Gypsum 5/8").matches(&item));
+        assert!(search("09-21 board").matches(&item));
+        assert!(!search("gypsum plaster").matches(&item));
+
+        let by_unit = PriceBookSearch {
+            unit: Some(Unit::SquareYard),
+            ..PriceBookSearch::default()
+        };
+        assert!(!by_unit.matches(&item));
+        let by_date = PriceBookSearch {
+            effective_on: Some(date(1, 1) - chrono::Days::new(1)),
+            ..PriceBookSearch::default()
+        };
+        assert!(!by_date.matches(&item));
+    }
+}
@@ -1,24 +1,34 @@
 //entity/section.rs
 
 use super::error::Error as EntityError;
+use super::limits::ValidationLimits;
 use crate::result::*;
 
+use crate::metrics;
+
+use super::line_item::LineItem;
 use super::traits::Identifiable;
+use super::unit::{Quantity, Unit};
 
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Section {
     pub id: Uuid,
     pub code: String,
     pub name: String,
     pub description: String,
     pub sections: Vec<Section>,
+    pub line_items: Vec<LineItem>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 
     pub estimate_id: Option<Uuid>,
+    // Sections are stored flat; the tree is rebuilt from `parent_id`, siblings ordered by `position`.
+    pub parent_id: Option<Uuid>,
+    pub position: u32,
 }
 
 impl Identifiable for Section {
@@ -27,54 +37,86 @@ impl Identifiable for Section {
     }
 }
 
+impl Section {
+    // Sum of this section's own line items; sub-sections stored separately are not included.
+    pub fn line_items_total(&self) -> f64 {
+        // Folding from 0.0 keeps an empty section at 0.0 rather than the -0.0 `sum` yields.
+        self.line_items
+            .iter()
+            .fold(0.0, |total, item| total + item.total())
+    }
+
+    // How much of `unit` this section's own line items add up to, e.g. all the SF of flooring
+    // whether entered in SF, SY or M2. Fails if any item measures something else.
+    pub fn total_quantity(&self, unit: Unit) -> Result<Quantity> {
+        self.line_items
+            .iter()
+            .try_fold(Quantity::new(0.0, unit), |total, item| {
+                total.plus(item.measured())
+            })
+    }
+}
+
 // region:    --- Basic Section Validation Rules
 
 impl Section {
-    pub fn is_valid_name(name: &str) -> Result<()> {
-        let validated = name.len() >= 3 && name.len() <= 100;
+    pub fn is_valid_name(name: &str, limits: &ValidationLimits) -> Result<()> {
+        let validated =
+            name.len() >= limits.name_min_length && name.len() <= limits.name_max_length;
 
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Section", "is_valid_name");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Section",
-                message: "Name must be between 3 and 100 characters".into(),
+                message: format!(
+                    "Name must be between {} and {} characters",
+                    limits.name_min_length, limits.name_max_length
+                ),
             }))
         }
     }
 
-    pub fn is_valid_description(description: &str) -> Result<()> {
-        let validated = description.len() >= 10 && description.len() <= 1000;
+    pub fn is_valid_description(description: &str, limits: &ValidationLimits) -> Result<()> {
+        let validated = description.len() >= limits.description_min_length
+            && description.len() <= limits.description_max_length;
 
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Section", "is_valid_description");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Section",
-                message: "Description must be between 10 and 1000 characters".into(),
+                message: format!(
+                    "Description must be between {} and {} characters",
+                    limits.description_min_length, limits.description_max_length
+                ),
             }))
         }
     }
 
-    pub fn is_valid_sections(sections: &Vec<Section>) -> Result<()> {
-        let validated = sections.len() <= 100;
+    pub fn is_valid_sections(sections: &[Section], limits: &ValidationLimits) -> Result<()> {
+        let validated = sections.len() <= limits.max_sections;
 
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Section", "is_valid_sections");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Section",
-                message: "Sections must be less than 100".into(),
+                message: format!("Sections must be less than {}", limits.max_sections),
             }))
         }
     }
 
     pub fn is_valid_project_id(project_id: &Uuid) -> Result<()> {
-        let validated = project_id.to_string().len() > 0;
+        let validated = !project_id.is_nil();
 
         if validated {
             Ok(())
         } else {
+            metrics::record_validation_failure("Section", "is_valid_project_id");
             Err(Box::new(EntityError::ValidationError {
                 entity: "Section",
                 message: "Project ID must be a valid UUID".into(),
@@ -84,3 +126,14 @@ impl Section {
 }
 
 // endregion: --- Basic Section Validation Rules
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_project_id() {
+        assert!(Section::is_valid_project_id(&Uuid::new_v4()).is_ok());
+        assert!(Section::is_valid_project_id(&Uuid::nil()).is_err());
+    }
+}
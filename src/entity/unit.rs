@@ -0,0 +1,451 @@
+// entity/unit.rs
+
+// Units of measure for line item quantities, grouped by dimension. Every unit knows its factor to
+// the dimension's base unit (metre, square metre, cubic metre, kilogram, each, hour), so
+// quantities convert between units of the same dimension and refuse to convert across them:
+// square feet never become cubic yards. Units are stored and shown by their code, e.g. "SF";
+// parsing also accepts the common spellings in `aliases`.
+
+use super::error::Error as EntityError;
+use crate::result::*;
+
+use std::fmt;
+use std::str::FromStr;
+
+use derive_more::Display;
+use serde::Deserialize;
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Display)]
+pub enum Dimension {
+    #[display("length")]
+    Length,
+    #[display("area")]
+    Area,
+    #[display("volume")]
+    Volume,
+    #[display("mass")]
+    Mass,
+    #[display("count")]
+    Count,
+    #[display("time")]
+    Time,
+}
+
+// Which family of units to show quantities in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, Deserialize, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    #[display("imperial")]
+    Imperial,
+    #[display("metric")]
+    Metric,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, SerializeDisplay, DeserializeFromStr)]
+pub enum Unit {
+    // Length
+    Inch,
+    LinearFoot,
+    Yard,
+    Millimetre,
+    Metre,
+    // Area
+    SquareFoot,
+    SquareYard,
+    // Roofing square, 100 SF.
+    Square,
+    Acre,
+    SquareMetre,
+    Hectare,
+    // Volume
+    CubicFoot,
+    CubicYard,
+    Gallon,
+    Litre,
+    CubicMetre,
+    // Mass
+    Pound,
+    // Short ton, 2000 LB.
+    Ton,
+    Kilogram,
+    // Metric ton, 1000 KG.
+    Tonne,
+    // Count
+    Each,
+    // Time
+    Minute,
+    Hour,
+}
+
+// Every unit, in the order pickers list them.
+pub const UNITS: &[Unit] = &[
+    Unit::Inch,
+    Unit::LinearFoot,
+    Unit::Yard,
+    Unit::Millimetre,
+    Unit::Metre,
+    Unit::SquareFoot,
+    Unit::SquareYard,
+    Unit::Square,
+    Unit::Acre,
+    Unit::SquareMetre,
+    Unit::Hectare,
+    Unit::CubicFoot,
+    Unit::CubicYard,
+    Unit::Gallon,
+    Unit::Litre,
+    Unit::CubicMetre,
+    Unit::Pound,
+    Unit::Ton,
+    Unit::Kilogram,
+    Unit::Tonne,
+    Unit::Each,
+    Unit::Minute,
+    Unit::Hour,
+];
+
+impl Unit {
+    pub fn code(&self) -> &'static str {
+        match self {
+            Unit::Inch => "IN",
+            Unit::LinearFoot => "LF",
+            Unit::Yard => "YD",
+            Unit::Millimetre => "MM",
+            Unit::Metre => "M",
+            Unit::SquareFoot => "SF",
+            Unit::SquareYard => "SY",
+            Unit::Square => "SQ",
+            Unit::Acre => "AC",
+            Unit::SquareMetre => "M2",
+            Unit::Hectare => "HA",
+            Unit::CubicFoot => "CF",
+            Unit::CubicYard => "CY",
+            Unit::Gallon => "GAL",
+            Unit::Litre => "L",
+            Unit::CubicMetre => "M3",
+            Unit::Pound => "LB",
+            Unit::Ton => "TON",
+            Unit::Kilogram => "KG",
+            Unit::Tonne => "MT",
+            Unit::Each => "EA",
+            Unit::Minute => "MIN",
+            Unit::Hour => "HR",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Unit::Inch => "inch",
+            Unit::LinearFoot => "linear foot",
+            Unit::Yard => "yard",
+            Unit::Millimetre => "millimetre",
+            Unit::Metre => "metre",
+            Unit::SquareFoot => "square foot",
+            Unit::SquareYard => "square yard",
+            Unit::Square => "roofing square",
+            Unit::Acre => "acre",
+            Unit::SquareMetre => "square metre",
+            Unit::Hectare => "hectare",
+            Unit::CubicFoot => "cubic foot",
+            Unit::CubicYard => "cubic yard",
+            Unit::Gallon => "US gallon",
+            Unit::Litre => "litre",
+            Unit::CubicMetre => "cubic metre",
+            Unit::Pound => "pound",
+            Unit::Ton => "short ton",
+            Unit::Kilogram => "kilogram",
+            Unit::Tonne => "metric ton",
+            Unit::Each => "each",
+            Unit::Minute => "minute",
+            Unit::Hour => "hour",
+        }
+    }
+
+    // Other spellings accepted when parsing, besides the code and the name.
+    fn aliases(&self) -> &'static [&'static str] {
+        match self {
+            Unit::Inch => &["in.", "inches", "\""],
+            Unit::LinearFoot => &["ft", "feet", "foot", "lin ft", "l.f."],
+            Unit::Yard => &["yards", "yds"],
+            Unit::Millimetre => &["millimeter", "millimetres", "millimeters"],
+            Unit::Metre => &["meter", "metres", "meters", "lm"],
+            Unit::SquareFoot => &["sqft", "sq ft", "sq. ft.", "ft2", "s.f."],
+            Unit::SquareYard => &["sqyd", "sq yd", "yd2"],
+            Unit::Square => &["sqs", "squares"],
+            Unit::Acre => &["acres"],
+            Unit::SquareMetre => &["m²", "sqm", "sq m", "square meter"],
+            Unit::Hectare => &["hectares"],
+            Unit::CubicFoot => &["cuft", "cu ft", "ft3"],
+            Unit::CubicYard => &["cuyd", "cu yd", "yd3"],
+            Unit::Gallon => &["gallons", "gals"],
+            Unit::Litre => &["liter", "litres", "liters", "ltr"],
+            Unit::CubicMetre => &["m³", "cu m", "cubic meter"],
+            Unit::Pound => &["lbs", "pounds", "#"],
+            Unit::Ton => &["tons", "tn"],
+            Unit::Kilogram => &["kgs", "kilograms"],
+            Unit::Tonne => &["tonne", "tonnes"],
+            Unit::Each => &["ea.", "pcs", "pc", "unit", "units"],
+            Unit::Minute => &["mins", "minutes"],
+            Unit::Hour => &["hrs", "hours", "h"],
+        }
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        match self {
+            Unit::Inch | Unit::LinearFoot | Unit::Yard | Unit::Millimetre | Unit::Metre => {
+                Dimension::Length
+            }
+            Unit::SquareFoot
+            | Unit::SquareYard
+            | Unit::Square
+            | Unit::Acre
+            | Unit::SquareMetre
+            | Unit::Hectare => Dimension::Area,
+            Unit::CubicFoot | Unit::CubicYard | Unit::Gallon | Unit::Litre | Unit::CubicMetre => {
+                Dimension::Volume
+            }
+            Unit::Pound | Unit::Ton | Unit::Kilogram | Unit::Tonne => Dimension::Mass,
+            Unit::Each => Dimension::Count,
+            Unit::Minute | Unit::Hour => Dimension::Time,
+        }
+    }
+
+    // How many of the dimension's base unit one of this unit is. The imperial factors are the
+    // exact international definitions.
+    fn factor(&self) -> f64 {
+        const FOOT: f64 = 0.3048;
+        match self {
+            Unit::Inch => FOOT / 12.0,
+            Unit::LinearFoot => FOOT,
+            Unit::Yard => FOOT * 3.0,
+            Unit::Millimetre => 0.001,
+            Unit::Metre => 1.0,
+            Unit::SquareFoot => FOOT * FOOT,
+            Unit::SquareYard => 9.0 * FOOT * FOOT,
+            Unit::Square => 100.0 * FOOT * FOOT,
+            Unit::Acre => 43_560.0 * FOOT * FOOT,
+            Unit::SquareMetre => 1.0,
+            Unit::Hectare => 10_000.0,
+            Unit::CubicFoot => FOOT * FOOT * FOOT,
+            Unit::CubicYard => 27.0 * FOOT * FOOT * FOOT,
+            Unit::Gallon => 0.003_785_411_784,
+            Unit::Litre => 0.001,
+            Unit::CubicMetre => 1.0,
+            Unit::Pound => 0.453_592_37,
+            Unit::Ton => 2000.0 * 0.453_592_37,
+            Unit::Kilogram => 1.0,
+            Unit::Tonne => 1000.0,
+            Unit::Each => 1.0,
+            Unit::Minute => 1.0 / 60.0,
+            Unit::Hour => 1.0,
+        }
+    }
+
+    // None for units both systems share, such as EA and HR.
+    pub fn system(&self) -> Option<UnitSystem> {
+        match self {
+            Unit::Inch
+            | Unit::LinearFoot
+            | Unit::Yard
+            | Unit::SquareFoot
+            | Unit::SquareYard
+            | Unit::Square
+            | Unit::Acre
+            | Unit::CubicFoot
+            | Unit::CubicYard
+            | Unit::Gallon
+            | Unit::Pound
+            | Unit::Ton => Some(UnitSystem::Imperial),
+            Unit::Millimetre
+            | Unit::Metre
+            | Unit::SquareMetre
+            | Unit::Hectare
+            | Unit::Litre
+            | Unit::CubicMetre
+            | Unit::Kilogram
+            | Unit::Tonne => Some(UnitSystem::Metric),
+            Unit::Each | Unit::Minute | Unit::Hour => None,
+        }
+    }
+
+    // The unit of `system` an estimator would use for the same kind of work, e.g. M2 for SF and
+    // SY, CY for M3. Units already in `system`, or in neither, stay as they are.
+    pub fn counterpart(&self, system: UnitSystem) -> Unit {
+        match (system, self) {
+            (UnitSystem::Metric, Unit::Inch) => Unit::Millimetre,
+            (UnitSystem::Metric, Unit::LinearFoot | Unit::Yard) => Unit::Metre,
+            (UnitSystem::Metric, Unit::SquareFoot | Unit::SquareYard | Unit::Square) => {
+                Unit::SquareMetre
+            }
+            (UnitSystem::Metric, Unit::Acre) => Unit::Hectare,
+            (UnitSystem::Metric, Unit::CubicFoot | Unit::CubicYard) => Unit::CubicMetre,
+            (UnitSystem::Metric, Unit::Gallon) => Unit::Litre,
+            (UnitSystem::Metric, Unit::Pound) => Unit::Kilogram,
+            (UnitSystem::Metric, Unit::Ton) => Unit::Tonne,
+            (UnitSystem::Imperial, Unit::Millimetre) => Unit::Inch,
+            (UnitSystem::Imperial, Unit::Metre) => Unit::LinearFoot,
+            (UnitSystem::Imperial, Unit::SquareMetre) => Unit::SquareFoot,
+            (UnitSystem::Imperial, Unit::Hectare) => Unit::Acre,
+            (UnitSystem::Imperial, Unit::CubicMetre) => Unit::CubicYard,
+            (UnitSystem::Imperial, Unit::Litre) => Unit::Gallon,
+            (UnitSystem::Imperial, Unit::Kilogram) => Unit::Pound,
+            (UnitSystem::Imperial, Unit::Tonne) => Unit::Ton,
+            (_, unit) => *unit,
+        }
+    }
+
+    // How many `to` one of this unit is, e.g. 1/9 from SF to SY. Fails across dimensions.
+    pub fn conversion_factor(&self, to: Unit) -> Result<f64> {
+        if self.dimension() != to.dimension() {
+            return Err(Box::new(EntityError::IncompatibleUnits { from: *self, to }));
+        }
+        Ok(self.factor() / to.factor())
+    }
+
+    pub fn convert(&self, value: f64, to: Unit) -> Result<f64> {
+        Ok(value * self.conversion_factor(to)?)
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+// Case-insensitive; accepts the code, the name or an alias.
+impl FromStr for Unit {
+    type Err = EntityError;
+
+    fn from_str(text: &str) -> std::result::Result<Self, Self::Err> {
+        let text = text.trim();
+        UNITS
+            .iter()
+            .find(|unit| {
+                unit.code().eq_ignore_ascii_case(text)
+                    || unit.name().eq_ignore_ascii_case(text)
+                    || unit
+                        .aliases()
+                        .iter()
+                        .any(|alias| alias.eq_ignore_ascii_case(text))
+            })
+            .copied()
+            .ok_or_else(|| EntityError::UnknownUnit {
+                unit: text.to_string(),
+            })
+    }
+}
+
+// An amount in a unit.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quantity {
+    pub value: f64,
+    pub unit: Unit,
+}
+
+impl Quantity {
+    pub fn new(value: f64, unit: Unit) -> Self {
+        Quantity { value, unit }
+    }
+
+    pub fn to(&self, unit: Unit) -> Result<Quantity> {
+        Ok(Quantity::new(self.unit.convert(self.value, unit)?, unit))
+    }
+
+    // The sum in this quantity's unit. Fails if `other` measures something else.
+    pub fn plus(&self, other: Quantity) -> Result<Quantity> {
+        Ok(Quantity::new(
+            self.value + other.to(self.unit)?.value,
+            self.unit,
+        ))
+    }
+
+    // Fails if the counterpart measures something else, which would be a bug in `counterpart`.
+    pub fn in_system(&self, system: UnitSystem) -> Result<Quantity> {
+        self.to(self.unit.counterpart(system))
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.value, self.unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9 * a.abs().max(1.0)
+    }
+
+    #[test]
+    fn test_conversions_within_a_dimension() {
+        assert!(close(
+            Unit::SquareYard.convert(1.0, Unit::SquareFoot).unwrap(),
+            9.0
+        ));
+        assert!(close(
+            Unit::CubicYard.convert(1.0, Unit::CubicFoot).unwrap(),
+            27.0
+        ));
+        assert!(close(Unit::Ton.convert(1.0, Unit::Pound).unwrap(), 2000.0));
+        assert!(close(Unit::Hour.convert(1.5, Unit::Minute).unwrap(), 90.0));
+        assert!(close(
+            Unit::LinearFoot.convert(1000.0, Unit::Metre).unwrap(),
+            304.8
+        ));
+        let there = Unit::SquareFoot.convert(123.4, Unit::SquareMetre).unwrap();
+        assert!(close(
+            Unit::SquareMetre.convert(there, Unit::SquareFoot).unwrap(),
+            123.4
+        ));
+    }
+
+    #[test]
+    fn test_incompatible_units_are_rejected() {
+        let error = Unit::SquareFoot.convert(1.0, Unit::CubicYard).unwrap_err();
+        assert_eq!(error.to_string(), "Cannot convert SF (area) to CY (volume)");
+        let sum = Quantity::new(10.0, Unit::Each).plus(Quantity::new(2.0, Unit::Hour));
+        assert!(sum.is_err());
+        let sum = Quantity::new(1.0, Unit::SquareYard)
+            .plus(Quantity::new(9.0, Unit::SquareFoot))
+            .unwrap();
+        assert!(close(sum.value, 2.0));
+    }
+
+    #[test]
+    fn test_parsing_accepts_codes_names_and_aliases() {
+        assert_eq!("sf".parse::<Unit>().unwrap(), Unit::SquareFoot);
+        assert_eq!(" Sq Ft ".parse::<Unit>().unwrap(), Unit::SquareFoot);
+        assert_eq!("cubic yard".parse::<Unit>().unwrap(), Unit::CubicYard);
+        assert_eq!("tons".parse::<Unit>().unwrap(), Unit::Ton);
+        assert!("bundle".parse::<Unit>().is_err());
+        // "T" means a short ton to US estimators and a metric ton elsewhere, so it is refused.
+        assert!("T".parse::<Unit>().is_err());
+        assert_eq!("mt".parse::<Unit>().unwrap(), Unit::Tonne);
+        assert_eq!("TON".parse::<Unit>().unwrap(), Unit::Ton);
+        for unit in UNITS {
+            assert_eq!(unit.code().parse::<Unit>().unwrap(), *unit);
+        }
+    }
+
+    #[test]
+    fn test_display_system() {
+        let metric = Quantity::new(100.0, Unit::SquareFoot)
+            .in_system(UnitSystem::Metric)
+            .unwrap();
+        assert_eq!(metric.unit, Unit::SquareMetre);
+        assert!(close(metric.value, 9.290304));
+        let imperial = Quantity::new(1.0, Unit::CubicMetre)
+            .in_system(UnitSystem::Imperial)
+            .unwrap();
+        assert_eq!(imperial.unit, Unit::CubicYard);
+        let each = Quantity::new(4.0, Unit::Each)
+            .in_system(UnitSystem::Metric)
+            .unwrap();
+        assert_eq!(each, Quantity::new(4.0, Unit::Each));
+    }
+}
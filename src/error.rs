@@ -6,10 +6,12 @@ use serde_json::error;
 use std::fmt;
 
 // Assuming each module's error implements std::error::Error and std::fmt::Display
-use crate::{controller, entity, presenter, repository, service, use_case};
+use crate::{bus, config, controller, entity, presenter, repository, service, use_case};
 
 // Unified Result type for the entire crate
 
+// Variants are named after the layer error they carry.
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Serialize, Display)]
 pub enum Error {
     #[display("Main thread error: {}", message)]
@@ -18,6 +20,9 @@ pub enum Error {
     RepositoryError(repository::error::Error),
     #[display("InMemoryRepository error: {}", _0)]
     InMemoryRepositoryError(repository::in_memory_repo::error::Error),
+    #[cfg(feature = "file-backend")]
+    #[display("FileRepository error: {}", _0)]
+    FileRepositoryError(repository::file_repo::error::Error),
     #[display("UseCase error: {}", _0)]
     UseCaseError(use_case::error::Error),
     #[display("Entity error: {}", _0)]
@@ -28,6 +33,15 @@ pub enum Error {
     ControllerError(controller::error::Error),
     #[display("Presenter error: {}", _0)]
     PresenterError(presenter::error::Error),
+    #[display("Bus error: {}", _0)]
+    BusError(bus::error::Error),
+    #[display("Config error: {}", _0)]
+    ConfigError(config::error::Error),
+    #[display("Migration error: {}", _0)]
+    MigrationError(crate::migration::error::Error),
+    #[cfg(feature = "backup")]
+    #[display("Backup error: {}", _0)]
+    BackupError(crate::backup::error::Error),
     // MyError could be included if it's used outside as a common error type
     // MyError(MyError),
 }
@@ -43,14 +57,44 @@ impl From<Error> for Result<()> {
     }
 }
 
-#[macro_export]
-macro_rules! try_or_log {
-    ($expr:expr, $success_msg:expr, $err_msg:expr) => {{
-        let result = $expr;
-        match &result {
-            Ok(_) => println!("Success: {}", $success_msg),
-            Err(_) => println!("Error: {}", $err_msg),
+// Recovers the layer error behind a boxed error, so `?` from a controller or use case keeps its
+// problem details instead of becoming an internal error. Anything else becomes a `MainError`.
+impl From<Box<dyn std::error::Error + Send + Sync>> for Box<Error> {
+    fn from(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
+        macro_rules! recover {
+            ($error:ident, $($(#[$meta:meta])* $layer:ty => $variant:path),* $(,)?) => {
+                $(
+                    $(#[$meta])*
+                    let $error = match $error.downcast::<$layer>() {
+                        Ok(layer) => return Box::new($variant(*layer)),
+                        Err(other) => other,
+                    };
+                )*
+            };
         }
-        result?
-    }};
+
+        let error = match error.downcast::<Error>() {
+            Ok(error) => return error,
+            Err(other) => other,
+        };
+        recover!(error,
+            use_case::error::Error => Error::UseCaseError,
+            service::error::Error => Error::ServiceError,
+            entity::error::Error => Error::EntityError,
+            controller::error::Error => Error::ControllerError,
+            presenter::error::Error => Error::PresenterError,
+            repository::error::Error => Error::RepositoryError,
+            repository::in_memory_repo::error::Error => Error::InMemoryRepositoryError,
+            #[cfg(feature = "file-backend")]
+            repository::file_repo::error::Error => Error::FileRepositoryError,
+            bus::error::Error => Error::BusError,
+            config::error::Error => Error::ConfigError,
+            crate::migration::error::Error => Error::MigrationError,
+            #[cfg(feature = "backup")]
+            crate::backup::error::Error => Error::BackupError,
+        );
+        Box::new(Error::MainError {
+            message: error.to_string(),
+        })
+    }
 }
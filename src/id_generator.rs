@@ -0,0 +1,79 @@
+// id_generator.rs
+
+// Where new entity ids come from. Production code uses random v4 UUIDs; the fixed and sequential
+// generators make ids predictable for exact-assertion tests and for reproducing a bug.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use uuid::Uuid;
+
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self) -> Uuid;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn next_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+// Hands out the same id every time; only useful when a single entity is created.
+#[derive(Clone, Copy, Debug)]
+pub struct FixedIdGenerator(pub Uuid);
+
+impl IdGenerator for FixedIdGenerator {
+    fn next_id(&self) -> Uuid {
+        self.0
+    }
+}
+
+// 00000000-0000-0000-0000-000000000001, ...0002 and so on.
+#[derive(Debug)]
+pub struct SequentialIdGenerator {
+    next: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new() -> Self {
+        SequentialIdGenerator::starting_at(1)
+    }
+
+    pub fn starting_at(first: u64) -> Self {
+        SequentialIdGenerator {
+            next: AtomicU64::new(first),
+        }
+    }
+}
+
+impl Default for SequentialIdGenerator {
+    fn default() -> Self {
+        SequentialIdGenerator::new()
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_id(&self) -> Uuid {
+        Uuid::from_u128(self.next.fetch_add(1, Ordering::Relaxed) as u128)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_ids() {
+        let ids = SequentialIdGenerator::new();
+        assert_eq!(
+            ids.next_id().to_string(),
+            "00000000-0000-0000-0000-000000000001"
+        );
+        assert_eq!(ids.next_id(), Uuid::from_u128(2));
+
+        let ids = SequentialIdGenerator::starting_at(0xff);
+        assert_eq!(ids.next_id(), Uuid::from_u128(255));
+    }
+}
@@ -1 +1,39 @@
+// lib.rs
+
+// The estimating library: entities, the repository trait and its backends, the services and use
+// cases built on them, the buses that dispatch to the use cases and the controllers in front, plus
+// the presenters, configuration and diagnostics the front-ends share. `AppBuilder` wires all of
+// it from an `AppConfig`; the binary in `main.rs` is one such front-end.
+//
+// Optional parts are behind cargo features:
+//   file-backend   the JSON file repository (`repository::file_repo`, `Backend::File`)
+//   xlsx           Excel export (`presenter::xlsx_export`, `ExportFormat::Xlsx`)
+//   backup         zip backups of every collection and restoring them (`backup`)
+//   cli            the command-line binary
+//   test-support   test harnesses for other backends and callers (off by default)
+
+pub mod app;
+#[cfg(feature = "backup")]
+pub mod backup;
+pub mod bus;
+pub mod clock;
+pub mod config;
+pub mod controller;
+pub mod dto;
+pub mod entity;
+pub mod error;
+pub mod id_generator;
+pub mod metrics;
+pub mod migration;
+pub mod presenter;
+pub mod problem_details;
+pub mod repository;
 pub mod result;
+pub mod service;
+pub mod telemetry;
+pub mod use_case;
+
+pub use app::{App, AppBuilder, ShutdownReport};
+pub use config::AppConfig;
+pub use error::Error;
+pub use problem_details::ProblemDetails;
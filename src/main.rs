@@ -1,55 +1,293 @@
 //main.rs
 
-use crate::error::Error;
-
-mod controller;
-mod dto;
-mod entity;
-mod error;
-mod presenter;
-mod repository;
-mod result;
-mod service;
-mod use_case;
-
-use std::sync::Arc;
-
-use dto::estimate_dto::EstimateDTO;
-use entity::{estimate::Estimate, section::Section};
-
-use repository::in_memory_repo::InMemoryRepository;
-use service::generic_service::GenericService;
-use tokio::sync::Mutex;
-use uuid::Uuid;
+// The command-line front-end: reads the configuration, builds the app and runs a short demo.
+// SIGINT or SIGTERM stops it early; either way the app is shut down in order before exiting.
+//
+// Exit codes: 0 on success, 1 if the app could not start or a request failed, 3 if the final
+// flush or snapshot failed and data may have been lost.
+
+use std::path::PathBuf;
 
-use crate::dto::section_dto::SectionDTO;
+#[cfg(feature = "backup")]
+use rust_architecture_4::backup;
+use rust_architecture_4::dto::estimate_dto::EstimateDTO;
+use rust_architecture_4::dto::section_dto::SectionDTO;
+use rust_architecture_4::{
+    controller, dto, error, metrics, migration, presenter, problem_details, telemetry,
+};
+use rust_architecture_4::{App, AppBuilder, AppConfig, Error};
+use uuid::Uuid;
 
 type Result<T> = std::result::Result<T, Box<error::Error>>;
 
+// The repositories or the snapshot could not be written at shutdown.
+const EXIT_FLUSH_FAILED: u8 = 3;
+
+struct CliArgs {
+    command: CliCommand,
+    output_format: presenter::formats::OutputFormat,
+    config: AppConfig,
+    dump_metrics: bool,
+}
+
+enum CliCommand {
+    Demo,
+    Migrate(migration::Target, bool),
+    #[cfg(feature = "backup")]
+    Backup(PathBuf),
+    #[cfg(feature = "backup")]
+    Restore(PathBuf, backup::RestoreMode),
+}
+
+// `--name value` or `--name=value`.
+fn flag_value(args: &[String], name: &str) -> Option<String> {
+    let prefix = format!("{}=", name);
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if arg == name {
+            args.get(i + 1).cloned()
+        } else {
+            arg.strip_prefix(&prefix).map(str::to_string)
+        }
+    })
+}
+
+// backup <file>                 write every collection to a zip archive and exit
+// restore <file> [--replace]    load an archive into the configured backend and exit; without
+//                               --replace the backend must be empty
+// migrate [--to <n>] [--dry-run] bring the stored data to migration n (default: the latest) and
+//                               exit; --dry-run lists the steps without running them
+// --config <path>               settings file; defaults to ./config.toml when present
+// --format json|table|text      how the controllers present their results
+// --log-format pretty|json      how diagnostics are written
+// --log-level debug|info|warn|error
+// --log-file <path>             append diagnostics to a file instead of stderr
+// --metrics                     print the metrics in Prometheus text format before exiting
+fn parse_args() -> Result<CliArgs> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let command = match args.first().map(String::as_str) {
+        #[cfg(feature = "backup")]
+        Some(name @ ("backup" | "restore")) => {
+            let path = args
+                .get(1)
+                .filter(|path| !path.starts_with("--"))
+                .map(PathBuf::from)
+                .ok_or_else(|| {
+                    Box::new(Error::MainError {
+                        message: format!("{} needs an archive path", name),
+                    })
+                })?;
+            if name == "backup" {
+                CliCommand::Backup(path)
+            } else if args.iter().any(|arg| arg == "--replace") {
+                CliCommand::Restore(path, backup::RestoreMode::Replace)
+            } else {
+                CliCommand::Restore(path, backup::RestoreMode::IntoEmpty)
+            }
+        }
+        Some("migrate") => {
+            let target = match flag_value(&args, "--to") {
+                Some(value) => migration::Target::Version(value.parse().map_err(|_| {
+                    Box::new(Error::MainError {
+                        message: format!("--to needs a migration number, not `{}`", value),
+                    })
+                })?),
+                None => migration::Target::Latest,
+            };
+            CliCommand::Migrate(target, args.iter().any(|arg| arg == "--dry-run"))
+        }
+        _ => CliCommand::Demo,
+    };
+    let output_format = match flag_value(&args, "--format") {
+        Some(value) => value
+            .parse()
+            .map_err(|e| Box::new(Error::PresenterError(e)))?,
+        None => presenter::formats::OutputFormat::default(),
+    };
+
+    let config_path = flag_value(&args, "--config").map(PathBuf::from);
+    let mut config =
+        AppConfig::load(config_path.as_deref()).map_err(|e| Box::new(Error::ConfigError(e)))?;
+
+    // The logging flags take precedence over the file and the environment.
+    if let Some(value) = flag_value(&args, "--log-format") {
+        config.logging.format = value;
+    }
+    if let Some(value) = flag_value(&args, "--log-level") {
+        config.logging.level = value;
+    }
+    if let Some(value) = flag_value(&args, "--log-file") {
+        config.logging.file = Some(value.into());
+    }
+    config
+        .validate()
+        .map_err(|e| Box::new(Error::ConfigError(e)))?;
+
+    Ok(CliArgs {
+        command,
+        output_format,
+        config,
+        dump_metrics: args.iter().any(|arg| arg == "--metrics"),
+    })
+}
+
+// Failures go to stderr; in JSON mode as a problem-details document.
+fn report_error(error: &error::Error, output_format: presenter::formats::OutputFormat) {
+    match output_format {
+        presenter::formats::OutputFormat::Json => {
+            eprintln!("{}", problem_details::ProblemDetails::from(error).to_json())
+        }
+        _ => eprintln!("Error: {}", error),
+    }
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
-    // Initialize the repository for entities.
-    let estimate_repo = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
-    let section_repo = Arc::new(Mutex::new(InMemoryRepository::<Section>::new()));
-
-    // Initialize the services with the repositories
-    let estimate_service = Arc::new(Mutex::new(GenericService::<Estimate>::new(estimate_repo)));
-    let section_service = Arc::new(Mutex::new(GenericService::<Section>::new(section_repo)));
-
-    // Initialize the use cases with the services
-    let create_estimate =
-        use_case::create_estimate::CreateEstimate::new(Arc::clone(&estimate_service));
-    let create_section_add_to_estimate =
-        use_case::create_section_add_to_estimate::CreateSectionAddToEstimate::new(
-            Arc::clone(&section_service),
-            Arc::clone(&estimate_service),
-        );
-
-    // Initialize the controllers with the use cases
-    let section_controller =
-        controller::section_controller::SectionController::new(create_section_add_to_estimate);
-    let estimate_controller =
-        controller::estimate_controller::EstimateController::new(create_estimate);
+async fn main() -> std::process::ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(error) => {
+            report_error(&error, presenter::formats::OutputFormat::default());
+            return std::process::ExitCode::FAILURE;
+        }
+    };
+    let telemetry_config = match args.config.telemetry() {
+        Ok(telemetry_config) => telemetry_config,
+        Err(error) => {
+            report_error(&Error::ConfigError(error), args.output_format);
+            return std::process::ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = telemetry::init(&telemetry_config) {
+        eprintln!("Error: cannot open log output: {}", e);
+        return std::process::ExitCode::FAILURE;
+    }
+    let output_format = args.output_format;
+
+    // Runs before the app is built: the repositories cannot load data that still needs migrating.
+    if let CliCommand::Migrate(target, dry_run) = args.command {
+        return match migrate(&args.config, target, dry_run).await {
+            Ok(()) => std::process::ExitCode::SUCCESS,
+            Err(error) => {
+                report_error(&error, output_format);
+                std::process::ExitCode::FAILURE
+            }
+        };
+    }
+
+    let app = match AppBuilder::new(args.config)
+        .with_output_format(output_format)
+        .build()
+        .await
+    {
+        Ok(app) => app,
+        Err(error) => {
+            report_error(&error, output_format);
+            return std::process::ExitCode::FAILURE;
+        }
+    };
+    let result = match args.command {
+        CliCommand::Demo => serve(&app, output_format).await,
+        CliCommand::Migrate(..) => unreachable!("handled before the app is built"),
+        #[cfg(feature = "backup")]
+        CliCommand::Backup(path) => backup::backup(&app, &path)
+            .await
+            .map(|report| println!("{}", report))
+            .map_err(Into::into),
+        #[cfg(feature = "backup")]
+        CliCommand::Restore(path, mode) => backup::restore(&app, &path, mode)
+            .await
+            .map(|report| println!("Restored {}", report))
+            .map_err(Into::into),
+    };
+    let shutdown = app.shutdown().await;
+    if args.dump_metrics {
+        print!("{}", metrics::prometheus::render_global());
+    }
+
+    if let Err(error) = shutdown {
+        report_error(&error, output_format);
+        return std::process::ExitCode::from(EXIT_FLUSH_FAILED);
+    }
+    match result {
+        Ok(()) => std::process::ExitCode::SUCCESS,
+        Err(error) => {
+            report_error(&error, output_format);
+            std::process::ExitCode::FAILURE
+        }
+    }
+}
+
+// Runs the demo until it ends or a signal arrives. On a signal the buses stop admitting requests
+// and the one running has until the shutdown deadline to finish; `App::shutdown` keeps the same
+// deadline rather than starting another.
+async fn serve(app: &App, output_format: presenter::formats::OutputFormat) -> Result<()> {
+    let work = run(app, output_format);
+    tokio::pin!(work);
+    tokio::select! {
+        result = &mut work => result,
+        signal = shutdown_signal() => {
+            tracing::info!("Received {}, shutting down", signal);
+            tokio::time::timeout_at(app.begin_shutdown(), work)
+                .await
+                .unwrap_or_else(|_| {
+                    Err(Box::new(Error::MainError {
+                        message: format!("Interrupted by {}", signal),
+                    }))
+                })
+        }
+    }
+}
+
+async fn migrate(config: &AppConfig, target: migration::Target, dry_run: bool) -> Result<()> {
+    // The migration store reads and writes its files with blocking calls.
+    let storage = config.storage.clone();
+    let report = tokio::task::spawn_blocking(move || match migration::for_storage(&storage)? {
+        Some(migrator) => migrator.run(target, dry_run).map(Some),
+        None => Ok(None),
+    })
+    .await
+    .map_err(|e| {
+        Box::new(Error::MainError {
+            message: format!("Migration stopped abnormally: {}", e),
+        })
+    })??;
+    match report {
+        Some(report) => println!("{}", report),
+        None => println!(
+            "The {} backend keeps nothing to migrate",
+            config.storage.backend
+        ),
+    }
+    Ok(())
+}
+
+async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => tokio::select! {
+                _ = tokio::signal::ctrl_c() => "SIGINT",
+                _ = terminate.recv() => "SIGTERM",
+            },
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+                "SIGINT"
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        "SIGINT"
+    }
+}
+
+async fn run(app: &App, output_format: presenter::formats::OutputFormat) -> Result<()> {
+    let App {
+        estimate_controller,
+        section_controller,
+        ..
+    } = app;
 
     // Create a new estimate
     let mut new_estimate_dto = dto::estimate_dto::EstimateDTO::new();
@@ -57,7 +295,7 @@ async fn main() -> Result<()> {
     new_estimate_dto.description = "A new estimate".to_string();
     new_estimate_dto.location = "New York".to_string();
 
-    println!("Creating new estimate: {:?}", new_estimate_dto);
+    tracing::info!("Creating new estimate: {}", new_estimate_dto.name);
 
     let estimate_request = controller::estimate_controller::CreateEstimateRequest::new(
         new_estimate_dto.name,
@@ -65,96 +303,56 @@ async fn main() -> Result<()> {
         new_estimate_dto.location,
     );
 
-    let estimate_id_result = estimate_controller.create_estimate(estimate_request).await;
-    match estimate_id_result {
-        Ok(estimate_id) => {
-            println!("Estimate created successfully with ID: {:?}", estimate_id);
-        }
-        Err(e) => {
-            return Err(Box::new(Error::MainError {
-                message: format!("Error creating estimate: {}", e),
-            }));
-        }
+    let response = estimate_controller
+        .create_estimate(estimate_request)
+        .await?;
+    match estimate_controller.presenter().present(&response) {
+        Ok(output) => println!("{}", output),
+        Err(e) => tracing::warn!("Error presenting new estimate: {}", e),
     }
+    let estimate_id = response.estimate_id.ok_or_else(|| {
+        Box::new(Error::MainError {
+            message: response.message,
+        })
+    })?;
+    tracing::info!("Estimate created successfully with ID: {}", estimate_id);
 
-    let retrieved_estimate_dto: Result<EstimateDTO> = match estimate_id_result {
-        Ok(estimate_id) => {
-            let estimate_service = estimate_service.lock().await;
-            let estimate = estimate_service.get_estimate(estimate_id).await;
-            match estimate {
-                Ok(Some(estimate)) => Ok(estimate.into()),
-                Ok(None) => Err(Box::new(Error::MainError {
-                    message: "Estimate does not exist".to_string(),
-                })),
-                Err(e) => Err(Box::new(Error::MainError {
-                    message: format!("Error getting estimate: {}", e),
-                })),
-            }
-        }
-        Err(e) => Err(Box::new(Error::MainError {
-            message: format!("Error creating estimate: {}", e),
-        })),
-    };
+    let request = controller::estimate_controller::GetEstimateRequest::new(estimate_id);
+    let response = estimate_controller.get_estimate(request).await?;
+    let estimate_dto: EstimateDTO = response.estimate.ok_or_else(|| {
+        Box::new(Error::MainError {
+            message: response.message,
+        })
+    })?;
+    if let Ok(output) = estimate_controller
+        .presenter()
+        .present_estimate(&estimate_dto)
+    {
+        println!("Retrieved estimate:\n{}", output);
+    }
 
-    let mut new_section_dto =
+    let new_section_dto =
         dto::section_dto::SectionDTO::new("New Section".to_string(), "Code:0001".to_string());
+    let request = controller::section_controller::CreateSectionAddToEstimateRequest::new(
+        new_section_dto,
+        estimate_dto.id,
+    );
+    let section_id = section_controller
+        .create_section_add_to_estimate(request)
+        .await?;
+    tracing::info!("Section created successfully with ID: {}", section_id);
 
-    let section_request = match retrieved_estimate_dto {
-        Ok(estimate_dto) => {
-            println!("Retrieved estimate: {:?}", estimate_dto);
-            // Construct the request using the successfully retrieved estimate_dto.
-            Some(
-                controller::section_controller::CreateSectionAddToEstimateRequest::new(
-                    new_section_dto,
-                    estimate_dto,
-                ),
-            )
-        }
-        Err(e) => {
-            return Err(Box::new(Error::MainError {
-                message: "Error retrieving estimate DTO".to_string(),
-            }));
-        }
-    };
-
-    if let Some(request) = section_request {
-        // Proceed with creating the section, assuming this function call is async and returns a Result.
-        let section_result = section_controller
-            .create_section_add_to_estimate(request)
-            .await;
-        println!("Section Results --> {:?}", section_result);
-
-        match section_result {
-            Ok(section_id) => {
-                // Handle the successful creation of the section, if necessary.
-                println!("Section created successfully with ID: {:?}", section_id);
-
-                // Attempt to retrieve the created section
-                let section = section_service.lock().await.get_section(section_id).await;
-
-                match section {
-                    Ok(t) => println!("Section retrieved successfully: {:?}", t),
-                    Err(e) => {
-                        // Return an error if retrieving the section fails
-                        return Err(Box::new(Error::MainError {
-                            message: format!("Error retrieving section: {}", e),
-                        }));
-                    }
-                }
-            }
-            Err(e) => {
-                // Return an error if creating the section fails
-                return Err(Box::new(Error::MainError {
-                    message: format!("Error creating section: {}", e),
-                }));
-            }
-        }
-    } else {
-        // Return an error if section_request is None
-        return Err(Box::new(Error::MainError {
-            message: "Section request is None".to_string(),
-        }));
-    };
+    // Retrieve the created section
+    let request = controller::section_controller::GetSectionRequest::new(section_id);
+    let response = section_controller.get_section(request).await?;
+    if let Some(output) = response.section.and_then(|section| {
+        section_controller
+            .presenter()
+            .present_section(&section)
+            .ok()
+    }) {
+        println!("Section retrieved successfully:\n{}", output);
+    }
 
     Ok(())
 }
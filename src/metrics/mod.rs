@@ -0,0 +1,243 @@
+// metrics/mod.rs
+
+// Operational numbers kept in a process-wide registry and exported in the Prometheus text
+// format:
+//   operations_total{layer,operation,outcome}        calls per use case, service and repository operation
+//   operation_duration_seconds{layer,operation}      their latency
+//   lock_wait_seconds{lock}                          time spent waiting for a tokio Mutex
+//   validation_failures_total{scope,rule}            failed validation rules
+// Spans feed the first two, so anything traced is also measured.
+
+pub mod prometheus;
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex as StdMutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use tokio::sync::{Mutex, MutexGuard};
+
+// Upper bounds in seconds, from 50µs to 5s.
+pub const LATENCY_BUCKETS: [f64; 12] = [
+    0.00005, 0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.05, 0.1, 1.0, 5.0,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Counter,
+    Histogram,
+}
+
+type Labels = Vec<(&'static str, String)>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Series {
+    Counter(f64),
+    Histogram {
+        // Per-bucket (not cumulative) counts; the last one is the +Inf bucket.
+        buckets: Vec<u64>,
+        sum: f64,
+        count: u64,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Family {
+    pub help: &'static str,
+    pub kind: MetricKind,
+    pub series: BTreeMap<Labels, Series>,
+}
+
+#[derive(Debug, Default)]
+pub struct Registry {
+    families: StdMutex<BTreeMap<&'static str, Family>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    pub fn increment(&self, name: &'static str, help: &'static str, labels: Labels) {
+        self.update(name, help, MetricKind::Counter, labels, |series| {
+            if let Series::Counter(value) = series {
+                *value += 1.0;
+            }
+        });
+    }
+
+    pub fn observe(&self, name: &'static str, help: &'static str, labels: Labels, seconds: f64) {
+        self.update(name, help, MetricKind::Histogram, labels, |series| {
+            if let Series::Histogram {
+                buckets,
+                sum,
+                count,
+            } = series
+            {
+                let index = LATENCY_BUCKETS
+                    .iter()
+                    .position(|bound| seconds <= *bound)
+                    .unwrap_or(LATENCY_BUCKETS.len());
+                buckets[index] += 1;
+                *sum += seconds;
+                *count += 1;
+            }
+        });
+    }
+
+    fn update(
+        &self,
+        name: &'static str,
+        help: &'static str,
+        kind: MetricKind,
+        labels: Labels,
+        apply: impl FnOnce(&mut Series),
+    ) {
+        // Metrics are best effort; a poisoned registry just stops recording.
+        let Ok(mut families) = self.families.lock() else {
+            return;
+        };
+        let family = families.entry(name).or_insert_with(|| Family {
+            help,
+            kind,
+            series: BTreeMap::new(),
+        });
+        if family.kind != kind {
+            return;
+        }
+        let series = family.series.entry(labels).or_insert_with(|| match kind {
+            MetricKind::Counter => Series::Counter(0.0),
+            MetricKind::Histogram => Series::Histogram {
+                buckets: vec![0; LATENCY_BUCKETS.len() + 1],
+                sum: 0.0,
+                count: 0,
+            },
+        });
+        apply(series);
+    }
+
+    pub fn snapshot(&self) -> BTreeMap<&'static str, Family> {
+        self.families
+            .lock()
+            .map(|families| families.clone())
+            .unwrap_or_default()
+    }
+}
+
+pub fn registry() -> &'static Registry {
+    static REGISTRY: OnceLock<Registry> = OnceLock::new();
+    REGISTRY.get_or_init(Registry::new)
+}
+
+pub fn record_operation(layer: &'static str, operation: &str, ok: bool, duration: Duration) {
+    let outcome = if ok { "ok" } else { "error" };
+    registry().increment(
+        "operations_total",
+        "Operations executed, by layer, operation and outcome.",
+        vec![
+            ("layer", layer.to_string()),
+            ("operation", operation.to_string()),
+            ("outcome", outcome.to_string()),
+        ],
+    );
+    registry().observe(
+        "operation_duration_seconds",
+        "Operation latency in seconds, by layer and operation.",
+        vec![
+            ("layer", layer.to_string()),
+            ("operation", operation.to_string()),
+        ],
+        duration.as_secs_f64(),
+    );
+}
+
+// `scope` is the entity or use case the rule belongs to, `rule` the check that failed.
+pub fn record_validation_failure(scope: &str, rule: &str) {
+    registry().increment(
+        "validation_failures_total",
+        "Failed validation rules, by scope and rule.",
+        vec![("scope", scope.to_string()), ("rule", rule.to_string())],
+    );
+}
+
+// `event` is what a service policy did to a call: retry, timeout, opened, closed or rejected.
+pub fn record_policy_event(operation: &str, event: &'static str) {
+    registry().increment(
+        "service_policy_events_total",
+        "Retries, timeouts and circuit breaker events, by operation and event.",
+        vec![
+            ("operation", operation.to_string()),
+            ("event", event.to_string()),
+        ],
+    );
+}
+
+// Locks `mutex`, recording how long the caller waited under the name of the guarded type.
+pub async fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    let started = Instant::now();
+    let guard = mutex.lock().await;
+    registry().observe(
+        "lock_wait_seconds",
+        "Time spent waiting to acquire a lock, by guarded type.",
+        vec![("lock", lock_name::<T>())],
+        started.elapsed().as_secs_f64(),
+    );
+    guard
+}
+
+// `tokio::sync::Mutex<dyn crate::repository::Repository<crate::entity::Estimate> + Send + Sync>`
+// is reported as `Repository<Estimate>`.
+fn lock_name<T: ?Sized>() -> String {
+    let full = std::any::type_name::<T>();
+    let mut name = String::new();
+    let mut word = String::new();
+    for c in full.chars().chain(std::iter::once('\0')) {
+        if c.is_alphanumeric() || c == '_' || c == ':' {
+            word.push(c);
+            continue;
+        }
+        name.push_str(word.rsplit("::").next().unwrap_or(&word));
+        word.clear();
+        if c != '\0' {
+            name.push(c);
+        }
+    }
+    name.trim_start_matches("dyn ")
+        .replace(" + Send", "")
+        .replace(" + Sync", "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::estimate::Estimate;
+    use crate::repository::repository::Repository;
+    use crate::service::generic_service::GenericService;
+
+    #[test]
+    fn test_lock_names_drop_paths_and_auto_traits() {
+        assert_eq!(
+            lock_name::<GenericService<Estimate>>(),
+            "GenericService<Estimate>"
+        );
+        assert_eq!(
+            lock_name::<dyn Repository<Estimate> + Send + Sync>(),
+            "Repository<Estimate>"
+        );
+    }
+
+    #[test]
+    fn test_histograms_fill_the_first_bucket_that_fits() {
+        let registry = Registry::new();
+        registry.observe("latency", "help", vec![], 0.0003);
+        registry.observe("latency", "help", vec![], 10.0);
+
+        let families = registry.snapshot();
+        let series = &families["latency"].series[&vec![]];
+        let Series::Histogram { buckets, count, .. } = series else {
+            panic!("expected a histogram");
+        };
+        assert_eq!(*count, 2);
+        assert_eq!(buckets[3], 1);
+        assert_eq!(buckets[LATENCY_BUCKETS.len()], 1);
+    }
+}
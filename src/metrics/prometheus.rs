@@ -0,0 +1,108 @@
+// metrics/prometheus.rs
+
+use super::{registry, MetricKind, Registry, Series, LATENCY_BUCKETS};
+
+// Renders every metric in the Prometheus text exposition format (version 0.0.4).
+pub fn render(registry: &Registry) -> String {
+    let mut out = String::new();
+    for (name, family) in registry.snapshot() {
+        let kind = match family.kind {
+            MetricKind::Counter => "counter",
+            MetricKind::Histogram => "histogram",
+        };
+        out.push_str(&format!("# HELP {} {}\n", name, family.help));
+        out.push_str(&format!("# TYPE {} {}\n", name, kind));
+
+        for (labels, series) in &family.series {
+            let labels: Vec<(String, String)> = labels
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect();
+            match series {
+                Series::Counter(value) => {
+                    out.push_str(&format!("{}{} {}\n", name, label_set(&labels), value));
+                }
+                Series::Histogram {
+                    buckets,
+                    sum,
+                    count,
+                } => {
+                    let mut cumulative = 0;
+                    for (index, bucket) in buckets.iter().enumerate() {
+                        cumulative += bucket;
+                        let bound = LATENCY_BUCKETS
+                            .get(index)
+                            .map(|bound| bound.to_string())
+                            .unwrap_or_else(|| "+Inf".to_string());
+                        let mut with_le = labels.clone();
+                        with_le.push(("le".to_string(), bound));
+                        out.push_str(&format!(
+                            "{}_bucket{} {}\n",
+                            name,
+                            label_set(&with_le),
+                            cumulative
+                        ));
+                    }
+                    out.push_str(&format!("{}_sum{} {}\n", name, label_set(&labels), sum));
+                    out.push_str(&format!("{}_count{} {}\n", name, label_set(&labels), count));
+                }
+            }
+        }
+    }
+    out
+}
+
+// What a `/metrics` endpoint or a CLI dump serves.
+pub fn render_global() -> String {
+    render(registry())
+}
+
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+fn label_set(labels: &[(String, String)]) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = labels
+        .iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, escape(value)))
+        .collect();
+    format!("{{{}}}", pairs.join(","))
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_counters_and_cumulative_buckets() {
+        let registry = Registry::new();
+        registry.increment(
+            "validation_failures_total",
+            "Failed validation rules.",
+            vec![
+                ("scope", "Estimate".to_string()),
+                ("rule", "say \"hi\"".to_string()),
+            ],
+        );
+        registry.observe("wait_seconds", "Wait.", vec![], 0.00002);
+        registry.observe("wait_seconds", "Wait.", vec![], 0.002);
+
+        let text = render(&registry);
+
+        assert!(text.contains("# TYPE validation_failures_total counter\n"));
+        assert!(text
+            .contains("validation_failures_total{scope=\"Estimate\",rule=\"say \\\"hi\\\"\"} 1\n"));
+        assert!(text.contains("wait_seconds_bucket{le=\"0.00005\"} 1\n"));
+        assert!(text.contains("wait_seconds_bucket{le=\"0.0025\"} 2\n"));
+        assert!(text.contains("wait_seconds_bucket{le=\"+Inf\"} 2\n"));
+        assert!(text.contains("wait_seconds_count 2\n"));
+    }
+}
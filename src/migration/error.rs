@@ -0,0 +1,46 @@
+//migration/error.rs
+use crate::error::Error as MainError;
+
+use derive_more::Display;
+
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+#[serde_as]
+#[derive(Debug, Serialize, Display)]
+pub enum Error {
+    #[display("Cannot access {}: {}", path, message)]
+    IoError { path: String, message: String },
+    #[display("Cannot decode {}: {}", path, message)]
+    DecodeError { path: String, message: String },
+    #[display(
+        "Migrations must have distinct versions above 0 in ascending order; {} is out of place",
+        version
+    )]
+    InvalidOrder { version: u32 },
+    #[display("There is no migration {}", version)]
+    UnknownVersion { version: u32 },
+    #[display(
+        "Migration {} is recorded as applied but this build does not know it",
+        version
+    )]
+    UnknownApplied { version: u32 },
+    #[display("Migration {} is not applied but later ones are", version)]
+    Gap { version: u32 },
+    #[display("Migration {} ({}) cannot be reverted", version, name)]
+    Irreversible { version: u32, name: &'static str },
+    #[display("Migration {} failed on {}: {}", version, collection, message)]
+    StepFailed {
+        version: u32,
+        collection: &'static str,
+        message: String,
+    },
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for MainError {
+    fn from(error: Error) -> Self {
+        MainError::MigrationError(error)
+    }
+}
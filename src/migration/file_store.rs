@@ -0,0 +1,333 @@
+// migration/file_store.rs
+
+// Migrates the file backend's `<data_path>/<collection>.json` files and records what has been
+// applied in `<data_path>/migrations.json`. Each migration rewrites the files it touches next to
+// the originals first and only then renames them into place, followed by the record, so a failed
+// step leaves the data as it was.
+
+use crate::result::*;
+
+use super::error::Error as MigrationError;
+use super::{Applied, Direction, Migration, MigrationStore, Step};
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+pub const MANIFEST: &str = "migrations.json";
+
+pub struct FileMigrationStore {
+    data_path: PathBuf,
+}
+
+impl FileMigrationStore {
+    pub fn new(data_path: impl Into<PathBuf>) -> Self {
+        FileMigrationStore {
+            data_path: data_path.into(),
+        }
+    }
+
+    fn manifest_path(&self) -> PathBuf {
+        self.data_path.join(MANIFEST)
+    }
+
+    fn collection_path(&self, collection: &str) -> PathBuf {
+        self.data_path.join(format!("{}.json", collection))
+    }
+}
+
+impl MigrationStore for FileMigrationStore {
+    fn applied(&self) -> Result<Vec<Applied>> {
+        let path = self.manifest_path();
+        let mut applied: Vec<Applied> = match read(&path)? {
+            Some(bytes) => serde_json::from_slice(&bytes).map_err(|e| decode_error(&path, e))?,
+            None => Vec::new(),
+        };
+        applied.sort_by_key(|applied| applied.version);
+        Ok(applied)
+    }
+
+    fn apply(
+        &self,
+        migration: &Migration,
+        direction: Direction,
+        steps: &[Step],
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        // Every step on a collection sees the result of the ones before it.
+        let mut collections: BTreeMap<&'static str, Vec<Value>> = BTreeMap::new();
+        for step in steps {
+            if !collections.contains_key(step.collection) {
+                let path = self.collection_path(step.collection);
+                let items = match read(&path)? {
+                    Some(bytes) => {
+                        serde_json::from_slice(&bytes).map_err(|e| decode_error(&path, e))?
+                    }
+                    // Nothing stored yet, nothing to change.
+                    None => continue,
+                };
+                collections.insert(step.collection, items);
+            }
+            let failed = |message: String| MigrationError::StepFailed {
+                version: migration.version,
+                collection: step.collection,
+                message,
+            };
+            for item in collections.get_mut(step.collection).into_iter().flatten() {
+                let Value::Object(fields) = item else {
+                    return Err(Box::new(failed("item is not an object".to_string())));
+                };
+                (step.change)(fields).map_err(|e| failed(e.to_string()))?;
+            }
+        }
+
+        let mut applied = self.applied()?;
+        match direction {
+            Direction::Up => applied.push(Applied {
+                version: migration.version,
+                name: migration.name.to_string(),
+                applied_at: at,
+            }),
+            Direction::Down => applied.retain(|applied| applied.version != migration.version),
+        }
+
+        let mut writes = Vec::new();
+        for (collection, items) in &collections {
+            writes.push((
+                self.collection_path(collection),
+                serde_json::to_vec_pretty(items)?,
+            ));
+        }
+        writes.push((self.manifest_path(), serde_json::to_vec_pretty(&applied)?));
+        // Migrating before anything is stored records the migrations as applied.
+        std::fs::create_dir_all(&self.data_path).map_err(|e| io_error(&self.data_path, e))?;
+        let mut staged = Vec::new();
+        for (path, bytes) in &writes {
+            let temporary = temporary(path);
+            std::fs::write(&temporary, bytes).map_err(|e| io_error(&temporary, e))?;
+            staged.push((temporary, path));
+        }
+        for (temporary, path) in staged {
+            std::fs::rename(&temporary, path).map_err(|e| io_error(path, e))?;
+        }
+        Ok(())
+    }
+}
+
+fn read(path: &Path) -> Result<Option<Vec<u8>>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(io_error(path, error).into()),
+    }
+}
+
+fn temporary(path: &Path) -> PathBuf {
+    let mut temporary = path.to_path_buf().into_os_string();
+    temporary.push(".migrating");
+    temporary.into()
+}
+
+fn io_error(path: &Path, error: std::io::Error) -> MigrationError {
+    MigrationError::IoError {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+fn decode_error(path: &Path, error: serde_json::Error) -> MigrationError {
+    MigrationError::DecodeError {
+        path: path.display().to_string(),
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::migration::{Migrator, Target};
+    use chrono::TimeZone;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    fn add_currency(item: &mut serde_json::Map<String, Value>) -> Result<()> {
+        item.insert("currency".to_string(), json!("USD"));
+        Ok(())
+    }
+
+    fn drop_currency(item: &mut serde_json::Map<String, Value>) -> Result<()> {
+        item.remove("currency");
+        Ok(())
+    }
+
+    fn rename_location(item: &mut serde_json::Map<String, Value>) -> Result<()> {
+        let location = item.remove("location").ok_or("location is missing")?;
+        item.insert("site".to_string(), location);
+        Ok(())
+    }
+
+    fn migrations() -> Vec<Migration> {
+        vec![
+            Migration {
+                version: 1,
+                name: "add estimate currency",
+                up: vec![Step {
+                    collection: "estimates",
+                    change: add_currency,
+                }],
+                down: Some(vec![Step {
+                    collection: "estimates",
+                    change: drop_currency,
+                }]),
+            },
+            Migration {
+                version: 2,
+                name: "rename location to site",
+                up: vec![Step {
+                    collection: "estimates",
+                    change: rename_location,
+                }],
+                down: None,
+            },
+        ]
+    }
+
+    fn setup() -> (PathBuf, Migrator) {
+        let data_path = std::env::temp_dir().join(format!("migrate-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&data_path).unwrap();
+        std::fs::write(
+            data_path.join("estimates.json"),
+            json!([{ "name": "Library", "location": "Springfield" }]).to_string(),
+        )
+        .unwrap();
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 7, 8, 9).unwrap();
+        let migrator = Migrator::new(migrations(), Box::new(FileMigrationStore::new(&data_path)))
+            .unwrap()
+            .with_clock(Arc::new(FixedClock::new(now)));
+        (data_path, migrator)
+    }
+
+    fn estimates(data_path: &Path) -> Value {
+        serde_json::from_slice(&std::fs::read(data_path.join("estimates.json")).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_up_and_down_are_recorded() {
+        let (data_path, migrator) = setup();
+
+        let report = migrator.run(Target::Version(1), false).unwrap();
+        assert_eq!((report.from, report.to, report.steps.len()), (0, 1, 1));
+        assert_eq!(estimates(&data_path)[0]["currency"], "USD");
+        let applied = FileMigrationStore::new(&data_path).applied().unwrap();
+        assert_eq!(applied.len(), 1);
+        assert_eq!(applied[0].name, "add estimate currency");
+
+        migrator.run(Target::Latest, false).unwrap();
+        assert_eq!(estimates(&data_path)[0]["site"], "Springfield");
+        assert!(migrator
+            .run(Target::Latest, false)
+            .unwrap()
+            .steps
+            .is_empty());
+
+        // Version 2 cannot be undone, so nothing below it can be reached.
+        let error = migrator.run(Target::Version(0), false).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::Irreversible { version: 2, .. })
+        ));
+
+        std::fs::remove_dir_all(data_path).unwrap();
+    }
+
+    #[test]
+    fn test_down_reverts_and_forgets() {
+        let (data_path, migrator) = setup();
+        migrator.run(Target::Version(1), false).unwrap();
+
+        let report = migrator.run(Target::Version(0), false).unwrap();
+        assert_eq!(report.steps[0].direction, Direction::Down);
+        assert!(estimates(&data_path)[0].get("currency").is_none());
+        assert!(FileMigrationStore::new(&data_path)
+            .applied()
+            .unwrap()
+            .is_empty());
+
+        std::fs::remove_dir_all(data_path).unwrap();
+    }
+
+    #[test]
+    fn test_dry_run_changes_nothing() {
+        let (data_path, migrator) = setup();
+        let before = estimates(&data_path);
+
+        let report = migrator.run(Target::Latest, true).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.steps.len(), 2);
+        assert_eq!(estimates(&data_path), before);
+        assert!(!data_path.join(MANIFEST).exists());
+
+        std::fs::remove_dir_all(data_path).unwrap();
+    }
+
+    #[test]
+    fn test_failed_step_leaves_data_alone() {
+        let (data_path, migrator) = setup();
+        std::fs::write(
+            data_path.join("estimates.json"),
+            json!([{ "name": "Library" }]).to_string(),
+        )
+        .unwrap();
+        migrator.run(Target::Version(1), false).unwrap();
+        let before = estimates(&data_path);
+
+        let error = migrator.run(Target::Latest, false).unwrap_err();
+        assert!(error.to_string().contains("location is missing"));
+        assert_eq!(estimates(&data_path), before);
+        assert_eq!(
+            FileMigrationStore::new(&data_path).applied().unwrap().len(),
+            1
+        );
+
+        std::fs::remove_dir_all(data_path).unwrap();
+    }
+
+    #[test]
+    fn test_missing_data_directory_is_created() {
+        let data_path = std::env::temp_dir().join(format!("migrate-{}", uuid::Uuid::new_v4()));
+        let migrator =
+            Migrator::new(migrations(), Box::new(FileMigrationStore::new(&data_path))).unwrap();
+
+        let report = migrator.run(Target::Latest, false).unwrap();
+        assert_eq!(report.to, 2);
+        assert_eq!(
+            FileMigrationStore::new(&data_path).applied().unwrap().len(),
+            2
+        );
+
+        std::fs::remove_dir_all(data_path).unwrap();
+    }
+
+    #[test]
+    fn test_unknown_applied_version_is_refused() {
+        let (data_path, _) = setup();
+        let store = FileMigrationStore::new(&data_path);
+        Migrator::new(migrations(), Box::new(FileMigrationStore::new(&data_path)))
+            .unwrap()
+            .run(Target::Latest, false)
+            .unwrap();
+
+        // An older build knows only the first migration.
+        let older = Migrator::new(migrations()[..1].to_vec(), Box::new(store)).unwrap();
+        let error = older.plan(Target::Latest).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<MigrationError>(),
+            Some(MigrationError::UnknownApplied { version: 2 })
+        ));
+
+        std::fs::remove_dir_all(data_path).unwrap();
+    }
+}
@@ -0,0 +1,472 @@
+// migration/mod.rs
+
+// Upgrades persisted data as the entities change. Migrations are numbered from 1 and run in
+// order; each has `up` steps and, unless it cannot be undone, `down` steps. A step rewrites every
+// stored item of one collection as a JSON object, so the same migration serves any store that
+// keeps items as documents. The store records which migrations have been applied: the file
+// store in `<data_path>/migrations.json`. There is no SQL backend yet; one would add a
+// `MigrationStore` that keeps the record in a migrations table.
+//
+// `Migrator::run` brings the store to a target version, up or down. With `dry_run` it only
+// reports what it would do. `AppBuilder` runs it at startup when `storage.auto_migrate` is set,
+// and the binary has a `migrate` command.
+
+pub mod error;
+#[cfg(feature = "file-backend")]
+pub mod file_store;
+
+use crate::result::*;
+
+use self::error::Error as MigrationError;
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use derive_more::Display;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::clock::{Clock, SystemClock};
+use crate::config::{Backend, StorageConfig};
+use crate::entity::unit::Unit;
+
+// Changes one stored item in place.
+pub type Change = fn(&mut Map<String, Value>) -> Result<()>;
+
+#[derive(Clone, Copy)]
+pub struct Step {
+    // e.g. "estimates"
+    pub collection: &'static str,
+    pub change: Change,
+}
+
+#[derive(Clone)]
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub up: Vec<Step>,
+    // None when the migration cannot be undone, e.g. it drops data.
+    pub down: Option<Vec<Step>>,
+}
+
+// Every migration of the application, oldest first. New ones go at the end with the next
+// version; released ones are never edited or renumbered.
+pub fn migrations() -> Vec<Migration> {
+    vec![
+        Migration {
+            version: 1,
+            name: "normalize line item units",
+            up: vec![Step {
+                collection: "sections",
+                change: normalize_units,
+            }],
+            // The codes read back as the same units, so there is nothing to undo.
+            down: Some(Vec::new()),
+        },
+        Migration {
+            version: 2,
+            name: "add line item price references",
+            up: vec![Step {
+                collection: "sections",
+                change: add_price_references,
+            }],
+            down: Some(vec![Step {
+                collection: "sections",
+                change: drop_price_references,
+            }]),
+        },
+        Migration {
+            version: 3,
+            name: "store metric tons as MT",
+            up: vec![
+                Step {
+                    collection: "sections",
+                    change: metric_ton_code_up,
+                },
+                Step {
+                    collection: "price_book",
+                    change: price_book_metric_ton_code_up,
+                },
+            ],
+            down: Some(vec![
+                Step {
+                    collection: "sections",
+                    change: metric_ton_code_down,
+                },
+                Step {
+                    collection: "price_book",
+                    change: price_book_metric_ton_code_down,
+                },
+            ]),
+        },
+    ]
+}
+
+// Calls `change` on every line item of `section` and of the sections nested in it.
+fn each_line_item(
+    section: &mut Map<String, Value>,
+    change: &dyn Fn(&mut Map<String, Value>) -> Result<()>,
+) -> Result<()> {
+    for item in section
+        .get_mut("line_items")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+    {
+        if let Value::Object(item) = item {
+            change(item)?;
+        }
+    }
+    for child in section
+        .get_mut("sections")
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+    {
+        if let Value::Object(child) = child {
+            each_line_item(child, change)?;
+        }
+    }
+    Ok(())
+}
+
+// 1: line item units were free text ("sq ft", "Tons"); they become catalog codes ("SF", "TON").
+// Fails on a unit the catalog does not know, so the data is fixed before the entity refuses it.
+fn normalize_units(section: &mut Map<String, Value>) -> Result<()> {
+    each_line_item(section, &|item| {
+        if let Some(unit) = item.get_mut("unit") {
+            let text = unit.as_str().ok_or("unit is not text")?;
+            *unit = Value::String(text.parse::<Unit>()?.code().to_string());
+        }
+        Ok(())
+    })
+}
+
+// 2: line items can point at the price book item they were priced from.
+fn add_price_references(section: &mut Map<String, Value>) -> Result<()> {
+    each_line_item(section, &|item| {
+        item.entry("price_reference").or_insert(Value::Null);
+        Ok(())
+    })
+}
+
+fn drop_price_references(section: &mut Map<String, Value>) -> Result<()> {
+    each_line_item(section, &|item| {
+        item.remove("price_reference");
+        Ok(())
+    })
+}
+
+// 3: the metric ton's code was "T", which US estimators read as a short ton. Only the metric ton
+// was ever stored as "T", so it becomes "MT" wherever a unit is kept.
+fn rename_unit(item: &mut Map<String, Value>, from: &str, to: &str) {
+    if let Some(unit) = item.get_mut("unit").filter(|unit| *unit == from) {
+        *unit = Value::String(to.to_string());
+    }
+}
+
+fn metric_ton_code_up(section: &mut Map<String, Value>) -> Result<()> {
+    each_line_item(section, &|item| {
+        rename_unit(item, "T", "MT");
+        Ok(())
+    })
+}
+
+fn metric_ton_code_down(section: &mut Map<String, Value>) -> Result<()> {
+    each_line_item(section, &|item| {
+        rename_unit(item, "MT", "T");
+        Ok(())
+    })
+}
+
+fn price_book_metric_ton_code_up(item: &mut Map<String, Value>) -> Result<()> {
+    rename_unit(item, "T", "MT");
+    Ok(())
+}
+
+fn price_book_metric_ton_code_down(item: &mut Map<String, Value>) -> Result<()> {
+    rename_unit(item, "MT", "T");
+    Ok(())
+}
+
+// The migrator for the configured backend, or None when it keeps nothing between runs.
+pub fn for_storage(storage: &StorageConfig) -> Result<Option<Migrator>> {
+    match storage.backend {
+        Backend::Memory => Ok(None),
+        #[cfg(feature = "file-backend")]
+        Backend::File => Migrator::new(
+            migrations(),
+            Box::new(file_store::FileMigrationStore::new(&storage.data_path)),
+        )
+        .map(Some),
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, Serialize)]
+pub enum Direction {
+    #[display("up")]
+    Up,
+    #[display("down")]
+    Down,
+}
+
+// A migration the store has recorded as applied.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Applied {
+    pub version: u32,
+    pub name: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+// Where migrations are applied and recorded.
+pub trait MigrationStore: Send + Sync {
+    // In version order.
+    fn applied(&self) -> Result<Vec<Applied>>;
+    // Runs `steps` and records (`Up`) or forgets (`Down`) the migration.
+    fn apply(
+        &self,
+        migration: &Migration,
+        direction: Direction,
+        steps: &[Step],
+        at: DateTime<Utc>,
+    ) -> Result<()>;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Target {
+    #[default]
+    Latest,
+    // 0 reverts everything.
+    Version(u32),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Planned {
+    pub version: u32,
+    pub name: &'static str,
+    pub direction: Direction,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct MigrationReport {
+    pub dry_run: bool,
+    // The version the store was at, and is at now (or would be, for a dry run).
+    pub from: u32,
+    pub to: u32,
+    pub steps: Vec<Planned>,
+}
+
+impl fmt::Display for MigrationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verb = if self.dry_run {
+            "Would migrate"
+        } else {
+            "Migrated"
+        };
+        if self.steps.is_empty() {
+            return write!(f, "Up to date at version {}", self.from);
+        }
+        write!(f, "{} from version {} to {}", verb, self.from, self.to)?;
+        for step in &self.steps {
+            write!(
+                f,
+                "\n  {:>4} {:<4} {}",
+                step.version, step.direction, step.name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub struct Migrator {
+    migrations: Vec<Migration>,
+    store: Box<dyn MigrationStore>,
+    clock: Arc<dyn Clock>,
+}
+
+impl Migrator {
+    // Fails unless the versions start above 0 and strictly increase.
+    pub fn new(migrations: Vec<Migration>, store: Box<dyn MigrationStore>) -> Result<Self> {
+        let mut previous = 0;
+        for migration in &migrations {
+            if migration.version <= previous {
+                return Err(Box::new(MigrationError::InvalidOrder {
+                    version: migration.version,
+                }));
+            }
+            previous = migration.version;
+        }
+        Ok(Migrator {
+            migrations,
+            store,
+            clock: Arc::new(SystemClock),
+        })
+    }
+
+    // Stamps the applied records.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn latest(&self) -> u32 {
+        self.migrations
+            .last()
+            .map_or(0, |migration| migration.version)
+    }
+
+    // What `run` would do, without doing it.
+    pub fn plan(&self, target: Target) -> Result<MigrationReport> {
+        let applied: BTreeMap<u32, Applied> = self
+            .store
+            .applied()?
+            .into_iter()
+            .map(|applied| (applied.version, applied))
+            .collect();
+        if let Some(version) = applied
+            .keys()
+            .find(|version| !self.migrations.iter().any(|m| m.version == **version))
+        {
+            return Err(Box::new(MigrationError::UnknownApplied {
+                version: *version,
+            }));
+        }
+        let current = applied.keys().last().copied().unwrap_or(0);
+        if let Some(missing) = self
+            .migrations
+            .iter()
+            .find(|m| m.version < current && !applied.contains_key(&m.version))
+        {
+            return Err(Box::new(MigrationError::Gap {
+                version: missing.version,
+            }));
+        }
+
+        let target = match target {
+            Target::Latest => self.latest(),
+            Target::Version(0) => 0,
+            Target::Version(version) if self.migrations.iter().any(|m| m.version == version) => {
+                version
+            }
+            Target::Version(version) => {
+                return Err(Box::new(MigrationError::UnknownVersion { version }))
+            }
+        };
+        let planned = |migration: &Migration, direction| Planned {
+            version: migration.version,
+            name: migration.name,
+            direction,
+        };
+        let steps = if target >= current {
+            self.migrations
+                .iter()
+                .filter(|m| m.version > current && m.version <= target)
+                .map(|m| planned(m, Direction::Up))
+                .collect()
+        } else {
+            let mut steps = Vec::new();
+            for migration in self
+                .migrations
+                .iter()
+                .rev()
+                .filter(|m| m.version > target && m.version <= current)
+            {
+                if migration.down.is_none() {
+                    return Err(Box::new(MigrationError::Irreversible {
+                        version: migration.version,
+                        name: migration.name,
+                    }));
+                }
+                steps.push(planned(migration, Direction::Down));
+            }
+            steps
+        };
+        Ok(MigrationReport {
+            dry_run: true,
+            from: current,
+            to: target,
+            steps,
+        })
+    }
+
+    // Brings the store to `target`. Stops at the first migration that fails; the ones before it
+    // stay applied.
+    pub fn run(&self, target: Target, dry_run: bool) -> Result<MigrationReport> {
+        let mut report = self.plan(target)?;
+        if dry_run {
+            return Ok(report);
+        }
+        for planned in &report.steps {
+            let migration = self
+                .migrations
+                .iter()
+                .find(|m| m.version == planned.version)
+                .expect("planned migrations exist");
+            let steps = match planned.direction {
+                Direction::Up => migration.up.as_slice(),
+                Direction::Down => migration.down.as_deref().unwrap_or_default(),
+            };
+            self.store
+                .apply(migration, planned.direction, steps, self.clock.now())?;
+        }
+        report.dry_run = false;
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_units() {
+        let mut section = json!({
+            "line_items": [{ "unit": "sq ft" }, { "unit": "Tons" }],
+            "sections": [{ "line_items": [{ "unit": "ea" }], "sections": [] }],
+        });
+        normalize_units(section.as_object_mut().unwrap()).unwrap();
+        assert_eq!(section["line_items"][0]["unit"], "SF");
+        assert_eq!(section["line_items"][1]["unit"], "TON");
+        assert_eq!(section["sections"][0]["line_items"][0]["unit"], "EA");
+
+        let mut section = json!({ "line_items": [{ "unit": "bundle" }] });
+        let error = normalize_units(section.as_object_mut().unwrap()).unwrap_err();
+        assert_eq!(error.to_string(), "Unknown unit of measure: bundle");
+    }
+
+    #[test]
+    fn test_price_references_round_trip() {
+        let mut section = json!({
+            "line_items": [{ "unit": "SF" }],
+            "sections": [{ "line_items": [{ "unit": "EA" }] }],
+        });
+        let before = section.clone();
+        add_price_references(section.as_object_mut().unwrap()).unwrap();
+        assert_eq!(section["line_items"][0]["price_reference"], Value::Null);
+        assert!(section["sections"][0]["line_items"][0]
+            .get("price_reference")
+            .is_some());
+
+        drop_price_references(section.as_object_mut().unwrap()).unwrap();
+        assert_eq!(section, before);
+    }
+
+    #[test]
+    fn test_metric_ton_code_round_trip() {
+        let mut section = json!({
+            "line_items": [{ "unit": "T" }, { "unit": "TON" }],
+            "sections": [{ "line_items": [{ "unit": "T" }] }],
+        });
+        let before = section.clone();
+        metric_ton_code_up(section.as_object_mut().unwrap()).unwrap();
+        assert_eq!(section["line_items"][0]["unit"], "MT");
+        assert_eq!(section["line_items"][1]["unit"], "TON");
+        assert_eq!(section["sections"][0]["line_items"][0]["unit"], "MT");
+
+        metric_ton_code_down(section.as_object_mut().unwrap()).unwrap();
+        assert_eq!(section, before);
+    }
+}
@@ -0,0 +1,77 @@
+//presenter/csv_export.rs
+use super::error::Error as PresenterError;
+use crate::result::*;
+
+use crate::dto::estimate_report_dto::{EstimateReportDTO, ReportRowDTO};
+
+pub const CSV_EXPORT_HEADERS: [&str; 7] = [
+    "WBS",
+    "Code",
+    "Description",
+    "Quantity",
+    "Unit",
+    "Unit Cost",
+    "Total",
+];
+
+pub struct CsvEstimateExporter;
+
+impl CsvEstimateExporter {
+    // One row per section and line item in tree order, descriptions indented two spaces per
+    // level, followed by the subtotal, each markup and the grand total.
+    pub fn present(report: &EstimateReportDTO) -> Result<String> {
+        let mut writer = csv::Writer::from_writer(vec![]);
+
+        let mut write = |record: Vec<String>| {
+            writer
+                .write_record(&record)
+                .map_err(|e| Self::error(e.to_string()))
+        };
+
+        write(CSV_EXPORT_HEADERS.iter().map(|h| h.to_string()).collect())?;
+        for row in &report.rows {
+            write(Self::row_record(row))?;
+        }
+        write(Self::summary_record("Subtotal", report.subtotal))?;
+        for markup in &report.markups {
+            write(Self::summary_record(
+                &format!("{} ({}%)", markup.name, markup.rate),
+                markup.amount,
+            ))?;
+        }
+        write(Self::summary_record("Total", report.total))?;
+
+        let bytes = writer
+            .into_inner()
+            .map_err(|e| Self::error(e.to_string()))?;
+        Ok(String::from_utf8(bytes).map_err(|e| Self::error(e.to_string()))?)
+    }
+
+    fn row_record(row: &ReportRowDTO) -> Vec<String> {
+        vec![
+            row.wbs.clone(),
+            row.code.clone(),
+            format!("{}{}", "  ".repeat(row.depth), row.description),
+            row.quantity.map(|q| q.to_string()).unwrap_or_default(),
+            row.unit.map(|unit| unit.to_string()).unwrap_or_default(),
+            row.unit_cost
+                .map(|c| format!("{:.2}", c))
+                .unwrap_or_default(),
+            format!("{:.2}", row.total),
+        ]
+    }
+
+    fn summary_record(label: &str, amount: f64) -> Vec<String> {
+        let mut record = vec![String::new(); CSV_EXPORT_HEADERS.len()];
+        record[2] = label.to_string();
+        record[6] = format!("{:.2}", amount);
+        record
+    }
+
+    fn error(message: String) -> Box<PresenterError> {
+        Box::new(PresenterError::ExportError {
+            format: "CSV",
+            message,
+        })
+    }
+}
@@ -0,0 +1,122 @@
+//presenter/currency.rs
+
+// Currencies amounts can be shown in. Reports carry bare numbers; the presenters format them in
+// the currency they are given, which the application takes from its configuration.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Currency {
+    // ISO 4217 code, e.g. "USD".
+    pub code: &'static str,
+    pub symbol: &'static str,
+    // Digits after the decimal point.
+    pub minor_units: u32,
+}
+
+pub const USD: Currency = Currency {
+    code: "USD",
+    symbol: "$",
+    minor_units: 2,
+};
+
+pub const CURRENCIES: &[Currency] = &[
+    USD,
+    Currency {
+        code: "CAD",
+        symbol: "CA$",
+        minor_units: 2,
+    },
+    Currency {
+        code: "AUD",
+        symbol: "A$",
+        minor_units: 2,
+    },
+    Currency {
+        code: "NZD",
+        symbol: "NZ$",
+        minor_units: 2,
+    },
+    Currency {
+        code: "MXN",
+        symbol: "MX$",
+        minor_units: 2,
+    },
+    Currency {
+        code: "EUR",
+        symbol: "€",
+        minor_units: 2,
+    },
+    Currency {
+        code: "GBP",
+        symbol: "£",
+        minor_units: 2,
+    },
+    Currency {
+        code: "CHF",
+        symbol: "CHF ",
+        minor_units: 2,
+    },
+    Currency {
+        code: "JPY",
+        symbol: "¥",
+        minor_units: 0,
+    },
+];
+
+impl Currency {
+    // Case-insensitive lookup by ISO code.
+    pub fn from_code(code: &str) -> Option<Currency> {
+        CURRENCIES
+            .iter()
+            .find(|currency| currency.code.eq_ignore_ascii_case(code))
+            .copied()
+    }
+
+    // "$12,345.67"; negative amounts keep the sign in front of the symbol.
+    pub fn format(&self, amount: f64) -> String {
+        let scale = 10u64.pow(self.minor_units);
+        let minor = (amount.abs() * scale as f64).round() as u64;
+        let whole = (minor / scale).to_string();
+        let mut grouped = String::new();
+        for (index, digit) in whole.chars().enumerate() {
+            if index > 0 && (whole.len() - index).is_multiple_of(3) {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        let sign = if amount < 0.0 && minor > 0 { "-" } else { "" };
+        if self.minor_units == 0 {
+            format!("{}{}{}", sign, self.symbol, grouped)
+        } else {
+            format!(
+                "{}{}{}.{:0width$}",
+                sign,
+                self.symbol,
+                grouped,
+                minor % scale,
+                width = self.minor_units as usize
+            )
+        }
+    }
+}
+
+impl Default for Currency {
+    fn default() -> Self {
+        USD
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_in_other_currencies() {
+        let euro = Currency::from_code("eur").unwrap();
+        assert_eq!(euro.format(1234.5), "€1,234.50");
+
+        let yen = Currency::from_code("JPY").unwrap();
+        assert_eq!(yen.format(-1234567.4), "-¥1,234,567");
+
+        assert_eq!(Currency::from_code("XYZ"), None);
+    }
+}
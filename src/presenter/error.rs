@@ -8,27 +8,26 @@ use serde_with::{serde_as, DisplayFromStr};
 use uuid::Uuid;
 
 #[serde_as]
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Serialize, From, Display)]
 pub enum Error {
     #[display("Placeholder error: {}", message)]
     BasicError { status_code: u16, message: String },
+    #[display("Error exporting {}: {}", format, message)]
+    ExportError {
+        format: &'static str,
+        message: String,
+    },
+    #[display("Invalid template: {}", issues.join("; "))]
+    InvalidTemplate { issues: Vec<String> },
+    #[display("Unknown output format: {} (expected json, table or text)", format)]
+    UnknownFormat { format: String },
 }
 
 impl std::error::Error for Error {}
 
 impl From<Error> for MainError {
     fn from(error: Error) -> Self {
-        match error {
-            Error::BasicError {
-                status_code,
-                message,
-            } => MainError::PresenterError(
-                Error::BasicError {
-                    status_code,
-                    message,
-                }
-                .into(),
-            ),
-        }
+        MainError::PresenterError(error)
     }
 }
@@ -2,18 +2,72 @@
 use super::error::Error as PresenterError;
 use crate::result::*;
 
+use serde_json::json;
+
+use super::formats::OutputFormat;
+use super::traits::{Presentable, Presenter, Record};
 use crate::controller::estimate_controller::CreateEstimateResponse;
+use crate::dto::estimate_dto::EstimateDTO;
+
+impl Presentable for EstimateDTO {
+    fn to_record(&self) -> Record {
+        vec![
+            ("name", json!(self.name)),
+            ("id", json!(self.id.to_string())),
+            ("description", json!(self.description)),
+            ("location", json!(self.location)),
+            ("status", json!(self.status.to_string())),
+            ("price_guess", json!(self.price_guess)),
+            ("is_template", json!(self.is_template)),
+            (
+                "markups",
+                json!(self
+                    .markups
+                    .iter()
+                    .map(|markup| format!("{} ({}%)", markup.name, markup.rate))
+                    .collect::<Vec<_>>()),
+            ),
+            ("exclusions", json!(self.exclusions)),
+        ]
+    }
+}
 
-pub struct EstimatePresenter;
+pub struct EstimatePresenter {
+    presenter: Box<dyn Presenter>,
+}
 
 impl EstimatePresenter {
-    pub fn present(response: CreateEstimateResponse) -> Result<String> {
-        match response.status_code {
-            200 => Ok(format!("Success: {}", response.message)),
+    pub fn new(format: OutputFormat) -> Self {
+        EstimatePresenter {
+            presenter: format.presenter(),
+        }
+    }
+
+    pub fn present(&self, response: &CreateEstimateResponse) -> Result<String> {
+        match (response.status_code, response.estimate_id) {
+            (200, Some(estimate_id)) => self.presenter.present_record(&vec![
+                ("message", json!(response.message)),
+                ("estimate_id", json!(estimate_id.to_string())),
+            ]),
             _ => Err(Box::new(PresenterError::BasicError {
                 status_code: response.status_code,
-                message: response.message,
+                message: response.message.clone(),
             })),
         }
     }
+
+    pub fn present_estimate(&self, estimate: &EstimateDTO) -> Result<String> {
+        self.presenter.present_record(&estimate.to_record())
+    }
+
+    pub fn present_estimates(&self, estimates: &[EstimateDTO]) -> Result<String> {
+        let records: Vec<Record> = estimates.iter().map(Presentable::to_record).collect();
+        self.presenter.present_records(&records)
+    }
+}
+
+impl Default for EstimatePresenter {
+    fn default() -> Self {
+        EstimatePresenter::new(OutputFormat::default())
+    }
 }
@@ -0,0 +1,304 @@
+//presenter/formats.rs
+use super::error::Error as PresenterError;
+use crate::result::*;
+
+use std::str::FromStr;
+
+use derive_more::Display;
+use serde_json::{Map, Value};
+
+use super::traits::{Presenter, Record, TreeNode};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Display)]
+pub enum OutputFormat {
+    #[display("json")]
+    Json,
+    #[display("table")]
+    Table,
+    #[default]
+    #[display("text")]
+    Text,
+}
+
+impl OutputFormat {
+    pub fn presenter(&self) -> Box<dyn Presenter> {
+        match self {
+            OutputFormat::Json => Box::new(JsonPresenter),
+            OutputFormat::Table => Box::new(TablePresenter),
+            OutputFormat::Text => Box::new(PlainTextPresenter),
+        }
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = PresenterError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(OutputFormat::Json),
+            "table" => Ok(OutputFormat::Table),
+            "text" | "plain" => Ok(OutputFormat::Text),
+            _ => Err(PresenterError::UnknownFormat {
+                format: s.to_string(),
+            }),
+        }
+    }
+}
+
+// Strings are shown without quotes, lists as comma separated values and null as blank.
+fn display_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(display_value)
+            .collect::<Vec<_>>()
+            .join(", "),
+        other => other.to_string(),
+    }
+}
+
+// region:    --- JSON
+
+pub struct JsonPresenter;
+
+impl JsonPresenter {
+    fn object(record: &Record) -> Map<String, Value> {
+        record
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+
+    fn tree(nodes: &[TreeNode]) -> Value {
+        Value::Array(
+            nodes
+                .iter()
+                .map(|node| {
+                    let mut object = Self::object(&node.record);
+                    object.insert("children".to_string(), Self::tree(&node.children));
+                    Value::Object(object)
+                })
+                .collect(),
+        )
+    }
+
+    fn to_string(value: &Value) -> Result<String> {
+        Ok(serde_json::to_string_pretty(value)?)
+    }
+}
+
+impl Presenter for JsonPresenter {
+    fn present_record(&self, record: &Record) -> Result<String> {
+        Self::to_string(&Value::Object(Self::object(record)))
+    }
+
+    fn present_records(&self, records: &[Record]) -> Result<String> {
+        Self::to_string(&Value::Array(
+            records
+                .iter()
+                .map(|record| Value::Object(Self::object(record)))
+                .collect(),
+        ))
+    }
+
+    fn present_tree(&self, nodes: &[TreeNode]) -> Result<String> {
+        Self::to_string(&Self::tree(nodes))
+    }
+}
+
+// endregion: --- JSON
+
+// region:    --- Table
+
+// Columns padded to their widest value under an upper-case header. In trees the first
+// column is indented two spaces per level.
+pub struct TablePresenter;
+
+impl TablePresenter {
+    fn table(rows: &[(usize, &Record)]) -> String {
+        let Some((_, first)) = rows.first() else {
+            return "(none)".to_string();
+        };
+        let headers: Vec<String> = first.iter().map(|(key, _)| key.to_uppercase()).collect();
+        let cells: Vec<Vec<String>> = rows
+            .iter()
+            .map(|(depth, record)| {
+                record
+                    .iter()
+                    .enumerate()
+                    .map(|(col, (_, value))| {
+                        let indent = if col == 0 {
+                            "  ".repeat(*depth)
+                        } else {
+                            String::new()
+                        };
+                        format!("{}{}", indent, display_value(value))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+        for row in &cells {
+            for (col, cell) in row.iter().enumerate() {
+                if let Some(width) = widths.get_mut(col) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+        }
+
+        let line = |row: &[String]| {
+            row.iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+        let separator: Vec<String> = widths.iter().map(|width| "-".repeat(*width)).collect();
+
+        let mut lines = vec![line(&headers), line(&separator)];
+        lines.extend(cells.iter().map(|row| line(row)));
+        lines.join("\n")
+    }
+
+    fn flatten<'a>(nodes: &'a [TreeNode], depth: usize, rows: &mut Vec<(usize, &'a Record)>) {
+        for node in nodes {
+            rows.push((depth, &node.record));
+            Self::flatten(&node.children, depth + 1, rows);
+        }
+    }
+}
+
+impl Presenter for TablePresenter {
+    fn present_record(&self, record: &Record) -> Result<String> {
+        Ok(Self::table(&[(0, record)]))
+    }
+
+    fn present_records(&self, records: &[Record]) -> Result<String> {
+        let rows: Vec<(usize, &Record)> = records.iter().map(|record| (0, record)).collect();
+        Ok(Self::table(&rows))
+    }
+
+    fn present_tree(&self, nodes: &[TreeNode]) -> Result<String> {
+        let mut rows = vec![];
+        Self::flatten(nodes, 0, &mut rows);
+        Ok(Self::table(&rows))
+    }
+}
+
+// endregion: --- Table
+
+// region:    --- Plain Text
+
+// "key: value" lines, records separated by a blank line. Trees show each node's first value
+// as a bullet with the remaining fields beneath it.
+pub struct PlainTextPresenter;
+
+impl PlainTextPresenter {
+    fn lines(record: &Record, indent: &str) -> Vec<String> {
+        record
+            .iter()
+            .map(|(key, value)| format!("{}{}: {}", indent, key, display_value(value)))
+            .collect()
+    }
+
+    fn tree(nodes: &[TreeNode], depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        for node in nodes {
+            let mut fields = node.record.iter();
+            if let Some((_, value)) = fields.next() {
+                lines.push(format!("{}- {}", indent, display_value(value)));
+            }
+            for (key, value) in fields {
+                lines.push(format!("{}    {}: {}", indent, key, display_value(value)));
+            }
+            Self::tree(&node.children, depth + 1, lines);
+        }
+    }
+}
+
+impl Presenter for PlainTextPresenter {
+    fn present_record(&self, record: &Record) -> Result<String> {
+        Ok(Self::lines(record, "").join("\n"))
+    }
+
+    fn present_records(&self, records: &[Record]) -> Result<String> {
+        if records.is_empty() {
+            return Ok("(none)".to_string());
+        }
+        Ok(records
+            .iter()
+            .map(|record| Self::lines(record, "").join("\n"))
+            .collect::<Vec<_>>()
+            .join("\n\n"))
+    }
+
+    fn present_tree(&self, nodes: &[TreeNode]) -> Result<String> {
+        if nodes.is_empty() {
+            return Ok("(none)".to_string());
+        }
+        let mut lines = vec![];
+        Self::tree(nodes, 0, &mut lines);
+        Ok(lines.join("\n"))
+    }
+}
+
+// endregion: --- Plain Text
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tree() -> Vec<TreeNode> {
+        vec![TreeNode {
+            record: vec![("name", json!("Sitework")), ("total", json!(2000.0))],
+            children: vec![TreeNode {
+                record: vec![("name", json!("Paving")), ("total", json!(1500.5))],
+                children: vec![],
+            }],
+        }]
+    }
+
+    #[test]
+    fn test_each_format_renders_the_same_tree() {
+        let json = OutputFormat::Json
+            .presenter()
+            .present_tree(&tree())
+            .unwrap();
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value[0]["children"][0]["name"], "Paving");
+
+        let table = OutputFormat::Table
+            .presenter()
+            .present_tree(&tree())
+            .unwrap();
+        assert_eq!(
+            table,
+            "NAME      TOTAL\n\
+             --------  ------\n\
+             Sitework  2000.0\n  \
+             Paving  1500.5"
+        );
+
+        let text = OutputFormat::Text
+            .presenter()
+            .present_tree(&tree())
+            .unwrap();
+        assert_eq!(
+            text,
+            "- Sitework\n    total: 2000.0\n  - Paving\n      total: 1500.5"
+        );
+    }
+
+    #[test]
+    fn test_output_format_parses_names() {
+        assert_eq!("JSON".parse::<OutputFormat>().unwrap(), OutputFormat::Json);
+        assert_eq!("plain".parse::<OutputFormat>().unwrap(), OutputFormat::Text);
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+}
@@ -1,4 +1,15 @@
 //presenter/mod.rs
 
+pub mod csv_export;
+pub mod currency;
 pub mod error;
 pub mod estimate_presenter;
+pub mod formats;
+pub mod pdf_writer;
+pub mod proposal_presenter;
+pub mod section_presenter;
+pub mod template_engine;
+pub mod template_presenter;
+pub mod traits;
+#[cfg(feature = "xlsx")]
+pub mod xlsx_export;
@@ -0,0 +1,331 @@
+//presenter/proposal_presenter.rs
+use chrono::NaiveDate;
+
+use super::currency::Currency;
+use super::pdf_writer::{wrap_text, PdfDocument, PdfFont, PAGE_HEIGHT, PAGE_WIDTH};
+use crate::dto::estimate_report_dto::{EstimateReportDTO, ReportRowKind};
+
+const MARGIN: f32 = 72.0;
+const RIGHT: f32 = PAGE_WIDTH - MARGIN;
+const BOTTOM: f32 = MARGIN + 24.0;
+const BODY: f32 = 10.0;
+
+pub struct ProposalOptions {
+    pub date: NaiveDate,
+    // Lists every line item under its section instead of section totals only.
+    pub include_line_items: bool,
+    pub prepared_by: Option<String>,
+    pub currency: Currency,
+}
+
+impl ProposalOptions {
+    pub fn new(date: NaiveDate) -> Self {
+        ProposalOptions {
+            date,
+            include_line_items: false,
+            prepared_by: None,
+            currency: Currency::default(),
+        }
+    }
+}
+
+pub struct ProposalPresenter;
+
+impl ProposalPresenter {
+    // Renders a client-facing bid proposal: a cover page, then the section summary with
+    // optional line items, the markups and total, the exclusions and a signature block.
+    pub fn present(report: &EstimateReportDTO, options: &ProposalOptions) -> Vec<u8> {
+        let mut layout = Layout::new(options.currency);
+
+        Self::cover_page(&mut layout, report, options);
+
+        layout.new_page();
+        layout.heading("Scope of Work");
+        layout.table_header();
+        for row in &report.rows {
+            let indent = MARGIN + 60.0 + 12.0 * row.depth as f32;
+            match row.kind {
+                ReportRowKind::Section => {
+                    layout.ensure(BODY + 6.0);
+                    let y = layout.y;
+                    let page = layout.page();
+                    page.text(MARGIN, y, BODY, PdfFont::Bold, &row.code);
+                    page.text(indent, y, BODY, PdfFont::Bold, &row.description);
+                    page.text_right(
+                        RIGHT,
+                        y,
+                        BODY,
+                        PdfFont::Bold,
+                        &options.currency.format(row.total),
+                    );
+                    layout.advance(BODY + 6.0);
+                }
+                ReportRowKind::LineItem if options.include_line_items => {
+                    layout.ensure(BODY + 4.0);
+                    let detail = format!(
+                        "{} {} @ {}",
+                        quantity(row.quantity.unwrap_or_default()),
+                        row.unit.map(|unit| unit.code()).unwrap_or_default(),
+                        options.currency.format(row.unit_cost.unwrap_or_default())
+                    );
+                    let y = layout.y;
+                    let page = layout.page();
+                    page.text(indent, y, BODY - 1.0, PdfFont::Regular, &row.description);
+                    page.text_right(RIGHT - 110.0, y, BODY - 1.0, PdfFont::Regular, &detail);
+                    page.text_right(
+                        RIGHT,
+                        y,
+                        BODY - 1.0,
+                        PdfFont::Regular,
+                        &options.currency.format(row.total),
+                    );
+                    layout.advance(BODY + 4.0);
+                }
+                ReportRowKind::LineItem => {}
+            }
+        }
+        if report.rows.is_empty() {
+            layout.paragraph(
+                "No sections have been added to this estimate.",
+                PdfFont::Regular,
+            );
+        }
+
+        layout.ensure(40.0 + 16.0 * report.markups.len() as f32);
+        layout.rule();
+        layout.amount_line("Subtotal", report.subtotal, PdfFont::Regular);
+        for markup in &report.markups {
+            let label = format!("{} ({}%)", markup.name, markup.rate);
+            layout.amount_line(&label, markup.amount, PdfFont::Regular);
+        }
+        layout.amount_line("Total", report.total, PdfFont::Bold);
+
+        layout.advance(12.0);
+        layout.heading("Exclusions");
+        if report.estimate.exclusions.is_empty() {
+            layout.paragraph("None.", PdfFont::Regular);
+        }
+        for exclusion in &report.estimate.exclusions {
+            layout.paragraph(&format!("- {}", exclusion), PdfFont::Regular);
+        }
+
+        Self::signature_block(&mut layout, options);
+        layout.finish(&report.estimate.name)
+    }
+
+    fn cover_page(layout: &mut Layout, report: &EstimateReportDTO, options: &ProposalOptions) {
+        layout.y = PAGE_HEIGHT - 2.5 * MARGIN;
+        let y = layout.y;
+        let page = layout.page();
+        page.text(MARGIN, y, 28.0, PdfFont::Bold, "Bid Proposal");
+        layout.advance(40.0);
+        layout.paragraph_sized(&report.estimate.name, 18.0, PdfFont::Bold);
+        layout.advance(8.0);
+        layout.paragraph(&report.estimate.description, PdfFont::Regular);
+        layout.advance(16.0);
+
+        let date = options.date.format("%B %-d, %Y").to_string();
+        let mut details = vec![
+            ("Location", report.estimate.location.clone()),
+            ("Date", date),
+        ];
+        if let Some(prepared_by) = &options.prepared_by {
+            details.push(("Prepared by", prepared_by.clone()));
+        }
+        details.push(("Proposal total", options.currency.format(report.total)));
+        for (label, value) in details {
+            let y = layout.y;
+            let page = layout.page();
+            page.text(MARGIN, y, 12.0, PdfFont::Bold, label);
+            page.text(MARGIN + 110.0, y, 12.0, PdfFont::Regular, &value);
+            layout.advance(18.0);
+        }
+    }
+
+    fn signature_block(layout: &mut Layout, options: &ProposalOptions) {
+        layout.ensure(150.0);
+        layout.advance(12.0);
+        layout.heading("Acceptance");
+        layout.paragraph(
+            "The prices, scope and exclusions above are accepted. You are authorized to do the \
+             work as specified.",
+            PdfFont::Regular,
+        );
+        layout.advance(28.0);
+
+        let middle = PAGE_WIDTH / 2.0;
+        for (label, x) in [("Accepted by", MARGIN), ("Submitted by", middle + 12.0)] {
+            let y = layout.y;
+            let page = layout.page();
+            page.line(x, y, x + 200.0, y);
+            page.text(x, y - 12.0, BODY - 1.0, PdfFont::Regular, label);
+        }
+        if let Some(prepared_by) = &options.prepared_by {
+            let y = layout.y + 4.0;
+            layout
+                .page()
+                .text(middle + 12.0, y, BODY, PdfFont::Regular, prepared_by);
+        }
+        layout.advance(40.0);
+        for x in [MARGIN, middle + 12.0] {
+            let y = layout.y;
+            let page = layout.page();
+            page.line(x, y, x + 200.0, y);
+            page.text(x, y - 12.0, BODY - 1.0, PdfFont::Regular, "Date");
+        }
+        layout.advance(24.0);
+    }
+}
+
+// Tracks the write position and starts a new page when the next block would not fit.
+struct Layout {
+    document: PdfDocument,
+    y: f32,
+    currency: Currency,
+}
+
+impl Layout {
+    fn new(currency: Currency) -> Self {
+        let mut document = PdfDocument::new();
+        document.add_page();
+        Layout {
+            document,
+            y: PAGE_HEIGHT - MARGIN,
+            currency,
+        }
+    }
+
+    fn page(&mut self) -> &mut super::pdf_writer::PdfPage {
+        self.document.pages_mut().last_mut().unwrap()
+    }
+
+    fn new_page(&mut self) {
+        self.document.add_page();
+        self.y = PAGE_HEIGHT - MARGIN;
+    }
+
+    fn ensure(&mut self, height: f32) {
+        if self.y - height < BOTTOM {
+            self.new_page();
+        }
+    }
+
+    fn advance(&mut self, height: f32) {
+        self.y -= height;
+    }
+
+    fn heading(&mut self, text: &str) {
+        self.ensure(30.0);
+        let y = self.y;
+        self.page().text(MARGIN, y, 14.0, PdfFont::Bold, text);
+        self.advance(22.0);
+    }
+
+    fn table_header(&mut self) {
+        let y = self.y;
+        let page = self.page();
+        page.text(MARGIN, y, BODY - 1.0, PdfFont::Bold, "CODE");
+        page.text(MARGIN + 60.0, y, BODY - 1.0, PdfFont::Bold, "DESCRIPTION");
+        page.text_right(RIGHT, y, BODY - 1.0, PdfFont::Bold, "AMOUNT");
+        page.line(MARGIN, y - 4.0, RIGHT, y - 4.0);
+        self.advance(18.0);
+    }
+
+    fn paragraph(&mut self, text: &str, font: PdfFont) {
+        self.paragraph_sized(text, BODY, font);
+    }
+
+    fn paragraph_sized(&mut self, text: &str, size: f32, font: PdfFont) {
+        for line in wrap_text(text, size, RIGHT - MARGIN) {
+            self.ensure(size + 4.0);
+            let y = self.y;
+            self.page().text(MARGIN, y, size, font, &line);
+            self.advance(size + 4.0);
+        }
+    }
+
+    fn rule(&mut self) {
+        let y = self.y + 6.0;
+        self.page().line(MARGIN, y, RIGHT, y);
+        self.advance(8.0);
+    }
+
+    fn amount_line(&mut self, label: &str, amount: f64, font: PdfFont) {
+        let y = self.y;
+        let amount = self.currency.format(amount);
+        let page = self.page();
+        page.text_right(RIGHT - 110.0, y, BODY, font, label);
+        page.text_right(RIGHT, y, BODY, font, &amount);
+        self.advance(BODY + 6.0);
+    }
+
+    // Stamps "name - Page x of y" in the footer of every page but the cover.
+    fn finish(mut self, name: &str) -> Vec<u8> {
+        let pages = self.document.pages_mut();
+        let count = pages.len();
+        for (index, page) in pages.iter_mut().enumerate().skip(1) {
+            page.text(MARGIN, MARGIN - 24.0, 8.0, PdfFont::Regular, name);
+            page.text_right(
+                RIGHT,
+                MARGIN - 24.0,
+                8.0,
+                PdfFont::Regular,
+                &format!("Page {} of {}", index + 1, count),
+            );
+        }
+        self.document.to_bytes()
+    }
+}
+
+fn quantity(quantity: f64) -> String {
+    if quantity.fract() == 0.0 {
+        format!("{}", quantity)
+    } else {
+        format!("{:.2}", quantity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::estimate_dto::EstimateDTO;
+    use crate::dto::section_dto::SectionDTO;
+    use crate::entity::estimate::Estimate;
+    use crate::entity::line_item::LineItem;
+    use crate::entity::section::Section;
+    use crate::entity::unit::Unit;
+
+    #[test]
+    fn test_money_groups_thousands() {
+        let usd = Currency::default();
+        assert_eq!(usd.format(0.0), "$0.00");
+        assert_eq!(usd.format(1234567.891), "$1,234,567.89");
+        assert_eq!(usd.format(-950.5), "-$950.50");
+    }
+
+    #[test]
+    fn test_proposal_is_a_complete_pdf() {
+        let mut estimate = EstimateDTO::new();
+        estimate.name = "Retail (Phase 2)".to_string();
+        estimate.description = "Tenant improvements for the ground floor suites".to_string();
+        estimate.exclusions = vec!["Permits and fees".to_string()];
+        let mut section = Section::from(SectionDTO::new("Drywall".to_string(), "09".to_string()));
+        section.line_items = (0..80)
+            .map(|i| LineItem::new(format!("Partition {}", i), 10.0, Unit::LinearFoot, 42.0))
+            .collect();
+        let report = EstimateReportDTO::new(Estimate::from(estimate), vec![section]);
+
+        let mut options = ProposalOptions::new(NaiveDate::from_ymd_opt(2024, 3, 5).unwrap());
+        options.include_line_items = true;
+        let pdf = ProposalPresenter::present(&report, &options);
+        let text = String::from_utf8_lossy(&pdf);
+
+        assert!(pdf.starts_with(b"%PDF-1.4"));
+        assert!(text.ends_with("%%EOF\n"));
+        assert!(text.contains("(Retail \\(Phase 2\\)) Tj"));
+        assert!(text.contains("(March 5, 2024) Tj"));
+        assert!(text.contains("(- Permits and fees) Tj"));
+        // Eighty line items do not fit on one page after the cover.
+        assert!(text.contains("/Count 4"));
+    }
+}
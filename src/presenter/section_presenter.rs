@@ -0,0 +1,107 @@
+//presenter/section_presenter.rs
+use crate::result::*;
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::json;
+use uuid::Uuid;
+
+use super::formats::OutputFormat;
+use super::traits::{Presentable, Presenter, Record, TreeNode};
+use crate::dto::section_dto::SectionDTO;
+
+impl Presentable for SectionDTO {
+    fn to_record(&self) -> Record {
+        let line_items = self.line_items.as_deref().unwrap_or_default();
+        vec![
+            ("name", json!(self.name)),
+            ("code", json!(self.code)),
+            ("id", json!(self.get_id().to_string())),
+            ("description", json!(self.description)),
+            ("position", json!(self.position)),
+            ("line_items", json!(line_items.len())),
+            (
+                "total",
+                json!(line_items
+                    .iter()
+                    .fold(0.0, |total, item| total + item.total())),
+            ),
+        ]
+    }
+}
+
+pub struct SectionPresenter {
+    presenter: Box<dyn Presenter>,
+}
+
+impl SectionPresenter {
+    pub fn new(format: OutputFormat) -> Self {
+        SectionPresenter {
+            presenter: format.presenter(),
+        }
+    }
+
+    pub fn present_section(&self, section: &SectionDTO) -> Result<String> {
+        self.presenter.present_record(&section.to_record())
+    }
+
+    // Nests the sections of an estimate under their parents. Sections whose parent is not in
+    // the list are shown at the top level; siblings keep the order they are given in.
+    pub fn present_tree(&self, sections: &[SectionDTO]) -> Result<String> {
+        self.presenter.present_tree(&Self::build_tree(sections))
+    }
+
+    fn build_tree(sections: &[SectionDTO]) -> Vec<TreeNode> {
+        let ids: HashSet<Uuid> = sections.iter().map(SectionDTO::get_id).collect();
+        let mut children: HashMap<Option<Uuid>, Vec<&SectionDTO>> = HashMap::new();
+        for section in sections {
+            let parent = section.parent_id.filter(|id| ids.contains(id));
+            children.entry(parent).or_default().push(section);
+        }
+        Self::nodes(None, &children)
+    }
+
+    fn nodes(
+        parent: Option<Uuid>,
+        children: &HashMap<Option<Uuid>, Vec<&SectionDTO>>,
+    ) -> Vec<TreeNode> {
+        children
+            .get(&parent)
+            .map(|sections| {
+                sections
+                    .iter()
+                    .map(|section| TreeNode {
+                        record: section.to_record(),
+                        children: Self::nodes(Some(section.get_id()), children),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl Default for SectionPresenter {
+    fn default() -> Self {
+        SectionPresenter::new(OutputFormat::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sections_are_nested_under_their_parents() {
+        let site = SectionDTO::new("Sitework".to_string(), "02".to_string());
+        let mut paving = SectionDTO::new("Paving".to_string(), "02-700".to_string());
+        paving.parent_id = Some(site.get_id());
+        let mut orphan = SectionDTO::new("Orphan".to_string(), "99".to_string());
+        orphan.parent_id = Some(Uuid::new_v4());
+
+        let tree = SectionPresenter::build_tree(&[site, paving, orphan]);
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].children[0].record[0].1, json!("Paving"));
+        assert_eq!(tree[1].record[0].1, json!("Orphan"));
+    }
+}
@@ -0,0 +1,339 @@
+//presenter/template_engine.rs
+
+// A small logic-less template language over a serialized view model:
+//   {{ path.to.field }}                   value, escaped for the output format
+//   {{#each path}} ... {{/each}}          repeats for every element; `this` is the element
+//   {{#if path}} ... {{else}} ... {{/if}} false, null, 0, "" and [] are falsy
+//   {{! comment }}
+// Names are looked up in the innermost scope first, then in the enclosing ones.
+
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateFormat {
+    Html,
+    Markdown,
+}
+
+impl TemplateFormat {
+    fn escape(&self, text: &str) -> String {
+        match self {
+            TemplateFormat::Html => text
+                .replace('&', "&amp;")
+                .replace('<', "&lt;")
+                .replace('>', "&gt;")
+                .replace('"', "&quot;")
+                .replace('\'', "&#39;"),
+            // Keeps values from breaking out of table cells.
+            TemplateFormat::Markdown => text.replace('|', "\\|"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Text(String),
+    Value {
+        path: String,
+        line: usize,
+    },
+    Each {
+        path: String,
+        line: usize,
+        body: Vec<Node>,
+    },
+    If {
+        path: String,
+        line: usize,
+        then: Vec<Node>,
+        otherwise: Vec<Node>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    nodes: Vec<Node>,
+    format: TemplateFormat,
+}
+
+impl Template {
+    // Fails on syntax errors such as unclosed or mismatched blocks, with the line number.
+    pub fn parse(source: &str, format: TemplateFormat) -> std::result::Result<Self, String> {
+        let mut parser = Parser { source, pos: 0 };
+        let (nodes, end) = parser.nodes()?;
+        if let Some((tag, line)) = end {
+            return Err(format!("line {}: unexpected {{{{{}}}}}", line, tag));
+        }
+        Ok(Template { nodes, format })
+    }
+
+    pub fn format(&self) -> TemplateFormat {
+        self.format
+    }
+
+    // Checks every referenced field against a fully populated example of the view model and
+    // returns one message per field that does not exist.
+    pub fn validate(&self, example: &Value) -> Vec<String> {
+        let mut issues = vec![];
+        validate_nodes(&self.nodes, &mut vec![example], &mut issues);
+        issues
+    }
+
+    pub fn render(&self, model: &Value) -> String {
+        let mut out = String::new();
+        self.render_nodes(&self.nodes, &mut vec![model], &mut out);
+        out
+    }
+
+    fn render_nodes<'a>(&self, nodes: &'a [Node], scopes: &mut Vec<&'a Value>, out: &mut String)
+    where
+        Self: 'a,
+    {
+        for node in nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Value { path, .. } => match lookup(scopes, path) {
+                    Some(Value::String(s)) => out.push_str(&self.format.escape(s)),
+                    Some(Value::Null) | None => {}
+                    Some(value) => out.push_str(&self.format.escape(&value.to_string())),
+                },
+                Node::Each { path, body, .. } => {
+                    if let Some(Value::Array(items)) = lookup(scopes, path) {
+                        for item in items {
+                            scopes.push(item);
+                            self.render_nodes(body, scopes, out);
+                            scopes.pop();
+                        }
+                    }
+                }
+                Node::If {
+                    path,
+                    then,
+                    otherwise,
+                    ..
+                } => {
+                    if lookup(scopes, path).is_some_and(is_truthy) {
+                        self.render_nodes(then, scopes, out);
+                    } else {
+                        self.render_nodes(otherwise, scopes, out);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn lookup<'a>(scopes: &[&'a Value], path: &str) -> Option<&'a Value> {
+    let mut segments = path.split('.');
+    let first = segments.next()?;
+    let rest: Vec<&str> = segments.collect();
+    scopes.iter().rev().find_map(|scope| {
+        let mut value = if first == "this" {
+            *scope
+        } else {
+            scope.get(first)?
+        };
+        for segment in &rest {
+            value = value.get(segment)?;
+        }
+        Some(value)
+    })
+}
+
+fn is_truthy(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::Bool(b) => *b,
+        Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(_) => true,
+    }
+}
+
+fn validate_nodes<'a>(nodes: &'a [Node], scopes: &mut Vec<&'a Value>, issues: &mut Vec<String>) {
+    for node in nodes {
+        match node {
+            Node::Text(_) => {}
+            Node::Value { path, line } => {
+                if lookup(scopes, path).is_none() {
+                    issues.push(format!("line {}: unknown field `{}`", line, path));
+                }
+            }
+            Node::Each { path, line, body } => match lookup(scopes, path) {
+                Some(Value::Array(items)) => match items.first() {
+                    Some(item) => {
+                        scopes.push(item);
+                        validate_nodes(body, scopes, issues);
+                        scopes.pop();
+                    }
+                    None => validate_nodes(body, scopes, issues),
+                },
+                Some(_) => issues.push(format!("line {}: `{}` is not a list", line, path)),
+                None => issues.push(format!("line {}: unknown field `{}`", line, path)),
+            },
+            Node::If {
+                path,
+                line,
+                then,
+                otherwise,
+            } => {
+                if lookup(scopes, path).is_none() {
+                    issues.push(format!("line {}: unknown field `{}`", line, path));
+                }
+                validate_nodes(then, scopes, issues);
+                validate_nodes(otherwise, scopes, issues);
+            }
+        }
+    }
+}
+
+struct Parser<'s> {
+    source: &'s str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn line(&self, pos: usize) -> usize {
+        self.source[..pos].matches('\n').count() + 1
+    }
+
+    // Parses until the end of input or a closing/else tag, which is returned to the caller.
+    #[allow(clippy::type_complexity)]
+    fn nodes(&mut self) -> std::result::Result<(Vec<Node>, Option<(String, usize)>), String> {
+        let mut nodes = vec![];
+        loop {
+            let rest = &self.source[self.pos..];
+            let Some(start) = rest.find("{{") else {
+                if !rest.is_empty() {
+                    nodes.push(Node::Text(rest.to_string()));
+                }
+                self.pos = self.source.len();
+                return Ok((nodes, None));
+            };
+            if start > 0 {
+                nodes.push(Node::Text(rest[..start].to_string()));
+            }
+            let tag_pos = self.pos + start;
+            let line = self.line(tag_pos);
+            let Some(len) = self.source[tag_pos + 2..].find("}}") else {
+                return Err(format!("line {}: unclosed {{{{", line));
+            };
+            let tag = self.source[tag_pos + 2..tag_pos + 2 + len]
+                .trim()
+                .to_string();
+            self.pos = tag_pos + 2 + len + 2;
+
+            if tag.starts_with('!') {
+                continue;
+            } else if tag.starts_with('/') || tag == "else" {
+                return Ok((nodes, Some((tag, line))));
+            } else if let Some(path) = tag.strip_prefix("#each ") {
+                let (body, end) = self.nodes()?;
+                expect_end(end, "/each", line)?;
+                nodes.push(Node::Each {
+                    path: path.trim().to_string(),
+                    line,
+                    body,
+                });
+            } else if let Some(path) = tag.strip_prefix("#if ") {
+                let (then, end) = self.nodes()?;
+                let otherwise = match end {
+                    Some((ref tag, _)) if tag == "else" => {
+                        let (otherwise, end) = self.nodes()?;
+                        expect_end(end, "/if", line)?;
+                        otherwise
+                    }
+                    end => {
+                        expect_end(end, "/if", line)?;
+                        vec![]
+                    }
+                };
+                nodes.push(Node::If {
+                    path: path.trim().to_string(),
+                    line,
+                    then,
+                    otherwise,
+                });
+            } else if tag.is_empty() || tag.starts_with('#') || tag.contains(char::is_whitespace) {
+                return Err(format!("line {}: unsupported tag {{{{{}}}}}", line, tag));
+            } else {
+                nodes.push(Node::Value { path: tag, line });
+            }
+        }
+    }
+}
+
+fn expect_end(
+    end: Option<(String, usize)>,
+    expected: &str,
+    opened: usize,
+) -> std::result::Result<(), String> {
+    match end {
+        Some((tag, _)) if tag == expected => Ok(()),
+        Some((tag, line)) => Err(format!(
+            "line {}: expected {{{{{}}}}} but found {{{{{}}}}}",
+            line, expected, tag
+        )),
+        None => Err(format!(
+            "line {}: block is never closed with {{{{{}}}}}",
+            opened, expected
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_render_values_loops_and_conditions() {
+        let template = Template::parse(
+            "<h1>{{ name }}</h1>{{#each items}}<li>{{ this.label }} of {{ name }}</li>{{/each}}\
+             {{#if notes}}notes{{else}}none{{/if}}{{! ignored }}",
+            TemplateFormat::Html,
+        )
+        .unwrap();
+
+        let out = template.render(&json!({
+            "name": "A & B",
+            "items": [{ "label": "<x>" }, { "label": "y" }],
+            "notes": [],
+        }));
+
+        assert_eq!(
+            out,
+            "<h1>A &amp; B</h1><li>&lt;x&gt; of A &amp; B</li><li>y of A &amp; B</li>none"
+        );
+    }
+
+    #[test]
+    fn test_validate_reports_unknown_fields_with_lines() {
+        let template = Template::parse(
+            "{{ estimate.name }}\n{{#each sections}}\n{{ nmae }}{{/each}}\n{{ totl }}",
+            TemplateFormat::Markdown,
+        )
+        .unwrap();
+
+        let issues = template.validate(&json!({
+            "estimate": { "name": "x" },
+            "sections": [{ "name": "y" }],
+        }));
+
+        assert_eq!(
+            issues,
+            vec![
+                "line 3: unknown field `nmae`",
+                "line 4: unknown field `totl`"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_unclosed_blocks() {
+        let error =
+            Template::parse("{{#each a}}\n{{#if b}}{{/each}}", TemplateFormat::Html).unwrap_err();
+        assert_eq!(error, "line 2: expected {{/if}} but found {{/each}}");
+    }
+}
@@ -0,0 +1,280 @@
+//presenter/template_presenter.rs
+use super::error::Error as PresenterError;
+use crate::result::*;
+
+use std::path::Path;
+
+use chrono::NaiveDate;
+use serde::Serialize;
+
+use super::currency::Currency;
+use super::template_engine::{Template, TemplateFormat};
+use crate::dto::estimate_dto::EstimateDTO;
+use crate::dto::estimate_report_dto::{EstimateReportDTO, ReportRowDTO, ReportRowKind};
+use crate::dto::section_dto::SectionDTO;
+use crate::entity::estimate::Estimate;
+use crate::entity::line_item::LineItem;
+use crate::entity::markup::Markup;
+use crate::entity::section::Section;
+use crate::entity::unit::Unit;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuiltInTemplate {
+    HtmlProposal,
+    MarkdownReport,
+}
+
+impl BuiltInTemplate {
+    fn source(&self) -> &'static str {
+        match self {
+            BuiltInTemplate::HtmlProposal => include_str!("templates/proposal.html"),
+            BuiltInTemplate::MarkdownReport => include_str!("templates/report.md"),
+        }
+    }
+
+    fn format(&self) -> TemplateFormat {
+        match self {
+            BuiltInTemplate::HtmlProposal => TemplateFormat::Html,
+            BuiltInTemplate::MarkdownReport => TemplateFormat::Markdown,
+        }
+    }
+}
+
+// What templates see. Sections are flattened in tree order with their depth; amounts come
+// both as numbers and pre-formatted `*_display` strings.
+#[derive(Debug, Serialize)]
+pub struct EstimateViewModel {
+    pub estimate: EstimateView,
+    pub date: String,
+    pub sections: Vec<SectionView>,
+    pub subtotal: f64,
+    pub subtotal_display: String,
+    pub markups: Vec<MarkupView>,
+    pub total: f64,
+    pub total_display: String,
+    pub exclusions: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EstimateView {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub location: String,
+    pub status: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SectionView {
+    pub wbs: String,
+    pub depth: usize,
+    pub code: String,
+    pub name: String,
+    pub total: f64,
+    pub total_display: String,
+    pub line_items: Vec<LineItemView>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LineItemView {
+    pub description: String,
+    pub depth: usize,
+    pub quantity: f64,
+    pub unit: String,
+    pub unit_cost: f64,
+    pub unit_cost_display: String,
+    pub total: f64,
+    pub total_display: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MarkupView {
+    pub name: String,
+    pub rate: f64,
+    pub amount: f64,
+    pub amount_display: String,
+}
+
+impl EstimateViewModel {
+    pub fn new(report: &EstimateReportDTO, date: NaiveDate, currency: Currency) -> Self {
+        let mut sections: Vec<SectionView> = vec![];
+        for row in &report.rows {
+            match row.kind {
+                ReportRowKind::Section => sections.push(SectionView {
+                    wbs: row.wbs.clone(),
+                    depth: row.depth,
+                    code: row.code.clone(),
+                    name: row.description.clone(),
+                    total: row.total,
+                    total_display: currency.format(row.total),
+                    line_items: vec![],
+                }),
+                ReportRowKind::LineItem => {
+                    if let Some(section) = sections.last_mut() {
+                        section.line_items.push(Self::line_item(row, currency));
+                    }
+                }
+            }
+        }
+
+        EstimateViewModel {
+            estimate: EstimateView {
+                id: report.estimate.id.to_string(),
+                name: report.estimate.name.clone(),
+                description: report.estimate.description.clone(),
+                location: report.estimate.location.clone(),
+                status: report.estimate.status.to_string(),
+            },
+            date: date.format("%B %-d, %Y").to_string(),
+            sections,
+            subtotal: report.subtotal,
+            subtotal_display: currency.format(report.subtotal),
+            markups: report
+                .markups
+                .iter()
+                .map(|markup| MarkupView {
+                    name: markup.name.clone(),
+                    rate: markup.rate,
+                    amount: markup.amount,
+                    amount_display: currency.format(markup.amount),
+                })
+                .collect(),
+            total: report.total,
+            total_display: currency.format(report.total),
+            exclusions: report.estimate.exclusions.clone(),
+        }
+    }
+
+    fn line_item(row: &ReportRowDTO, currency: Currency) -> LineItemView {
+        let unit_cost = row.unit_cost.unwrap_or_default();
+        LineItemView {
+            description: row.description.clone(),
+            depth: row.depth,
+            quantity: row.quantity.unwrap_or_default(),
+            unit: row.unit.map(|unit| unit.to_string()).unwrap_or_default(),
+            unit_cost,
+            unit_cost_display: currency.format(unit_cost),
+            total: row.total,
+            total_display: currency.format(row.total),
+        }
+    }
+
+    // A view model with one of everything, used to check which fields a template may use.
+    fn example() -> Self {
+        let mut estimate = EstimateDTO::new();
+        estimate.markups = vec![Markup::new("Overhead".to_string(), 10.0)];
+        estimate.exclusions = vec!["Permits".to_string()];
+        let mut section = Section::from(SectionDTO::new("Section".to_string(), "01".to_string()));
+        section.line_items = vec![LineItem::new("Item".to_string(), 1.0, Unit::Each, 1.0)];
+        let report = EstimateReportDTO::new(Estimate::from(estimate), vec![section]);
+        EstimateViewModel::new(&report, NaiveDate::default(), Currency::default())
+    }
+}
+
+pub struct TemplatePresenter {
+    template: Template,
+    currency: Currency,
+}
+
+impl TemplatePresenter {
+    // Parses and validates the template up front, so a template that refers to fields the view
+    // model does not have is rejected before anything is rendered.
+    pub fn new(source: &str, format: TemplateFormat) -> Result<Self> {
+        let template = Template::parse(source, format).map_err(|message| {
+            Box::new(PresenterError::InvalidTemplate {
+                issues: vec![message],
+            })
+        })?;
+
+        let example = serde_json::to_value(EstimateViewModel::example())?;
+        let issues = template.validate(&example);
+        if !issues.is_empty() {
+            return Err(Box::new(PresenterError::InvalidTemplate { issues }));
+        }
+
+        Ok(TemplatePresenter {
+            template,
+            currency: Currency::default(),
+        })
+    }
+
+    pub fn built_in(template: BuiltInTemplate) -> Result<Self> {
+        TemplatePresenter::new(template.source(), template.format())
+    }
+
+    // The format follows the extension: `.html`/`.htm` escape for HTML, anything else is
+    // treated as Markdown.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path)?;
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("html") | Some("htm") => TemplateFormat::Html,
+            _ => TemplateFormat::Markdown,
+        };
+        TemplatePresenter::new(&source, format)
+    }
+
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
+    pub fn format(&self) -> TemplateFormat {
+        self.template.format()
+    }
+
+    pub fn present(&self, report: &EstimateReportDTO, date: NaiveDate) -> Result<String> {
+        let model = serde_json::to_value(EstimateViewModel::new(report, date, self.currency))?;
+        Ok(self.template.render(&model))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> EstimateReportDTO {
+        let mut estimate = EstimateDTO::new();
+        estimate.name = "Clinic <Fit-out>".to_string();
+        let mut section = Section::from(SectionDTO::new("Finishes".to_string(), "09".to_string()));
+        section.line_items = vec![LineItem::new(
+            "Paint | two coats".to_string(),
+            100.0,
+            Unit::SquareFoot,
+            2.5,
+        )];
+        EstimateReportDTO::new(Estimate::from(estimate), vec![section])
+    }
+
+    #[test]
+    fn test_built_in_templates_are_valid_and_render() {
+        let date = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+
+        let html = TemplatePresenter::built_in(BuiltInTemplate::HtmlProposal)
+            .unwrap()
+            .present(&report(), date)
+            .unwrap();
+        assert!(html.contains("<h1>Clinic &lt;Fit-out&gt;</h1>"));
+        assert!(html.contains("$250.00"));
+
+        let markdown = TemplatePresenter::built_in(BuiltInTemplate::MarkdownReport)
+            .unwrap()
+            .present(&report(), date)
+            .unwrap();
+        assert!(markdown.contains("| | | Paint \\| two coats | 100.0 | SF | $2.50 | $250.00 |"));
+        assert!(markdown.contains("None."));
+    }
+
+    #[test]
+    fn test_template_with_missing_fields_is_rejected() {
+        let result = TemplatePresenter::new(
+            "{{ estimate.name }} {{ estimate.client }}\n{{#each sections}}{{ cost }}{{/each}}",
+            TemplateFormat::Markdown,
+        );
+
+        let error = result.err().unwrap().to_string();
+        assert_eq!(
+            error,
+            "Invalid template: line 1: unknown field `estimate.client`; line 2: unknown field `cost`"
+        );
+    }
+}
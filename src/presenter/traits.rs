@@ -0,0 +1,28 @@
+//presenter/traits.rs
+use crate::result::*;
+
+use serde_json::Value;
+
+// A named field and its value; the order is the display order.
+pub type Record = Vec<(&'static str, Value)>;
+
+pub trait Presentable {
+    fn to_record(&self) -> Record;
+}
+
+// A record with the records nested under it, such as a section and its subsections.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TreeNode {
+    pub record: Record,
+    pub children: Vec<TreeNode>,
+}
+
+// One output format. Presenters only see records, so any `Presentable` can be rendered in any
+// format, and the format can be chosen at runtime as a `Box<dyn Presenter>`.
+pub trait Presenter: Send + Sync {
+    fn present_record(&self, record: &Record) -> Result<String>;
+
+    fn present_records(&self, records: &[Record]) -> Result<String>;
+
+    fn present_tree(&self, nodes: &[TreeNode]) -> Result<String>;
+}
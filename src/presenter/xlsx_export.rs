@@ -0,0 +1,217 @@
+//presenter/xlsx_export.rs
+use super::error::Error as PresenterError;
+use crate::result::*;
+
+use std::collections::HashSet;
+
+use rust_xlsxwriter::{Format, Workbook, Worksheet, XlsxError};
+
+use super::csv_export::CSV_EXPORT_HEADERS;
+use crate::dto::estimate_report_dto::{EstimateReportDTO, ReportRowDTO, ReportRowKind};
+
+// Excel rejects sheet names longer than 31 characters or containing any of these.
+const MAX_SHEET_NAME_LEN: usize = 31;
+const INVALID_SHEET_NAME_CHARS: [char; 7] = ['[', ']', ':', '*', '?', '/', '\\'];
+
+pub struct XlsxEstimateExporter;
+
+impl XlsxEstimateExporter {
+    // A "Summary" sheet with every top-level section total and the markups, followed by one
+    // sheet per top-level section holding its full subtree.
+    pub fn present(report: &EstimateReportDTO) -> Result<Vec<u8>> {
+        Self::build(report).map_err(|e| {
+            Box::new(PresenterError::ExportError {
+                format: "XLSX",
+                message: e.to_string(),
+            }) as Box<dyn std::error::Error + Send + Sync>
+        })
+    }
+
+    fn build(report: &EstimateReportDTO) -> std::result::Result<Vec<u8>, XlsxError> {
+        let bold = Format::new().set_bold();
+        let money = Format::new().set_num_format("#,##0.00");
+        let bold_money = Format::new().set_bold().set_num_format("#,##0.00");
+
+        let mut workbook = Workbook::new();
+        let groups = report.top_level_groups();
+        let mut used_names = HashSet::new();
+        used_names.insert("summary".to_string());
+
+        let summary = workbook.add_worksheet();
+        summary.set_name("Summary")?;
+        summary.write_string_with_format(0, 0, &report.estimate.name, &bold)?;
+        summary.write_string(1, 0, &report.estimate.location)?;
+        summary.write_string_with_format(3, 0, "WBS", &bold)?;
+        summary.write_string_with_format(3, 1, "Section", &bold)?;
+        summary.write_string_with_format(3, 2, "Total", &bold)?;
+        let mut row = 4;
+        for group in &groups {
+            let section = &group[0];
+            summary.write_string(row, 0, &section.wbs)?;
+            summary.write_string(row, 1, &section.description)?;
+            summary.write_number_with_format(row, 2, section.total, &money)?;
+            row += 1;
+        }
+        row += 1;
+        summary.write_string_with_format(row, 1, "Subtotal", &bold)?;
+        summary.write_number_with_format(row, 2, report.subtotal, &bold_money)?;
+        for markup in &report.markups {
+            row += 1;
+            summary.write_string(row, 1, format!("{} ({}%)", markup.name, markup.rate))?;
+            summary.write_number_with_format(row, 2, markup.amount, &money)?;
+        }
+        row += 1;
+        summary.write_string_with_format(row, 1, "Total", &bold)?;
+        summary.write_number_with_format(row, 2, report.total, &bold_money)?;
+        summary.set_column_width(1, 40)?;
+        summary.set_column_width(2, 16)?;
+
+        for group in &groups {
+            let name = Self::sheet_name(&group[0], &mut used_names);
+            let sheet = workbook.add_worksheet();
+            sheet.set_name(name)?;
+            Self::write_section_sheet(sheet, group, &bold, &money)?;
+        }
+
+        workbook.save_to_buffer()
+    }
+
+    fn write_section_sheet(
+        sheet: &mut Worksheet,
+        rows: &[ReportRowDTO],
+        bold: &Format,
+        money: &Format,
+    ) -> std::result::Result<(), XlsxError> {
+        for (col, header) in CSV_EXPORT_HEADERS.iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, *header, bold)?;
+        }
+        sheet.set_freeze_panes(1, 0)?;
+
+        for (index, row) in rows.iter().enumerate() {
+            let r = index as u32 + 1;
+            let indent = Format::new().set_indent(row.depth.min(15) as u8);
+            let (text, amount) = match row.kind {
+                ReportRowKind::Section => (
+                    indent.clone().set_bold(),
+                    bold.clone().set_num_format("#,##0.00"),
+                ),
+                ReportRowKind::LineItem => (indent, money.clone()),
+            };
+            sheet.write_string(r, 0, &row.wbs)?;
+            sheet.write_string(r, 1, &row.code)?;
+            sheet.write_string_with_format(r, 2, &row.description, &text)?;
+            if let Some(quantity) = row.quantity {
+                sheet.write_number(r, 3, quantity)?;
+            }
+            if let Some(unit) = row.unit {
+                sheet.write_string(r, 4, unit.code())?;
+            }
+            if let Some(unit_cost) = row.unit_cost {
+                sheet.write_number_with_format(r, 5, unit_cost, money)?;
+            }
+            sheet.write_number_with_format(r, 6, row.total, &amount)?;
+        }
+
+        sheet.set_column_width(2, 40)?;
+        sheet.set_column_width(5, 14)?;
+        sheet.set_column_width(6, 16)?;
+        Ok(())
+    }
+
+    // Sheet names are case-insensitively unique in a workbook, so clashes get a numeric suffix.
+    fn sheet_name(section: &ReportRowDTO, used: &mut HashSet<String>) -> String {
+        let label = if section.code.is_empty() {
+            format!("{} {}", section.wbs, section.description)
+        } else {
+            format!("{} {}", section.code, section.description)
+        };
+        let cleaned: String = label
+            .chars()
+            .map(|c| {
+                if INVALID_SHEET_NAME_CHARS.contains(&c) {
+                    '-'
+                } else {
+                    c
+                }
+            })
+            .collect();
+        let cleaned = cleaned.trim().trim_matches('\'').to_string();
+        let base: String = cleaned.chars().take(MAX_SHEET_NAME_LEN).collect();
+        let base = if base.is_empty() {
+            section.wbs.clone()
+        } else {
+            base
+        };
+
+        let mut candidate = base.clone();
+        let mut counter = 2;
+        while !used.insert(candidate.to_lowercase()) {
+            let suffix = format!(" ({})", counter);
+            let keep = MAX_SHEET_NAME_LEN - suffix.chars().count();
+            candidate = format!("{}{}", base.chars().take(keep).collect::<String>(), suffix);
+            counter += 1;
+        }
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::estimate_dto::EstimateDTO;
+    use crate::dto::section_dto::SectionDTO;
+    use crate::entity::estimate::Estimate;
+    use crate::entity::line_item::LineItem;
+    use crate::entity::section::Section;
+    use crate::entity::unit::Unit;
+    use crate::presenter::csv_export::CsvEstimateExporter;
+
+    fn report() -> EstimateReportDTO {
+        let mut estimate = EstimateDTO::new();
+        estimate.name = "Warehouse".to_string();
+        let mut sections = vec![];
+        for name in [
+            "Concrete: Foundations and Slabs on Grade",
+            "Concrete: Foundations and Slabs on Grade",
+        ] {
+            let mut section = Section::from(SectionDTO::new(name.to_string(), "03".to_string()));
+            section.line_items = vec![LineItem::new(
+                "Footing".to_string(),
+                12.5,
+                Unit::CubicYard,
+                180.0,
+            )];
+            sections.push(section);
+        }
+        EstimateReportDTO::new(Estimate::from(estimate), sections)
+    }
+
+    #[test]
+    fn test_csv_export_includes_rows_and_summary() {
+        let csv = CsvEstimateExporter::present(&report()).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "WBS,Code,Description,Quantity,Unit,Unit Cost,Total"
+        );
+        assert_eq!(lines[2], ",,  Footing,12.5,CY,180.00,2250.00");
+        assert_eq!(lines[lines.len() - 1], ",,Total,,,,4500.00");
+    }
+
+    #[test]
+    fn test_xlsx_export_writes_a_workbook_with_unique_sheet_names() {
+        let bytes = XlsxEstimateExporter::present(&report()).unwrap();
+        assert!(bytes.starts_with(b"PK"));
+
+        let mut used = HashSet::new();
+        let groups = report();
+        let names: Vec<String> = groups
+            .top_level_groups()
+            .iter()
+            .map(|group| XlsxEstimateExporter::sheet_name(&group[0], &mut used))
+            .collect();
+        assert_eq!(names[0], "03 Concrete- Foundations and Sl");
+        assert_eq!(names[1], "03 Concrete- Foundations an (2)");
+    }
+}
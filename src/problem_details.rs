@@ -0,0 +1,481 @@
+// problem_details.rs
+
+// RFC 7807 problem details for every error the crate produces. The HTTP layer, the CLI's JSON
+// output and the logs all render errors through `ProblemDetails`, so the shape clients see does
+// not depend on how the error enums happen to be laid out.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+use crate::error::Error as MainError;
+use crate::{bus, config, controller, entity, presenter, repository, service, use_case};
+
+// Problem types are relative URIs under this path; resolve them against the API's base URL.
+pub const PROBLEM_TYPE_BASE: &str = "/problems/";
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    // Members beyond the standard ones, e.g. `violations` for validation failures.
+    #[serde(flatten)]
+    pub extensions: Map<String, Value>,
+}
+
+impl ProblemDetails {
+    pub fn new(slug: &str, title: &str, status: u16, detail: impl Into<String>) -> Self {
+        ProblemDetails {
+            problem_type: format!("{}{}", PROBLEM_TYPE_BASE, slug),
+            title: title.to_string(),
+            status,
+            detail: detail.into(),
+            instance: None,
+            extensions: Map::new(),
+        }
+    }
+
+    // Identifies this occurrence, e.g. the request path or request id.
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    pub fn with_extension(mut self, key: &str, value: Value) -> Self {
+        self.extensions.insert(key.to_string(), value);
+        self
+    }
+
+    // Works on the boxed errors use cases and controllers return: every layer's error type is
+    // recognised, anything else is reported as an internal error.
+    pub fn from_error(error: &(dyn std::error::Error + 'static)) -> Self {
+        #[cfg(feature = "file-backend")]
+        if let Some(error) = error.downcast_ref::<repository::file_repo::error::Error>() {
+            return ProblemDetails::from(error);
+        }
+        #[cfg(feature = "backup")]
+        if let Some(error) = error.downcast_ref::<crate::backup::error::Error>() {
+            return ProblemDetails::from(error);
+        }
+
+        if let Some(error) = error.downcast_ref::<MainError>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<use_case::error::Error>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<entity::error::Error>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<service::error::Error>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<repository::error::Error>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<repository::in_memory_repo::error::Error>()
+        {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<controller::error::Error>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<presenter::error::Error>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<bus::error::Error>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<config::error::Error>() {
+            ProblemDetails::from(error)
+        } else if let Some(error) = error.downcast_ref::<crate::migration::error::Error>() {
+            ProblemDetails::from(error)
+        } else {
+            internal(error.to_string())
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_else(|_| self.detail.clone())
+    }
+
+    // Single-line form for logs.
+    pub fn to_log_line(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| self.detail.clone())
+    }
+}
+
+fn internal(detail: String) -> ProblemDetails {
+    ProblemDetails::new("internal-error", "Internal error", 500, detail)
+}
+
+fn validation(detail: String, violations: Vec<Value>) -> ProblemDetails {
+    ProblemDetails::new("validation-error", "Validation failed", 422, detail)
+        .with_extension("violations", Value::Array(violations))
+}
+
+impl From<&MainError> for ProblemDetails {
+    fn from(error: &MainError) -> Self {
+        match error {
+            MainError::MainError { message } => internal(message.clone()),
+            MainError::RepositoryError(error) => error.into(),
+            MainError::InMemoryRepositoryError(error) => error.into(),
+            #[cfg(feature = "file-backend")]
+            MainError::FileRepositoryError(error) => error.into(),
+            MainError::UseCaseError(error) => error.into(),
+            MainError::EntityError(error) => error.into(),
+            MainError::ServiceError(error) => error.into(),
+            MainError::ControllerError(error) => error.into(),
+            MainError::PresenterError(error) => error.into(),
+            MainError::BusError(error) => error.into(),
+            MainError::ConfigError(error) => error.into(),
+            MainError::MigrationError(error) => error.into(),
+            #[cfg(feature = "backup")]
+            MainError::BackupError(error) => error.into(),
+        }
+    }
+}
+
+impl From<&use_case::error::Error> for ProblemDetails {
+    fn from(error: &use_case::error::Error) -> Self {
+        use use_case::error::Error;
+
+        let detail = error.to_string();
+        match error {
+            Error::BasicCaseError { .. } => internal(detail),
+            Error::ValidationError { use_case, message } => validation(
+                detail,
+                vec![json!({ "use_case": use_case, "message": message })],
+            ),
+            Error::NotFoundError { entity, id } => {
+                ProblemDetails::new("not-found", "Resource not found", 404, detail)
+                    .with_extension("entity", json!(entity))
+                    .with_extension("id", json!(id))
+            }
+            Error::PriceNotFound { code, date } => {
+                ProblemDetails::new("not-found", "Resource not found", 404, detail)
+                    .with_extension("entity", json!("PriceBookItem"))
+                    .with_extension("code", json!(code))
+                    .with_extension("date", json!(date))
+            }
+            Error::ImportError(report) => validation(
+                detail,
+                report
+                    .errors
+                    .iter()
+                    .map(|error| json!({ "row": error.row, "message": error.message }))
+                    .collect(),
+            ),
+            Error::AuthorizationError { use_case } => {
+                ProblemDetails::new("forbidden", "Not authorized", 403, detail)
+                    .with_extension("use_case", json!(use_case))
+            }
+        }
+    }
+}
+
+impl From<&entity::error::Error> for ProblemDetails {
+    fn from(error: &entity::error::Error) -> Self {
+        match error {
+            entity::error::Error::ValidationError { entity, message } => validation(
+                error.to_string(),
+                vec![json!({ "entity": entity, "message": message })],
+            ),
+            entity::error::Error::IncompatibleUnits { from, to } => validation(
+                error.to_string(),
+                vec![json!({ "from": from, "to": to, "message": error.to_string() })],
+            ),
+            entity::error::Error::UnknownUnit { unit } => validation(
+                error.to_string(),
+                vec![json!({ "unit": unit, "message": error.to_string() })],
+            ),
+        }
+    }
+}
+
+impl From<&service::error::Error> for ProblemDetails {
+    fn from(error: &service::error::Error) -> Self {
+        match error {
+            service::error::Error::ValidationError { message } => {
+                validation(error.to_string(), vec![json!({ "message": message })])
+            }
+            service::error::Error::Aborted { entity, .. } => {
+                internal(error.to_string()).with_extension("entity", json!(entity))
+            }
+            service::error::Error::Timeout {
+                operation,
+                after_ms,
+            } => ProblemDetails::new("timeout", "Operation timed out", 504, error.to_string())
+                .with_extension("operation", json!(operation))
+                .with_extension("after_ms", json!(after_ms)),
+            service::error::Error::Stopped { entity } => {
+                ProblemDetails::new("unavailable", "Service unavailable", 503, error.to_string())
+                    .with_extension("entity", json!(entity))
+            }
+            service::error::Error::CircuitOpen {
+                operation,
+                retry_at,
+            } => ProblemDetails::new("unavailable", "Service unavailable", 503, error.to_string())
+                .with_extension("operation", json!(operation))
+                .with_extension("retry_at", json!(retry_at)),
+        }
+    }
+}
+
+impl From<&repository::error::Error> for ProblemDetails {
+    fn from(error: &repository::error::Error) -> Self {
+        use repository::error::Error;
+
+        let detail = error.to_string();
+        match error {
+            Error::BasicError { .. } => {
+                ProblemDetails::new("repository-error", "Repository error", 500, detail)
+            }
+            Error::AlreadyExists { entity, id } => {
+                ProblemDetails::new("conflict", "Resource already exists", 409, detail)
+                    .with_extension("entity", json!(entity))
+                    .with_extension("id", json!(id))
+            }
+            Error::NotFound { entity, id } => {
+                ProblemDetails::new("not-found", "Resource not found", 404, detail)
+                    .with_extension("entity", json!(entity))
+                    .with_extension("id", json!(id))
+            }
+            Error::Unavailable { .. } => {
+                ProblemDetails::new("unavailable", "Service unavailable", 503, detail)
+            }
+        }
+    }
+}
+
+impl From<&repository::in_memory_repo::error::Error> for ProblemDetails {
+    fn from(error: &repository::in_memory_repo::error::Error) -> Self {
+        ProblemDetails::new(
+            "repository-error",
+            "Repository error",
+            500,
+            error.to_string(),
+        )
+    }
+}
+
+#[cfg(feature = "file-backend")]
+impl From<&repository::file_repo::error::Error> for ProblemDetails {
+    fn from(error: &repository::file_repo::error::Error) -> Self {
+        ProblemDetails::new(
+            "repository-error",
+            "Repository error",
+            500,
+            error.to_string(),
+        )
+    }
+}
+
+impl From<&controller::error::Error> for ProblemDetails {
+    fn from(error: &controller::error::Error) -> Self {
+        internal(error.to_string())
+    }
+}
+
+impl From<&presenter::error::Error> for ProblemDetails {
+    fn from(error: &presenter::error::Error) -> Self {
+        use presenter::error::Error;
+
+        let detail = error.to_string();
+        match error {
+            Error::BasicError { status_code, .. } => ProblemDetails::new(
+                "presentation-error",
+                "Presentation failed",
+                *status_code,
+                detail,
+            ),
+            Error::ExportError { format, .. } => {
+                ProblemDetails::new("export-error", "Export failed", 500, detail)
+                    .with_extension("format", json!(format))
+            }
+            Error::InvalidTemplate { issues } => validation(
+                detail,
+                issues
+                    .iter()
+                    .map(|issue| json!({ "message": issue }))
+                    .collect(),
+            ),
+            Error::UnknownFormat { format } => {
+                ProblemDetails::new("unknown-format", "Unknown output format", 400, detail)
+                    .with_extension("format", json!(format))
+            }
+        }
+    }
+}
+
+impl From<&bus::error::Error> for ProblemDetails {
+    fn from(error: &bus::error::Error) -> Self {
+        match error {
+            bus::error::Error::ShuttingDown => {
+                ProblemDetails::new("unavailable", "Service unavailable", 503, error.to_string())
+            }
+            _ => ProblemDetails::new("handler-error", "No usable handler", 500, error.to_string()),
+        }
+    }
+}
+
+impl From<&crate::migration::error::Error> for ProblemDetails {
+    fn from(error: &crate::migration::error::Error) -> Self {
+        ProblemDetails::new(
+            "migration-error",
+            "Migration failed",
+            500,
+            error.to_string(),
+        )
+    }
+}
+
+#[cfg(feature = "backup")]
+impl From<&crate::backup::error::Error> for ProblemDetails {
+    fn from(error: &crate::backup::error::Error) -> Self {
+        use crate::backup::error::Error;
+
+        let detail = error.to_string();
+        match error {
+            Error::IoError { .. } => internal(detail),
+            Error::RestoreFailed { collection, .. } | Error::RollbackFailed { collection, .. } => {
+                internal(detail).with_extension("collection", json!(collection))
+            }
+            Error::NotEmpty { collection, count } => {
+                ProblemDetails::new("conflict", "Target is not empty", 409, detail)
+                    .with_extension("collection", json!(collection))
+                    .with_extension("count", json!(count))
+            }
+            _ => ProblemDetails::new("invalid-backup", "Invalid backup", 422, detail),
+        }
+    }
+}
+
+impl From<&config::error::Error> for ProblemDetails {
+    fn from(error: &config::error::Error) -> Self {
+        let problem = ProblemDetails::new(
+            "invalid-configuration",
+            "Invalid configuration",
+            500,
+            error.to_string(),
+        );
+        match error {
+            config::error::Error::Invalid { problems } => {
+                problem.with_extension("problems", json!(problems))
+            }
+            config::error::Error::Syntax { line, .. } => {
+                problem.with_extension("line", json!(line))
+            }
+            config::error::Error::ReadError { path, .. } => {
+                problem.with_extension("path", json!(path))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::estimate::Estimate;
+    use crate::repository::conformance::Sample;
+    use crate::repository::in_memory_repo::InMemoryRepository;
+    use crate::service::generic_service::GenericService;
+    use crate::use_case::import_estimate_from_csv::{ImportErrorReport, RowError};
+    use crate::use_case::traits::UseCase;
+    use crate::use_case::update_estimate::{UpdateEstimate, UpdateEstimateCommand};
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+    use uuid::Uuid;
+
+    #[test]
+    fn test_validation_errors_list_violations() {
+        let error: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(use_case::error::Error::ImportError(ImportErrorReport {
+                errors: vec![RowError {
+                    row: 3,
+                    message: "Quantity must be a number".to_string(),
+                }],
+            }));
+
+        let problem = ProblemDetails::from_error(error.as_ref()).with_instance("/estimates/import");
+        let value: Value = serde_json::from_str(&problem.to_json()).unwrap();
+
+        assert_eq!(
+            value,
+            json!({
+                "type": "/problems/validation-error",
+                "title": "Validation failed",
+                "status": 422,
+                "detail": "CSV import failed: 1 row error(s); row 3: Quantity must be a number",
+                "instance": "/estimates/import",
+                "violations": [{ "row": 3, "message": "Quantity must be a number" }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_wrapped_and_unknown_errors() {
+        let id = Uuid::new_v4();
+        let wrapped = MainError::UseCaseError(use_case::error::Error::NotFoundError {
+            entity: "Estimate",
+            id,
+        });
+        let problem = ProblemDetails::from_error(&wrapped);
+        assert_eq!(problem.status, 404);
+        assert_eq!(problem.extensions["id"], json!(id));
+        assert_eq!(problem.instance, None);
+
+        let io = std::io::Error::other("disk full");
+        let problem = ProblemDetails::from_error(&io);
+        assert_eq!(problem.problem_type, "/problems/internal-error");
+        assert_eq!(problem.detail, "disk full");
+    }
+
+    #[test]
+    fn test_boxed_errors_keep_their_status_through_main_error() {
+        let timeout: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(service::error::Error::Timeout {
+                operation: "Estimate.get_estimate".to_string(),
+                after_ms: 50,
+            });
+        let error = Box::<MainError>::from(timeout);
+        assert!(matches!(*error, MainError::ServiceError(_)));
+        assert_eq!(ProblemDetails::from(error.as_ref()).status, 504);
+
+        let io: Box<dyn std::error::Error + Send + Sync> =
+            Box::new(std::io::Error::other("disk full"));
+        let error = Box::<MainError>::from(io);
+        assert!(matches!(*error, MainError::MainError { .. }));
+        assert_eq!(ProblemDetails::from(error.as_ref()).status, 500);
+    }
+
+    #[tokio::test]
+    async fn test_repository_conflicts_and_missing_items_keep_their_status() {
+        let repository = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let service = GenericService::<Estimate>::new(repository).spawn().handle();
+        let estimate = service.add_estimate(Estimate::sample(1)).await.unwrap();
+
+        let error = service.add_estimate(estimate).await.unwrap_err();
+        let problem = ProblemDetails::from_error(error.as_ref());
+        assert_eq!(problem.status, 409, "{}", problem.to_json());
+
+        let error = service.delete_estimate(Uuid::new_v4()).await.unwrap_err();
+        let problem = ProblemDetails::from_error(error.as_ref());
+        assert_eq!(problem.status, 404, "{}", problem.to_json());
+    }
+
+    #[tokio::test]
+    async fn test_use_case_keeps_entity_validation_status() {
+        let repository = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let service = GenericService::<Estimate>::new(repository).spawn().handle();
+        let estimate = service.add_estimate(Estimate::sample(1)).await.unwrap();
+
+        let error = UpdateEstimate::new(service)
+            .execute(UpdateEstimateCommand {
+                estimate_id: estimate.id,
+                name: Some("Go".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap_err();
+
+        let problem = ProblemDetails::from_error(error.as_ref());
+        assert_eq!(problem.status, 422, "{}", problem.to_json());
+        assert_eq!(problem.problem_type, "/problems/validation-error");
+    }
+}
@@ -0,0 +1,419 @@
+// repository/conformance.rs
+
+// The behaviour every `Repository<T>` backend must share, written as a test harness a backend's
+// own tests can run:
+//
+//     ConformanceSuite::new(InMemoryRepository::<Estimate>::new).run::<Estimate>().await;
+//
+// Each check gets a fresh repository from the factory and panics with the rule it broke. Missing
+// and duplicate ids must be reported as `repository::error::Error::NotFound` and `AlreadyExists`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use super::error::Error as RepositoryError;
+use super::repository::Repository;
+use crate::dto::estimate_dto::EstimateDTO;
+use crate::dto::section_dto::SectionDTO;
+use crate::entity::estimate::Estimate;
+use crate::entity::section::Section;
+use crate::entity::traits::Identifiable;
+
+// What the suite needs to know about the items it stores.
+pub trait Sample: Identifiable + Clone + Send + Sync + 'static {
+    // A different item, with a different id, for every `n`.
+    fn sample(n: usize) -> Self;
+    // The same item, same id, with different content.
+    fn changed(&self) -> Self;
+    // Whether two items carry the same content.
+    fn same_as(&self, other: &Self) -> bool;
+}
+
+impl Sample for Estimate {
+    fn sample(n: usize) -> Self {
+        let mut estimate = EstimateDTO::new();
+        estimate.name = format!("Estimate {}", n);
+        estimate.description = format!("Conformance sample {}", n);
+        estimate.location = "Test".to_string();
+        Estimate::from(estimate)
+    }
+
+    fn changed(&self) -> Self {
+        Estimate {
+            name: format!("{} (changed)", self.name),
+            ..self.clone()
+        }
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.description == other.description
+            && self.location == other.location
+    }
+}
+
+impl Sample for Section {
+    fn sample(n: usize) -> Self {
+        let mut section = SectionDTO::new(format!("Section {}", n), format!("{:02}", n));
+        section.position = n as u32;
+        Section::from(section)
+    }
+
+    fn changed(&self) -> Self {
+        Section {
+            name: format!("{} (changed)", self.name),
+            ..self.clone()
+        }
+    }
+
+    fn same_as(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.name == other.name
+            && self.code == other.code
+            && self.position == other.position
+    }
+}
+
+// How many tasks the concurrency checks run at once.
+const CONCURRENCY: usize = 16;
+
+pub struct ConformanceSuite<F> {
+    factory: F,
+}
+
+impl<F, R> ConformanceSuite<F>
+where
+    F: Fn() -> R,
+{
+    pub fn new(factory: F) -> Self {
+        ConformanceSuite { factory }
+    }
+
+    // Runs every check against fresh repositories of items `T`.
+    pub async fn run<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T> + Sync + 'static,
+    {
+        self.add_then_get::<T>().await;
+        self.get_missing_is_none::<T>().await;
+        self.duplicate_add_fails::<T>().await;
+        self.update_replaces::<T>().await;
+        self.update_missing_fails::<T>().await;
+        self.delete_removes::<T>().await;
+        self.delete_missing_fails::<T>().await;
+        self.list_returns_everything::<T>().await;
+        self.concurrent_adds_are_all_kept::<T>().await;
+        self.concurrent_duplicate_adds_admit_one::<T>().await;
+        self.concurrent_updates_keep_one_version::<T>().await;
+    }
+
+    async fn add_then_get<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T>,
+    {
+        let repository = (self.factory)();
+        let item = T::sample(1);
+        let id = repository
+            .add(item.clone())
+            .await
+            .expect("add must succeed");
+        assert_eq!(id, item.id(), "add must return the item's id");
+        let stored = repository.get(id).await.expect("get must succeed");
+        assert!(
+            stored.is_some_and(|stored| stored.same_as(&item)),
+            "get must return what was added"
+        );
+    }
+
+    async fn get_missing_is_none<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T>,
+    {
+        let repository = (self.factory)();
+        repository
+            .add(T::sample(1))
+            .await
+            .expect("add must succeed");
+        let missing = repository
+            .get(Uuid::new_v4())
+            .await
+            .expect("get of a missing id must not fail");
+        assert!(missing.is_none(), "get of a missing id must return None");
+    }
+
+    async fn duplicate_add_fails<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T>,
+    {
+        let repository = (self.factory)();
+        let item = T::sample(1);
+        repository
+            .add(item.clone())
+            .await
+            .expect("add must succeed");
+        let error = repository
+            .add(item.changed())
+            .await
+            .expect_err("adding an existing id must fail");
+        expect_already_exists(error.as_ref(), item.id());
+        let stored = repository.get(item.id()).await.expect("get must succeed");
+        assert!(
+            stored.is_some_and(|stored| stored.same_as(&item)),
+            "a rejected add must leave the stored item unchanged"
+        );
+    }
+
+    async fn update_replaces<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T>,
+    {
+        let repository = (self.factory)();
+        let item = T::sample(1);
+        repository
+            .add(item.clone())
+            .await
+            .expect("add must succeed");
+        let changed = item.changed();
+        repository
+            .update(changed.clone())
+            .await
+            .expect("update of an existing item must succeed");
+        let stored = repository.get(item.id()).await.expect("get must succeed");
+        assert!(
+            stored.is_some_and(|stored| stored.same_as(&changed)),
+            "get must return the updated item"
+        );
+    }
+
+    async fn update_missing_fails<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T>,
+    {
+        let repository = (self.factory)();
+        let item = T::sample(1);
+        let error = repository
+            .update(item.clone())
+            .await
+            .expect_err("update of a missing item must fail");
+        expect_not_found(error.as_ref(), item.id());
+        let stored = repository.get(item.id()).await.expect("get must succeed");
+        assert!(stored.is_none(), "a failed update must not insert the item");
+    }
+
+    async fn delete_removes<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T>,
+    {
+        let repository = (self.factory)();
+        let item = T::sample(1);
+        let other = T::sample(2);
+        repository
+            .add(item.clone())
+            .await
+            .expect("add must succeed");
+        repository
+            .add(other.clone())
+            .await
+            .expect("add must succeed");
+        repository
+            .delete(item.id())
+            .await
+            .expect("delete of an existing item must succeed");
+        let stored = repository.get(item.id()).await.expect("get must succeed");
+        assert!(stored.is_none(), "a deleted item must be gone");
+        let stored = repository.get(other.id()).await.expect("get must succeed");
+        assert!(stored.is_some(), "delete must leave other items alone");
+    }
+
+    async fn delete_missing_fails<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T>,
+    {
+        let repository = (self.factory)();
+        let item = T::sample(1);
+        repository
+            .add(item.clone())
+            .await
+            .expect("add must succeed");
+        repository
+            .delete(item.id())
+            .await
+            .expect("delete must succeed");
+        let error = repository
+            .delete(item.id())
+            .await
+            .expect_err("delete of a missing item must fail");
+        expect_not_found(error.as_ref(), item.id());
+    }
+
+    async fn list_returns_everything<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T>,
+    {
+        let repository = (self.factory)();
+        let listed = repository.list().await.expect("list must succeed");
+        assert!(listed.is_empty(), "a new repository must be empty");
+
+        let items: Vec<T> = (0..5).map(T::sample).collect();
+        for item in &items {
+            repository
+                .add(item.clone())
+                .await
+                .expect("add must succeed");
+        }
+        repository
+            .delete(items[0].id())
+            .await
+            .expect("delete must succeed");
+        let listed = repository.list().await.expect("list must succeed");
+        assert_eq!(listed.len(), 4, "list must return every stored item");
+        for item in &items[1..] {
+            assert!(
+                listed.iter().any(|listed| listed.same_as(item)),
+                "list must contain every stored item"
+            );
+        }
+    }
+
+    async fn concurrent_adds_are_all_kept<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T> + Sync + 'static,
+    {
+        let repository = Arc::new((self.factory)());
+        let items: Vec<T> = (0..CONCURRENCY).map(T::sample).collect();
+        let tasks: Vec<_> = items
+            .iter()
+            .cloned()
+            .map(|item| {
+                let repository = Arc::clone(&repository);
+                tokio::spawn(async move { repository.add(item).await.map_err(|e| e.to_string()) })
+            })
+            .collect();
+        for task in tasks {
+            task.await
+                .expect("task must not panic")
+                .expect("concurrent adds of distinct items must all succeed");
+        }
+
+        let listed = repository.list().await.expect("list must succeed");
+        let ids: HashSet<Uuid> = listed.iter().map(|item| item.id()).collect();
+        assert_eq!(
+            ids,
+            items.iter().map(|item| item.id()).collect(),
+            "every concurrently added item must be stored"
+        );
+    }
+
+    async fn concurrent_duplicate_adds_admit_one<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T> + Sync + 'static,
+    {
+        let repository = Arc::new((self.factory)());
+        let item = T::sample(1);
+        let tasks: Vec<_> = (0..CONCURRENCY)
+            .map(|_| {
+                let repository = Arc::clone(&repository);
+                let item = item.clone();
+                tokio::spawn(async move {
+                    repository
+                        .add(item)
+                        .await
+                        .map_err(|e| already_exists(e.as_ref()))
+                })
+            })
+            .collect();
+        let mut added = 0;
+        for task in tasks {
+            match task.await.expect("task must not panic") {
+                Ok(_) => added += 1,
+                Err(kind) => assert!(kind, "a losing duplicate add must fail with AlreadyExists"),
+            }
+        }
+        assert_eq!(added, 1, "exactly one of the racing adds must succeed");
+    }
+
+    async fn concurrent_updates_keep_one_version<T>(&self)
+    where
+        T: Sample,
+        R: Repository<T> + Sync + 'static,
+    {
+        let repository = Arc::new((self.factory)());
+        let item = T::sample(1);
+        repository
+            .add(item.clone())
+            .await
+            .expect("add must succeed");
+
+        let mut versions = vec![item.clone()];
+        for _ in 0..CONCURRENCY {
+            let next = versions.last().expect("starts with one").changed();
+            versions.push(next);
+        }
+        let tasks: Vec<_> = versions[1..]
+            .iter()
+            .cloned()
+            .map(|version| {
+                let repository = Arc::clone(&repository);
+                tokio::spawn(
+                    async move { repository.update(version).await.map_err(|e| e.to_string()) },
+                )
+            })
+            .collect();
+        for task in tasks {
+            task.await
+                .expect("task must not panic")
+                .expect("concurrent updates of an existing item must all succeed");
+        }
+
+        let stored = repository
+            .get(item.id())
+            .await
+            .expect("get must succeed")
+            .expect("the item must still exist");
+        assert!(
+            versions[1..].iter().any(|version| stored.same_as(version)),
+            "after racing updates the item must equal one of the written versions"
+        );
+        let listed = repository.list().await.expect("list must succeed");
+        assert_eq!(listed.len(), 1, "updates must not duplicate the item");
+    }
+}
+
+fn already_exists(error: &(dyn std::error::Error + Send + Sync + 'static)) -> bool {
+    matches!(
+        error.downcast_ref::<RepositoryError>(),
+        Some(RepositoryError::AlreadyExists { .. })
+    )
+}
+
+fn expect_already_exists(error: &(dyn std::error::Error + Send + Sync + 'static), id: Uuid) {
+    match error.downcast_ref::<RepositoryError>() {
+        Some(RepositoryError::AlreadyExists { id: reported, .. }) => {
+            assert_eq!(*reported, id, "AlreadyExists must name the duplicate id")
+        }
+        _ => panic!("expected RepositoryError::AlreadyExists, got: {}", error),
+    }
+}
+
+fn expect_not_found(error: &(dyn std::error::Error + Send + Sync + 'static), id: Uuid) {
+    match error.downcast_ref::<RepositoryError>() {
+        Some(RepositoryError::NotFound { id: reported, .. }) => {
+            assert_eq!(*reported, id, "NotFound must name the missing id")
+        }
+        _ => panic!("expected RepositoryError::NotFound, got: {}", error),
+    }
+}
@@ -7,22 +7,29 @@ use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 
 use std::borrow::Cow;
+use uuid::Uuid;
 
 #[serde_as]
 #[derive(Debug, Serialize, From, Display)]
 pub enum Error {
     #[display("Placeholder error: {}", message)]
     BasicError { message: String },
+    // Every backend reports these two the same way; see `repository::conformance`.
+    #[display("{} {} already exists", entity, id)]
+    AlreadyExists { entity: &'static str, id: Uuid },
+    #[from(skip)]
+    #[display("{} {} not found", entity, id)]
+    NotFound { entity: &'static str, id: Uuid },
+    // The backend could not be reached or answer right now; services may retry.
+    #[from(skip)]
+    #[display("Repository unavailable: {}", message)]
+    Unavailable { message: String },
 }
 
 impl std::error::Error for Error {}
 
 impl From<Error> for MainError {
     fn from(error: Error) -> Self {
-        match error {
-            Error::BasicError { message } => {
-                MainError::RepositoryError(Error::BasicError { message }.into())
-            }
-        }
+        MainError::RepositoryError(error)
     }
 }
@@ -0,0 +1,29 @@
+//fault_injecting_repo/error.rs
+use crate::repository::error::Error as RepositoryError;
+
+use derive_more::{Display, From};
+
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+#[serde_as]
+#[derive(Debug, Serialize, From, Display)]
+pub enum Error {
+    #[display("Injected failure in {}", operation)]
+    InjectedFailure { operation: &'static str },
+    #[display("{} timed out after {} ms", operation, after_ms)]
+    Timeout {
+        operation: &'static str,
+        after_ms: u64,
+    },
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for RepositoryError {
+    fn from(error: Error) -> Self {
+        RepositoryError::Unavailable {
+            message: error.to_string(),
+        }
+    }
+}
@@ -0,0 +1,436 @@
+// repository/fault_injecting_repo/mod.rs
+
+pub mod error;
+
+use self::error::Error as FaultError;
+use crate::result::*;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
+
+use derive_more::Display;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use uuid::Uuid;
+
+use crate::entity::traits::Identifiable;
+
+use super::repository::Repository;
+
+// Wraps another repository and misbehaves on request, so tests can see how services and use
+// cases cope with storage that fails, stalls or times out. Faults are armed through a
+// `FaultHandle`, which stays usable after the repository has been handed to a service, and
+// every call is recorded for assertions.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Display)]
+pub enum Operation {
+    #[display("add")]
+    Add,
+    #[display("get")]
+    Get,
+    #[display("list")]
+    List,
+    #[display("update")]
+    Update,
+    #[display("delete")]
+    Delete,
+    #[display("flush")]
+    Flush,
+}
+
+impl Operation {
+    fn name(&self) -> &'static str {
+        match self {
+            Operation::Add => "add",
+            Operation::Get => "get",
+            Operation::List => "list",
+            Operation::Update => "update",
+            Operation::Delete => "delete",
+            Operation::Flush => "flush",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Trigger {
+    Always,
+    // Only the n-th call of the operation, counting from 1.
+    Nth(usize),
+    // The first n calls of the operation.
+    FirstN(usize),
+    // Each call with this probability, drawn from the repository's seeded generator.
+    Probability(f64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fault {
+    // Fails without reaching the wrapped repository.
+    Fail,
+    // The wrapped repository applies the call but the caller still sees a failure, as when an
+    // acknowledgement is lost.
+    FailAfterApply,
+    // Waits, then goes through. Delays from several rules add up.
+    Latency(Duration),
+    // Waits, then fails with a timeout without reaching the wrapped repository.
+    Timeout(Duration),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaultRule {
+    // `None` applies to every operation.
+    pub operation: Option<Operation>,
+    pub trigger: Trigger,
+    pub fault: Fault,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    // Still running, or abandoned by the caller (e.g. cancelled by its own timeout).
+    Pending,
+    Succeeded,
+    Failed(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Call {
+    pub operation: Operation,
+    // The id the call was about; `None` for `list`.
+    pub id: Option<Uuid>,
+    // Every fault that fired on this call.
+    pub faults: Vec<Fault>,
+    pub outcome: Outcome,
+}
+
+struct State {
+    rules: Vec<FaultRule>,
+    counts: HashMap<Operation, usize>,
+    rng: StdRng,
+    calls: Vec<Call>,
+}
+
+// Arms faults and reads the call log of one `FaultInjectingRepository`.
+#[derive(Clone)]
+pub struct FaultHandle {
+    state: Arc<StdMutex<State>>,
+}
+
+impl FaultHandle {
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    pub fn inject(&self, rule: FaultRule) -> &Self {
+        self.state().rules.push(rule);
+        self
+    }
+
+    pub fn fail(&self, operation: Operation, trigger: Trigger) -> &Self {
+        self.inject(FaultRule {
+            operation: Some(operation),
+            trigger,
+            fault: Fault::Fail,
+        })
+    }
+
+    pub fn fail_after_apply(&self, operation: Operation, trigger: Trigger) -> &Self {
+        self.inject(FaultRule {
+            operation: Some(operation),
+            trigger,
+            fault: Fault::FailAfterApply,
+        })
+    }
+
+    pub fn delay(&self, operation: Operation, by: Duration, trigger: Trigger) -> &Self {
+        self.inject(FaultRule {
+            operation: Some(operation),
+            trigger,
+            fault: Fault::Latency(by),
+        })
+    }
+
+    pub fn time_out(&self, operation: Operation, after: Duration, trigger: Trigger) -> &Self {
+        self.inject(FaultRule {
+            operation: Some(operation),
+            trigger,
+            fault: Fault::Timeout(after),
+        })
+    }
+
+    // Disarms every rule; call counts keep running.
+    pub fn clear(&self) {
+        self.state().rules.clear();
+    }
+
+    pub fn calls(&self) -> Vec<Call> {
+        self.state().calls.clone()
+    }
+
+    pub fn calls_to(&self, operation: Operation) -> Vec<Call> {
+        self.calls()
+            .into_iter()
+            .filter(|call| call.operation == operation)
+            .collect()
+    }
+
+    // Starts a call: counts it, decides which faults fire and logs it as pending.
+    fn begin(&self, operation: Operation, id: Option<Uuid>) -> (usize, Vec<Fault>) {
+        let mut state = self.state();
+        let count = {
+            let count = state.counts.entry(operation).or_insert(0);
+            *count += 1;
+            *count
+        };
+        let rules: Vec<FaultRule> = state
+            .rules
+            .iter()
+            .filter(|rule| rule.operation.is_none_or(|op| op == operation))
+            .copied()
+            .collect();
+        let faults: Vec<Fault> = rules
+            .into_iter()
+            .filter(|rule| match rule.trigger {
+                Trigger::Always => true,
+                Trigger::Nth(n) => count == n,
+                Trigger::FirstN(n) => count <= n,
+                Trigger::Probability(p) => state.rng.gen_bool(p.clamp(0.0, 1.0)),
+            })
+            .map(|rule| rule.fault)
+            .collect();
+        state.calls.push(Call {
+            operation,
+            id,
+            faults: faults.clone(),
+            outcome: Outcome::Pending,
+        });
+        (state.calls.len() - 1, faults)
+    }
+
+    fn finish(&self, index: usize, outcome: Outcome) {
+        if let Some(call) = self.state().calls.get_mut(index) {
+            call.outcome = outcome;
+        }
+    }
+}
+
+pub struct FaultInjectingRepository<R> {
+    inner: R,
+    handle: FaultHandle,
+}
+
+impl<R> FaultInjectingRepository<R> {
+    // Probabilistic triggers draw from a generator seeded with 0; see `seeded`.
+    pub fn new(inner: R) -> Self {
+        FaultInjectingRepository {
+            inner,
+            handle: FaultHandle {
+                state: Arc::new(StdMutex::new(State {
+                    rules: Vec::new(),
+                    counts: HashMap::new(),
+                    rng: StdRng::seed_from_u64(0),
+                    calls: Vec::new(),
+                })),
+            },
+        }
+    }
+
+    // The same seed and the same sequence of calls give the same faults.
+    pub fn seeded(self, seed: u64) -> Self {
+        self.handle.state().rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    pub fn handle(&self) -> FaultHandle {
+        self.handle.clone()
+    }
+
+    async fn run<V, Fut>(&self, operation: Operation, id: Option<Uuid>, call: Fut) -> Result<V>
+    where
+        Fut: Future<Output = Result<V>>,
+    {
+        let (index, faults) = self.handle.begin(operation, id);
+
+        let latency: Duration = faults
+            .iter()
+            .filter_map(|fault| match fault {
+                Fault::Latency(by) => Some(*by),
+                _ => None,
+            })
+            .sum();
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+
+        let failure = FaultError::InjectedFailure {
+            operation: operation.name(),
+        };
+        let result = match faults
+            .iter()
+            .find(|fault| !matches!(fault, Fault::Latency(_)))
+        {
+            Some(Fault::Fail) => Err(failure.into()),
+            Some(Fault::FailAfterApply) => {
+                let _ = call.await;
+                Err(failure.into())
+            }
+            Some(Fault::Timeout(after)) => {
+                tokio::time::sleep(*after).await;
+                Err(FaultError::Timeout {
+                    operation: operation.name(),
+                    after_ms: after.as_millis() as u64,
+                }
+                .into())
+            }
+            _ => call.await,
+        };
+
+        self.handle.finish(
+            index,
+            match &result {
+                Ok(_) => Outcome::Succeeded,
+                Err(error) => Outcome::Failed(error.to_string()),
+            },
+        );
+        result
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, R> Repository<T> for FaultInjectingRepository<R>
+where
+    T: Identifiable + Send + Sync + 'static,
+    R: Repository<T> + Sync,
+{
+    async fn add(&self, item: T) -> Result<Uuid> {
+        let id = item.id();
+        self.run(Operation::Add, Some(id), self.inner.add(item))
+            .await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<T>> {
+        self.run(Operation::Get, Some(id), self.inner.get(id)).await
+    }
+
+    async fn list(&self) -> Result<Vec<T>> {
+        self.run(Operation::List, None, self.inner.list()).await
+    }
+
+    async fn update(&self, item: T) -> Result<()> {
+        let id = item.id();
+        self.run(Operation::Update, Some(id), self.inner.update(item))
+            .await
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<()> {
+        self.run(Operation::Delete, Some(id), self.inner.delete(id))
+            .await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.run(Operation::Flush, None, self.inner.flush()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::estimate::Estimate;
+    use crate::repository::conformance::{ConformanceSuite, Sample};
+    use crate::repository::in_memory_repo::InMemoryRepository;
+
+    #[tokio::test]
+    async fn test_conformance_without_faults() {
+        ConformanceSuite::new(|| FaultInjectingRepository::new(InMemoryRepository::new()))
+            .run::<Estimate>()
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_faults_by_count() {
+        let repository = FaultInjectingRepository::new(InMemoryRepository::<Estimate>::new());
+        let faults = repository.handle();
+        faults
+            .fail(Operation::Add, Trigger::Nth(2))
+            .fail_after_apply(Operation::Delete, Trigger::Always);
+
+        let first = Estimate::sample(1);
+        let second = Estimate::sample(2);
+        repository.add(first.clone()).await.unwrap();
+        let error = repository.add(second.clone()).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<FaultError>(),
+            Some(FaultError::InjectedFailure { operation: "add" })
+        ));
+        assert!(repository.get(second.id).await.unwrap().is_none());
+
+        // The delete goes through even though the caller is told it failed.
+        assert!(repository.delete(first.id).await.is_err());
+        assert!(repository.list().await.unwrap().is_empty());
+
+        let adds = faults.calls_to(Operation::Add);
+        assert_eq!(adds.len(), 2);
+        assert_eq!(adds[0].outcome, Outcome::Succeeded);
+        assert_eq!(adds[1].id, Some(second.id));
+        assert_eq!(adds[1].faults, vec![Fault::Fail]);
+        assert_eq!(
+            adds[1].outcome,
+            Outcome::Failed("Injected failure in add".to_string())
+        );
+        assert_eq!(faults.calls().len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_seeded_probability_is_reproducible() {
+        async fn pattern(seed: u64) -> Vec<bool> {
+            let repository =
+                FaultInjectingRepository::new(InMemoryRepository::<Estimate>::new()).seeded(seed);
+            repository
+                .handle()
+                .fail(Operation::Get, Trigger::Probability(0.5));
+            let mut failed = Vec::new();
+            for _ in 0..32 {
+                failed.push(repository.get(Uuid::nil()).await.is_err());
+            }
+            failed
+        }
+
+        let first = pattern(7).await;
+        assert_eq!(first, pattern(7).await);
+        assert!(first.contains(&true) && first.contains(&false));
+    }
+
+    #[tokio::test]
+    async fn test_latency_and_timeout() {
+        let repository = FaultInjectingRepository::new(InMemoryRepository::<Estimate>::new());
+        let faults = repository.handle();
+        faults
+            .delay(Operation::List, Duration::from_millis(20), Trigger::Always)
+            .time_out(
+                Operation::Get,
+                Duration::from_millis(10),
+                Trigger::FirstN(1),
+            );
+
+        let started = std::time::Instant::now();
+        repository.list().await.unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        let error = repository.get(Uuid::nil()).await.unwrap_err();
+        assert_eq!(error.to_string(), "get timed out after 10 ms");
+        assert!(repository.get(Uuid::nil()).await.is_ok());
+
+        // A caller that gives up first leaves the call pending in the log.
+        faults.time_out(Operation::Get, Duration::from_secs(60), Trigger::Always);
+        let abandoned =
+            tokio::time::timeout(Duration::from_millis(10), repository.get(Uuid::nil())).await;
+        assert!(abandoned.is_err());
+        assert_eq!(
+            faults.calls_to(Operation::Get).last().unwrap().outcome,
+            Outcome::Pending
+        );
+    }
+}
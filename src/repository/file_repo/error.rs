@@ -0,0 +1,30 @@
+//file_repo/error.rs
+use crate::repository::error::Error as RepositoryError;
+
+use derive_more::{Display, From};
+
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+#[serde_as]
+#[derive(Debug, Serialize, From, Display)]
+pub enum Error {
+    #[display("Cannot access {}: {}", path, message)]
+    IoError { path: String, message: String },
+    #[display("Cannot decode {}: {}", path, message)]
+    DecodeError {
+        path: String,
+        line: usize,
+        message: String,
+    },
+}
+
+impl std::error::Error for Error {}
+
+impl From<Error> for RepositoryError {
+    fn from(error: Error) -> Self {
+        RepositoryError::BasicError {
+            message: error.to_string(),
+        }
+    }
+}
@@ -0,0 +1,260 @@
+pub mod error;
+
+use self::error::Error as FileRepositoryError;
+use super::error::Error as RepositoryError;
+use crate::result::*;
+
+use crate::metrics;
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::entity::traits::Identifiable;
+use crate::telemetry::span::type_name;
+use crate::telemetry::{Level, Span};
+
+use super::repository::Repository;
+
+// Keeps every item in memory and rewrites one JSON file after each change, so the data outlives
+// the process. The file is replaced atomically: written next to the target, then renamed.
+pub struct FileRepository<T: Identifiable> {
+    path: PathBuf,
+    data: Arc<Mutex<HashMap<Uuid, T>>>,
+}
+
+impl<T> FileRepository<T>
+where
+    T: Identifiable + Clone + Send + Sync + Serialize + DeserializeOwned,
+{
+    // Loads the file if it exists; the parent directory is created if missing.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let io_error = |error: std::io::Error| FileRepositoryError::IoError {
+            path: path.display().to_string(),
+            message: error.to_string(),
+        };
+        if let Some(parent) = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            std::fs::create_dir_all(parent).map_err(io_error)?;
+        }
+        let data = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice::<Vec<T>>(&bytes)
+                .map_err(|error| FileRepositoryError::DecodeError {
+                    path: path.display().to_string(),
+                    line: error.line(),
+                    message: error.to_string(),
+                })?
+                .into_iter()
+                .map(|item| (item.id(), item))
+                .collect(),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(io_error(error).into()),
+        };
+        Ok(FileRepository {
+            path,
+            data: Arc::new(Mutex::new(data)),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    // Items are written in id order so the file only changes where the data does.
+    async fn persist(&self, data: &HashMap<Uuid, T>) -> Result<()> {
+        let mut items: Vec<&T> = data.values().collect();
+        items.sort_by_key(|item| item.id());
+        let bytes = serde_json::to_vec_pretty(&items)?;
+
+        let mut temporary = self.path.clone().into_os_string();
+        temporary.push(".tmp");
+        let io_error = |error: std::io::Error| FileRepositoryError::IoError {
+            path: self.path.display().to_string(),
+            message: error.to_string(),
+        };
+        let mut file = tokio::fs::File::create(&temporary)
+            .await
+            .map_err(io_error)?;
+        file.write_all(&bytes).await.map_err(io_error)?;
+        // Reach the disk before the rename so a crash never leaves a truncated file in place.
+        file.sync_all().await.map_err(io_error)?;
+        tokio::fs::rename(&temporary, &self.path)
+            .await
+            .map_err(io_error)?;
+        Ok(())
+    }
+
+    // Applies `change` to a copy of the data and keeps it only once it has been written.
+    async fn commit<F>(&self, change: F) -> Result<()>
+    where
+        F: FnOnce(&mut HashMap<Uuid, T>) -> Result<()>,
+    {
+        let mut data = metrics::lock(&self.data).await;
+        let mut updated = data.clone();
+        change(&mut updated)?;
+        self.persist(&updated).await?;
+        *data = updated;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<T> Repository<T> for FileRepository<T>
+where
+    T: Identifiable + Clone + Send + Sync + Serialize + DeserializeOwned + 'static,
+{
+    async fn add(&self, item: T) -> Result<Uuid> {
+        Span::new("repository", format!("{}.add", type_name::<T>()))
+            .with_entity(Some(item.id()))
+            .at(Level::Debug)
+            .instrument(async {
+                let id = item.id();
+                self.commit(|data| {
+                    if data.contains_key(&id) {
+                        return Err(RepositoryError::AlreadyExists {
+                            entity: type_name::<T>(),
+                            id,
+                        }
+                        .into());
+                    }
+                    data.insert(id, item);
+                    Ok(())
+                })
+                .await?;
+                Ok(id)
+            })
+            .await
+    }
+
+    async fn get(&self, id: Uuid) -> Result<Option<T>> {
+        Span::new("repository", format!("{}.get", type_name::<T>()))
+            .with_entity(Some(id))
+            .at(Level::Debug)
+            .instrument(async {
+                let data = metrics::lock(&self.data).await;
+                Ok(data.get(&id).cloned())
+            })
+            .await
+    }
+
+    async fn list(&self) -> Result<Vec<T>> {
+        Span::new("repository", format!("{}.list", type_name::<T>()))
+            .with_entity(None)
+            .at(Level::Debug)
+            .instrument(async {
+                let data = metrics::lock(&self.data).await;
+                Ok(data.values().cloned().collect())
+            })
+            .await
+    }
+
+    async fn update(&self, item: T) -> Result<()> {
+        Span::new("repository", format!("{}.update", type_name::<T>()))
+            .with_entity(Some(item.id()))
+            .at(Level::Debug)
+            .instrument(async {
+                let id = item.id();
+                self.commit(|data| match data.get_mut(&id) {
+                    Some(existing) => {
+                        *existing = item;
+                        Ok(())
+                    }
+                    None => Err(RepositoryError::NotFound {
+                        entity: type_name::<T>(),
+                        id,
+                    }
+                    .into()),
+                })
+                .await
+            })
+            .await
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<()> {
+        Span::new("repository", format!("{}.delete", type_name::<T>()))
+            .with_entity(Some(id))
+            .at(Level::Debug)
+            .instrument(async {
+                self.commit(|data| match data.remove(&id) {
+                    Some(_) => Ok(()),
+                    None => Err(RepositoryError::NotFound {
+                        entity: type_name::<T>(),
+                        id,
+                    }
+                    .into()),
+                })
+                .await
+            })
+            .await
+    }
+
+    // Every change is already on disk; this rewrites the file once more as the final snapshot.
+    async fn flush(&self) -> Result<()> {
+        Span::new("repository", format!("{}.flush", type_name::<T>()))
+            .with_entity(None)
+            .at(Level::Debug)
+            .instrument(async {
+                let data = metrics::lock(&self.data).await;
+                self.persist(&data).await
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::estimate_dto::EstimateDTO;
+    use crate::entity::estimate::Estimate;
+    use crate::repository::conformance::ConformanceSuite;
+
+    fn named(name: &str) -> Estimate {
+        let mut dto = EstimateDTO::new();
+        dto.name = name.to_string();
+        Estimate::from(dto)
+    }
+
+    #[tokio::test]
+    async fn test_items_survive_reopening() {
+        let path = std::env::temp_dir()
+            .join(format!("file-repo-{}", Uuid::new_v4()))
+            .join("estimates.json");
+
+        let repository = FileRepository::<Estimate>::open(&path).unwrap();
+        let estimate = named("Warehouse");
+        let id = repository.add(estimate.clone()).await.unwrap();
+        let second = named("Clinic");
+        repository.add(second.clone()).await.unwrap();
+        repository.delete(second.id).await.unwrap();
+        assert!(repository.add(estimate).await.is_err());
+
+        let reopened = FileRepository::<Estimate>::open(&path).unwrap();
+        let items = reopened.list().await.unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(reopened.get(id).await.unwrap().unwrap().name, "Warehouse");
+
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let directory = std::env::temp_dir().join(format!("file-repo-{}", Uuid::new_v4()));
+        ConformanceSuite::new(|| {
+            FileRepository::<Estimate>::open(directory.join(format!("{}.json", Uuid::new_v4())))
+                .unwrap()
+        })
+        .run::<Estimate>()
+        .await;
+
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -3,6 +3,9 @@ pub mod error;
 use super::error::Error as InMemoryRepositoryError;
 use crate::result::*;
 
+use crate::metrics;
+
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
@@ -11,6 +14,8 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::entity::traits::Identifiable;
+use crate::telemetry::span::type_name;
+use crate::telemetry::{Level, Span};
 
 use super::repository::Repository;
 
@@ -28,47 +33,113 @@ impl<T: Identifiable + Clone + Send + Sync> InMemoryRepository<T> {
     }
 }
 
+impl<T: Identifiable + Clone + Send + Sync> Default for InMemoryRepository<T> {
+    fn default() -> Self {
+        InMemoryRepository::new()
+    }
+}
+
 #[async_trait::async_trait]
 impl<T: Identifiable + Clone + Send + Sync + 'static> Repository<T> for InMemoryRepository<T> {
     async fn add(&self, item: T) -> Result<Uuid> {
-        let mut data = self.data.lock().await;
-        let id = item.id();
-        if data.contains_key(&id) {
-            Err(Box::new(InMemoryRepositoryError::BasicError {
-                message: "Item already exists".into(),
-            }))
-        } else {
-            data.insert(id, item);
-            Ok(id)
-        }
+        Span::new("repository", format!("{}.add", type_name::<T>()))
+            .with_entity(Some(item.id()))
+            .at(Level::Debug)
+            .instrument(async {
+                let mut data = metrics::lock(&self.data).await;
+                let id = item.id();
+                match data.entry(id) {
+                    Entry::Occupied(_) => Err(InMemoryRepositoryError::AlreadyExists {
+                        entity: type_name::<T>(),
+                        id,
+                    }
+                    .into()),
+                    Entry::Vacant(entry) => {
+                        entry.insert(item);
+                        Ok(id)
+                    }
+                }
+            })
+            .await
     }
 
     async fn get(&self, id: Uuid) -> Result<Option<T>> {
-        let data = self.data.lock().await;
-        Ok(data.get(&id).cloned())
+        Span::new("repository", format!("{}.get", type_name::<T>()))
+            .with_entity(Some(id))
+            .at(Level::Debug)
+            .instrument(async {
+                let data = metrics::lock(&self.data).await;
+                Ok(data.get(&id).cloned())
+            })
+            .await
+    }
+
+    async fn list(&self) -> Result<Vec<T>> {
+        Span::new("repository", format!("{}.list", type_name::<T>()))
+            .with_entity(None)
+            .at(Level::Debug)
+            .instrument(async {
+                let data = metrics::lock(&self.data).await;
+                Ok(data.values().cloned().collect())
+            })
+            .await
     }
 
     async fn update(&self, item: T) -> Result<()> {
-        let mut data = self.data.lock().await;
-        let id = item.id();
-        if data.contains_key(&id) {
-            data.insert(id, item);
-            Ok(())
-        } else {
-            Err(Box::new(InMemoryRepositoryError::BasicError {
-                message: "Item not found".into(),
-            }))
-        }
+        Span::new("repository", format!("{}.update", type_name::<T>()))
+            .with_entity(Some(item.id()))
+            .at(Level::Debug)
+            .instrument(async {
+                let mut data = metrics::lock(&self.data).await;
+                match data.get_mut(&item.id()) {
+                    Some(existing) => {
+                        *existing = item;
+                        Ok(())
+                    }
+                    None => Err(InMemoryRepositoryError::NotFound {
+                        entity: type_name::<T>(),
+                        id: item.id(),
+                    }
+                    .into()),
+                }
+            })
+            .await
     }
 
     async fn delete(&self, id: Uuid) -> Result<()> {
-        let mut data = self.data.lock().await;
-        if data.remove(&id).is_some() {
-            Ok(())
-        } else {
-            Err(Box::new(InMemoryRepositoryError::BasicError {
-                message: "Item already exists".into(),
-            }))
-        }
+        Span::new("repository", format!("{}.delete", type_name::<T>()))
+            .with_entity(Some(id))
+            .at(Level::Debug)
+            .instrument(async {
+                let mut data = metrics::lock(&self.data).await;
+                if data.remove(&id).is_some() {
+                    Ok(())
+                } else {
+                    Err(InMemoryRepositoryError::NotFound {
+                        entity: type_name::<T>(),
+                        id,
+                    }
+                    .into())
+                }
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::estimate::Estimate;
+    use crate::entity::section::Section;
+    use crate::repository::conformance::ConformanceSuite;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        ConformanceSuite::new(InMemoryRepository::<Estimate>::new)
+            .run::<Estimate>()
+            .await;
+        ConformanceSuite::new(InMemoryRepository::<Section>::new)
+            .run::<Section>()
+            .await;
     }
 }
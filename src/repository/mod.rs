@@ -1,5 +1,12 @@
 // repository/mod.rs
 
-pub(crate) mod error;
-pub(crate) mod in_memory_repo;
-pub(crate) mod repository;
+#[cfg(any(test, feature = "test-support"))]
+pub mod conformance;
+pub mod error;
+#[cfg(any(test, feature = "test-support"))]
+pub mod fault_injecting_repo;
+#[cfg(feature = "file-backend")]
+pub mod file_repo;
+pub mod in_memory_repo;
+#[allow(clippy::module_inception)]
+pub mod repository;
@@ -13,6 +13,13 @@ where
 {
     async fn add(&self, item: T) -> Result<Uuid>;
     async fn get(&self, id: Uuid) -> Result<Option<T>>;
+    async fn list(&self) -> Result<Vec<T>>;
     async fn update(&self, item: T) -> Result<()>;
     async fn delete(&self, id: Uuid) -> Result<()>;
+
+    // Makes every change so far durable. Called at shutdown; backends that write through on
+    // each change have nothing left to do.
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
 }
@@ -0,0 +1,333 @@
+// service/actor.rs
+
+// Runs a `GenericService<T>` as a task that owns it and works through a bounded mailbox one
+// command at a time, so use cases share a cloneable `ServiceHandle<T>` instead of a mutex. A full
+// mailbox makes callers wait, which is the backpressure. `ServiceActor::shutdown` refuses new
+// commands, finishes the ones already queued and hands the service back.
+//
+// A command that needs several service calls to happen without anything in between, e.g. delete
+// and renumber, sends them together with `ServiceHandle::run`.
+
+use crate::result::*;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::clock::Clock;
+use crate::entity::limits::ValidationLimits;
+use crate::entity::traits::Identifiable;
+use crate::id_generator::IdGenerator;
+use crate::service::error::Error as ServiceError;
+use crate::service::generic_service::{GenericService, Service};
+use crate::telemetry::span::{type_name, RequestContext};
+
+// How many commands may wait in a mailbox before senders are held back.
+pub const DEFAULT_MAILBOX_CAPACITY: usize = 64;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+type Job<T> = Box<dyn for<'a> FnOnce(&'a GenericService<T>) -> BoxFuture<'a, ()> + Send>;
+
+enum Message<T> {
+    Run(Job<T>),
+    Shutdown,
+}
+
+pub struct ServiceActor<T> {
+    handle: ServiceHandle<T>,
+    task: JoinHandle<GenericService<T>>,
+}
+
+impl<T: Send + Sync + 'static> ServiceActor<T> {
+    // Must be called from within a Tokio runtime.
+    pub fn spawn(service: GenericService<T>, capacity: usize) -> Self {
+        let (sender, mailbox) = mpsc::channel(capacity.max(1));
+        let handle = ServiceHandle {
+            sender,
+            clock: Arc::clone(&service.clock),
+            ids: Arc::clone(&service.ids),
+            limits: Arc::clone(&service.limits),
+        };
+        ServiceActor {
+            handle,
+            task: tokio::spawn(run_actor(service, mailbox)),
+        }
+    }
+
+    pub fn handle(&self) -> ServiceHandle<T> {
+        self.handle.clone()
+    }
+
+    // Commands queued before this call still run; later ones fail with `ServiceError::Stopped`.
+    pub async fn shutdown(self) -> Result<GenericService<T>> {
+        // If the send fails the actor has already stopped and the join below says why.
+        let _ = self.handle.sender.send(Message::Shutdown).await;
+        self.task.await.map_err(|e| {
+            Box::new(ServiceError::Aborted {
+                entity: type_name::<T>(),
+                message: e.to_string(),
+            }) as Box<dyn std::error::Error + Send + Sync>
+        })
+    }
+}
+
+impl<T: Send + Sync + 'static> GenericService<T> {
+    // Moves the service into an actor with the default mailbox.
+    pub fn spawn(self) -> ServiceActor<T> {
+        ServiceActor::spawn(self, DEFAULT_MAILBOX_CAPACITY)
+    }
+}
+
+async fn run_actor<T>(
+    service: GenericService<T>,
+    mut mailbox: mpsc::Receiver<Message<T>>,
+) -> GenericService<T> {
+    while let Some(message) = mailbox.recv().await {
+        match message {
+            Message::Run(job) => job(&service).await,
+            // Stops the senders; whatever is already queued is still received.
+            Message::Shutdown => mailbox.close(),
+        }
+    }
+    service
+}
+
+pub struct ServiceHandle<T> {
+    sender: mpsc::Sender<Message<T>>,
+    // The service's own clock, id generator and validation limits, for use cases that build
+    // or change entities.
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGenerator>,
+    pub limits: Arc<ValidationLimits>,
+}
+
+impl<T> Clone for ServiceHandle<T> {
+    fn clone(&self) -> Self {
+        ServiceHandle {
+            sender: self.sender.clone(),
+            clock: Arc::clone(&self.clock),
+            ids: Arc::clone(&self.ids),
+            limits: Arc::clone(&self.limits),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> ServiceHandle<T> {
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    pub fn next_id(&self) -> Uuid {
+        self.ids.next_id()
+    }
+
+    // Runs `command` on the actor and waits for its result. Nothing else touches the service
+    // until the command's future completes.
+    pub async fn run<R, F>(&self, command: F) -> Result<R>
+    where
+        F: for<'a> FnOnce(&'a GenericService<T>) -> BoxFuture<'a, Result<R>> + Send + 'static,
+        R: Send + 'static,
+    {
+        let stopped = || {
+            Box::new(ServiceError::Stopped {
+                entity: type_name::<T>(),
+            }) as Box<dyn std::error::Error + Send + Sync>
+        };
+        let (reply, answer) = oneshot::channel();
+        // The actor is a task of its own, so the caller's request is carried over by hand.
+        let request = RequestContext::current();
+        let job = job::<T, _>(move |service| {
+            Box::pin(async move {
+                let result = request.run(command(service)).await;
+                let _ = reply.send(result);
+            })
+        });
+        self.sender
+            .send(Message::Run(job))
+            .await
+            .map_err(|_| stopped())?;
+        answer.await.map_err(|_| stopped())?
+    }
+}
+
+// Pins down the closure's higher-ranked signature.
+fn job<T, F>(job: F) -> Job<T>
+where
+    F: for<'a> FnOnce(&'a GenericService<T>) -> BoxFuture<'a, ()> + Send + 'static,
+{
+    Box::new(job)
+}
+
+// The generic repository operations, as `Service<T>` offers them.
+impl<T> ServiceHandle<T>
+where
+    T: Identifiable + Clone + Send + Sync + 'static,
+{
+    pub async fn add(&self, item: T) -> Result<Uuid> {
+        self.run(move |service| Box::pin(service.add(item))).await
+    }
+
+    pub async fn get(&self, id: Uuid) -> Result<Option<T>> {
+        self.run(move |service| Box::pin(service.get(id))).await
+    }
+
+    pub async fn list(&self) -> Result<Vec<T>> {
+        self.run(|service| Box::pin(service.list())).await
+    }
+
+    pub async fn update(&self, item: T) -> Result<()> {
+        self.run(move |service| Box::pin(service.update(item)))
+            .await
+    }
+
+    pub async fn delete(&self, id: Uuid) -> Result<()> {
+        self.run(move |service| Box::pin(service.delete(id))).await
+    }
+
+    pub async fn flush(&self) -> Result<()> {
+        self.run(|service| Box::pin(service.flush())).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::estimate::Estimate;
+    use crate::repository::conformance::Sample;
+    use crate::repository::in_memory_repo::InMemoryRepository;
+    use crate::telemetry::with_request_id;
+    use serde_json::Value;
+    use std::io::Write;
+    use std::time::Duration;
+    use tokio::sync::Mutex;
+
+    fn actor(capacity: usize) -> ServiceActor<Estimate> {
+        let repository = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        ServiceActor::spawn(GenericService::<Estimate>::new(repository), capacity)
+    }
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    // The subscriber is the default for this test's thread only, and the single-threaded test
+    // runtime runs the actor on that thread too.
+    #[tokio::test]
+    async fn test_service_spans_carry_the_callers_request_id() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_span_list(true)
+            .with_max_level(tracing::Level::DEBUG)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _default = tracing::subscriber::set_default(subscriber);
+        let handle = actor(8).handle();
+
+        let request_id = Uuid::new_v4();
+        with_request_id(request_id, handle.add(Estimate::sample(1)))
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let records: Vec<Value> = output
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+            .collect();
+        assert!(
+            records.iter().any(|record| {
+                let spans = record["spans"].as_array().cloned().unwrap_or_default();
+                spans
+                    .iter()
+                    .any(|span| span["request_id"] == request_id.to_string())
+                    && spans.iter().any(|span| span["layer"] == "service")
+            }),
+            "{}",
+            output
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_queued_commands() {
+        let actor = actor(8);
+        let handle = actor.handle();
+
+        // Hold the actor busy so the adds below queue up behind it.
+        let busy = handle.clone();
+        let slow = tokio::spawn(async move {
+            busy.run(|_| {
+                Box::pin(async {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    Ok(())
+                })
+            })
+            .await
+        });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let adds: Vec<_> = (0..5)
+            .map(|n| {
+                let handle = handle.clone();
+                tokio::spawn(async move { handle.add(Estimate::sample(n)).await })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        let service = actor.shutdown().await.unwrap();
+        slow.await.unwrap().unwrap();
+        for add in adds {
+            add.await.unwrap().unwrap();
+        }
+        assert_eq!(service.list().await.unwrap().len(), 5);
+
+        let error = handle.list().await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ServiceError>(),
+            Some(ServiceError::Stopped { entity: "Estimate" })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_full_mailbox_holds_senders_back() {
+        let actor = actor(1);
+        let handle = actor.handle();
+        let (release, released) = oneshot::channel::<()>();
+        let blocker = handle.clone();
+        tokio::spawn(async move {
+            blocker
+                .run(move |_| Box::pin(async move { released.await.map_err(|e| e.into()) }))
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+
+        // One command fits in the mailbox; the next has to wait for room.
+        let queued = tokio::spawn({
+            let handle = handle.clone();
+            async move { handle.list().await }
+        });
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let waiting =
+            tokio::time::timeout(Duration::from_millis(20), handle.get(Uuid::nil())).await;
+        assert!(waiting.is_err());
+
+        release.send(()).unwrap();
+        assert!(queued.await.unwrap().unwrap().is_empty());
+        assert!(handle.get(Uuid::nil()).await.unwrap().is_none());
+        actor.shutdown().await.unwrap();
+    }
+}
@@ -7,27 +7,40 @@ use serde_with::{serde_as, DisplayFromStr};
 
 use crate::error::Error as MainError;
 
+use chrono::{DateTime, Utc};
+
 #[serde_as]
 #[derive(Debug, Serialize, Display)]
 pub enum Error {
     #[display("Validation error: {}", message)]
     ValidationError { message: String },
 
-    #[display("Lock error: {}", message)]
-    LockError { message: String },
+    #[display("{} timed out after {} ms", operation, after_ms)]
+    Timeout { operation: String, after_ms: u64 },
+
+    // The service's actor has shut down and takes no more commands.
+    #[display("{} service has stopped", entity)]
+    Stopped { entity: &'static str },
+
+    // The service's actor ended without being asked to, e.g. a command panicked.
+    #[display("{} service stopped abnormally: {}", entity, message)]
+    Aborted {
+        entity: &'static str,
+        message: String,
+    },
+
+    // The repository failed too often; calls are refused until `retry_at`.
+    #[display("Circuit open for {} until {}", operation, retry_at)]
+    CircuitOpen {
+        operation: String,
+        retry_at: DateTime<Utc>,
+    },
 }
 
 impl std::error::Error for Error {}
 
 impl From<Error> for MainError {
     fn from(error: Error) -> Self {
-        match error {
-            Error::ValidationError { message } => {
-                MainError::ServiceError(Error::ValidationError { message }.into())
-            }
-            Error::LockError { message } => {
-                MainError::ServiceError(Error::LockError { message }.into())
-            }
-        }
+        MainError::ServiceError(error)
     }
 }
@@ -1,89 +1,123 @@
 use crate::result::*;
+
+use crate::clock::SystemClock;
+use crate::id_generator::RandomIdGenerator;
+use crate::service::policy::Policy;
+
+use crate::metrics;
+
+use super::generic_service::{repository_error, span};
 use crate::service::error::Error as ServiceError;
 
 use crate::entity::estimate::Estimate;
 use crate::entity::section::Section;
 use crate::repository::repository::Repository; // Adjust path as necessary
+use crate::service::actor::ServiceHandle;
 use crate::service::generic_service::GenericService;
-use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
 impl GenericService<Estimate> {
     pub fn new(repo: Arc<Mutex<dyn Repository<Estimate> + Send + Sync>>) -> Self {
-        GenericService { repository: repo }
-    }
-
-    // Make `with_repository` async to properly await lock acquisition and repo operations
-    async fn with_repository<F, Fut, R>(&self, operation: F) -> Result<R>
-    where
-        F: FnOnce(&mut (dyn Repository<Estimate> + Send + Sync + 'static)) -> Fut + Send,
-        Fut: Future<Output = Result<R>> + Send,
-        R: Send + 'static,
-    {
-        let mut repo = self.repository.lock().await;
-        operation(&mut *repo).await
+        GenericService {
+            repository: repo,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+            limits: Arc::default(),
+            policy: Policy::disabled(),
+            breaker: Arc::default(),
+        }
     }
 
     pub async fn add_estimate(&self, estimate: Estimate) -> Result<Estimate> {
-        // Assuming is_valid_estimate is a synchronous function validating the estimate
-        // This needs to be defined and should return a Result<(), Error>
-        let is_valid = Self::is_valid_estimate(&estimate);
-
-        if let Err(e) = is_valid {
-            return Err(e);
-        }
-        // Acquire a lock and attempt to add the estimate to the repository
-        let mut repo = self.repository.lock().await;
-
-        let operation_result = repo.add(estimate.clone()).await;
-
-        operation_result.map_err(|err| ServiceError::LockError {
-            message: format!("Error adding estimate: {}", err),
-        })?;
-
-        Ok(estimate)
+        span::<Estimate>("add_estimate", Some(estimate.id))
+            .instrument(async {
+                // Assuming is_valid_estimate is a synchronous function validating the estimate
+                // This needs to be defined and should return a Result<(), Error>
+                self.is_valid_estimate(&estimate)?;
+
+                // Add the estimate under the service policy; each attempt takes the lock again
+                let estimate_ref = &estimate;
+                self.guarded("add_estimate", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.add(estimate_ref.clone()).await
+                })
+                .await
+                .map_err(repository_error("Error adding estimate"))?;
+
+                Ok(estimate)
+            })
+            .await
     }
 
     pub async fn get_estimate(&self, id: Uuid) -> Result<Option<Estimate>> {
-        // Acquire a lock and attempt to retrieve the estimate from the repository
-        let repo = self.repository.lock().await;
-        Ok(repo.get(id).await.map_err(|err| ServiceError::LockError {
-            message: format!("Error getting estimate: {}", err),
-        })?)
+        span::<Estimate>("get_estimate", Some(id))
+            .instrument(async {
+                // Retrieve the estimate from the repository under the service policy
+                self.guarded("get_estimate", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.get(id).await
+                })
+                .await
+                .map_err(repository_error("Error getting estimate"))
+            })
+            .await
     }
 
-    pub async fn update_estimate(&self, estimate: Estimate) -> Result<Estimate> {
-        // Assuming is_valid_estimate is a synchronous function validating the estimate
-        // This needs to be defined and should return a Result<(), Error>
-        Self::is_valid_estimate(&estimate)?;
-
-        // Acquire a lock and attempt to update the estimate in the repository
-        let mut repo = self.repository.lock().await;
-        repo.update(estimate.clone())
+    pub async fn list_estimates(&self) -> Result<Vec<Estimate>> {
+        span::<Estimate>("list_estimates", None)
+            .instrument(async {
+                // Retrieve every estimate from the repository under the service policy
+                self.guarded("list_estimates", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.list().await
+                })
+                .await
+                .map_err(repository_error("Error listing estimates"))
+            })
             .await
-            .map_err(|err| ServiceError::LockError {
-                message: format!("Error updating estimate: {}", err),
-            })?;
+    }
 
-        Ok(estimate)
+    pub async fn update_estimate(&self, estimate: Estimate) -> Result<Estimate> {
+        span::<Estimate>("update_estimate", Some(estimate.id))
+            .instrument(async {
+                // Assuming is_valid_estimate is a synchronous function validating the estimate
+                // This needs to be defined and should return a Result<(), Error>
+                self.is_valid_estimate(&estimate)?;
+
+                // Update the estimate under the service policy; each attempt takes the lock again
+                let estimate_ref = &estimate;
+                self.guarded("update_estimate", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.update(estimate_ref.clone()).await
+                })
+                .await
+                .map_err(repository_error("Error updating estimate"))?;
+
+                Ok(estimate)
+            })
+            .await
     }
 
     pub async fn delete_estimate(&self, id: Uuid) -> Result<()> {
-        // Acquire a lock and attempt to delete the estimate from the repository
-        let mut repo = self.repository.lock().await;
-        repo.delete(id)
+        span::<Estimate>("delete_estimate", Some(id))
+            .instrument(async {
+                // Delete the estimate from the repository under the service policy
+                self.guarded("delete_estimate", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.delete(id).await
+                })
+                .await
+                .map_err(repository_error("Error deleting estimate"))?;
+                Ok(())
+            })
             .await
-            .map_err(|err| ServiceError::LockError {
-                message: format!("Error deleting estimate: {}", err),
-            })?;
-        Ok(())
     }
 
-    fn is_valid_estimate(estimate: &Estimate) -> Result<()> {
-        Estimate::is_valid_name(&estimate.name)?;
-        Estimate::is_valid_description(&estimate.description)?;
+    fn is_valid_estimate(&self, estimate: &Estimate) -> Result<()> {
+        Estimate::is_valid_name(&estimate.name, &self.limits)?;
+        Estimate::is_valid_description(&estimate.description, &self.limits)?;
 
         Ok(())
     }
@@ -93,3 +127,30 @@ impl GenericService<Estimate> {
         Ok(())
     }
 }
+
+// The same operations sent to the estimate service's actor.
+impl ServiceHandle<Estimate> {
+    pub async fn add_estimate(&self, estimate: Estimate) -> Result<Estimate> {
+        self.run(move |service| Box::pin(service.add_estimate(estimate)))
+            .await
+    }
+
+    pub async fn get_estimate(&self, id: Uuid) -> Result<Option<Estimate>> {
+        self.run(move |service| Box::pin(service.get_estimate(id)))
+            .await
+    }
+
+    pub async fn list_estimates(&self) -> Result<Vec<Estimate>> {
+        self.run(|service| Box::pin(service.list_estimates())).await
+    }
+
+    pub async fn update_estimate(&self, estimate: Estimate) -> Result<Estimate> {
+        self.run(move |service| Box::pin(service.update_estimate(estimate)))
+            .await
+    }
+
+    pub async fn delete_estimate(&self, id: Uuid) -> Result<()> {
+        self.run(move |service| Box::pin(service.delete_estimate(id)))
+            .await
+    }
+}
@@ -2,6 +2,8 @@
 
 use crate::result::*;
 
+use crate::metrics;
+
 use async_trait::async_trait; // 0.1.50, for async trait methods
 use std::future::Future;
 use tokio::sync::Mutex;
@@ -10,10 +12,194 @@ use std::sync::Arc;
 use uuid::Uuid;
 
 use super::super::repository::repository::Repository;
+use crate::clock::{Clock, SystemClock};
+use crate::entity::limits::ValidationLimits;
 use crate::entity::traits::Identifiable;
+use crate::id_generator::{IdGenerator, RandomIdGenerator};
+use crate::repository::error::Error as RepositoryError;
+use crate::service::error::Error as ServiceError;
+use crate::service::policy::{self, CircuitBreaker, Policy, Transition};
+use crate::telemetry::span::type_name;
+use crate::telemetry::{self, Level, Span};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+// Logs and counts what the policy did to a call, e.g.
+// `policy_event!(Level::Warn, operation, "retry", message, attempt = attempts)`.
+macro_rules! policy_event {
+    ($level:expr, $operation:expr, $event:literal, $message:expr $(, $($field:tt)+)?) => {{
+        metrics::record_policy_event(&$operation, $event);
+        telemetry::event!(
+            $level,
+            layer = "service",
+            operation = %$operation,
+            event = $event,
+            $($($field)+,)?
+            "service {} {}",
+            $operation,
+            $message
+        );
+    }};
+}
+
+// Service spans are logged at debug level; use case spans already cover the happy path.
+pub(crate) fn span<T>(operation: &str, entity_id: Option<Uuid>) -> Span {
+    Span::new("service", format!("{}.{}", type_name::<T>(), operation))
+        .with_entity(entity_id)
+        .at(Level::Debug)
+}
 
 pub struct GenericService<T> {
     pub repository: Arc<Mutex<dyn Repository<T> + Send + Sync>>,
+    // Timestamps and ids for what the service creates or changes; the system clock and random
+    // ids unless replaced with `with_clock` / `with_id_generator`.
+    pub clock: Arc<dyn Clock>,
+    pub ids: Arc<dyn IdGenerator>,
+    // Bounds the validation rules apply; the defaults unless replaced with `with_limits`.
+    pub limits: Arc<ValidationLimits>,
+    // Timeout, retries and circuit breaking around repository calls; see `service::policy`.
+    pub policy: Policy,
+    pub breaker: Arc<CircuitBreaker>,
+}
+
+impl<T> GenericService<T> {
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_id_generator(mut self, ids: Arc<dyn IdGenerator>) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    pub fn with_limits(mut self, limits: ValidationLimits) -> Self {
+        self.limits = Arc::new(limits);
+        self
+    }
+
+    // Also resets the circuit breaker.
+    pub fn with_policy(mut self, policy: Policy) -> Self {
+        self.policy = policy;
+        self.breaker = Arc::new(CircuitBreaker::default());
+        self
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    pub fn next_id(&self) -> Uuid {
+        self.ids.next_id()
+    }
+
+    // Runs one repository call under the policy. `attempt` is called again for each retry, so
+    // it must take the repository lock itself rather than hold it across attempts.
+    pub(crate) async fn guarded<R, F, Fut>(&self, operation: &str, mut attempt: F) -> Result<R>
+    where
+        F: FnMut() -> Fut + Send,
+        Fut: Future<Output = Result<R>> + Send,
+        R: Send,
+    {
+        let operation = format!("{}.{}", type_name::<T>(), operation);
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            if let Err(retry_at) = self.breaker.admit(self.now()) {
+                policy_event!(Level::Debug, operation, "rejected", "circuit open");
+                return Err(Box::new(ServiceError::CircuitOpen {
+                    operation,
+                    retry_at,
+                }));
+            }
+
+            let result = match self.policy.timeout() {
+                Some(limit) => match tokio::time::timeout(limit, attempt()).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        policy_event!(Level::Warn, operation, "timeout", "timed out");
+                        Err(Box::new(ServiceError::Timeout {
+                            operation: operation.clone(),
+                            after_ms: self.policy.timeout_ms,
+                        })
+                            as Box<dyn std::error::Error + Send + Sync>)
+                    }
+                },
+                None => attempt().await,
+            };
+
+            let error = match result {
+                Ok(value) => {
+                    if self.breaker.succeeded() == Some(Transition::Closed) {
+                        policy_event!(Level::Info, operation, "closed", "circuit closed");
+                    }
+                    return Ok(value);
+                }
+                Err(error) if policy::is_transient(error.as_ref()) => error,
+                Err(error) => {
+                    // The backend answered, so it is healthy even if the request was refused.
+                    self.breaker.succeeded();
+                    return Err(error);
+                }
+            };
+
+            if self.breaker.failed(self.now(), &self.policy) == Some(Transition::Opened) {
+                policy_event!(
+                    Level::Warn,
+                    operation,
+                    "opened",
+                    "circuit opened",
+                    error = %error
+                );
+                return Err(error);
+            }
+            if attempts >= self.policy.max_attempts {
+                return Err(error);
+            }
+            let delay = self.policy.backoff(attempts, policy::jitter_sample());
+            policy_event!(
+                Level::Warn,
+                operation,
+                "retry",
+                format!("retrying in {} ms", delay.as_millis()),
+                attempt = attempts,
+                delay_ms = delay.as_millis() as u64,
+                error = %error
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+// Passes the policy's own errors (a timeout, an open circuit) and the repositories' typed errors
+// on as they are, so callers and problem details can still tell a conflict from a missing item.
+// Anything else becomes a `RepositoryError` naming what the service was doing: `Unavailable` if
+// another attempt might succeed, `BasicError` otherwise.
+pub(crate) fn repository_error(
+    context: &'static str,
+) -> impl FnOnce(Box<dyn std::error::Error + Send + Sync>) -> Box<dyn std::error::Error + Send + Sync>
+{
+    move |error| {
+        if is_typed(error.as_ref()) {
+            return error;
+        }
+        let message = format!("{}: {}", context, error);
+        if policy::is_transient(error.as_ref()) {
+            Box::new(RepositoryError::Unavailable { message })
+        } else {
+            Box::new(RepositoryError::BasicError { message })
+        }
+    }
+}
+
+fn is_typed(error: &(dyn std::error::Error + 'static)) -> bool {
+    #[cfg(feature = "file-backend")]
+    if error.is::<crate::repository::file_repo::error::Error>() {
+        return true;
+    }
+    error.is::<ServiceError>()
+        || error.is::<RepositoryError>()
+        || error.is::<crate::repository::in_memory_repo::error::Error>()
 }
 
 #[async_trait]
@@ -29,8 +215,10 @@ where
 
     async fn add(&self, item: T) -> Result<Uuid>;
     async fn get(&self, id: Uuid) -> Result<Option<T>>;
+    async fn list(&self) -> Result<Vec<T>>;
     async fn update(&self, item: T) -> Result<()>;
     async fn delete(&self, id: Uuid) -> Result<()>;
+    async fn flush(&self) -> Result<()>;
 }
 
 #[async_trait]
@@ -39,23 +227,65 @@ where
     T: Identifiable + Clone + Send + Sync + 'static, // Adjust trait bounds for async and concurrency
 {
     async fn add(&self, item: T) -> Result<Uuid> {
-        let mut repo = self.repository.lock().await; // Use async lock
-        repo.add(item).await // Assume repo.add is async
+        span::<T>("add", Some(item.id()))
+            .instrument(async {
+                let item = &item;
+                self.guarded("add", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.add(item.clone()).await
+                })
+                .await
+            })
+            .await
     }
 
     async fn get(&self, id: Uuid) -> Result<Option<T>> {
-        let repo = self.repository.lock().await;
-        repo.get(id).await // Assume repo.get is async
+        span::<T>("get", Some(id))
+            .instrument(self.guarded("get", || async move {
+                let repo = metrics::lock(&self.repository).await;
+                repo.get(id).await
+            }))
+            .await
+    }
+
+    async fn list(&self) -> Result<Vec<T>> {
+        span::<T>("list", None)
+            .instrument(self.guarded("list", || async move {
+                let repo = metrics::lock(&self.repository).await;
+                repo.list().await
+            }))
+            .await
     }
 
     async fn update(&self, item: T) -> Result<()> {
-        let mut repo = self.repository.lock().await;
-        repo.update(item).await // Assume repo.update is async
+        span::<T>("update", Some(item.id()))
+            .instrument(async {
+                let item = &item;
+                self.guarded("update", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.update(item.clone()).await
+                })
+                .await
+            })
+            .await
     }
 
     async fn delete(&self, id: Uuid) -> Result<()> {
-        let mut repo = self.repository.lock().await;
-        repo.delete(id).await // Assume repo.delete is async
+        span::<T>("delete", Some(id))
+            .instrument(self.guarded("delete", || async move {
+                let repo = metrics::lock(&self.repository).await;
+                repo.delete(id).await
+            }))
+            .await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        span::<T>("flush", None)
+            .instrument(self.guarded("flush", || async move {
+                let repo = metrics::lock(&self.repository).await;
+                repo.flush().await
+            }))
+            .await
     }
 
     async fn with_repository<F, Fut, R>(&self, operation: F) -> Result<R>
@@ -64,7 +294,101 @@ where
         Fut: Future<Output = Result<R>> + Send,
         R: Send + 'static,
     {
-        let mut repo = self.repository.lock().await;
-        operation(&mut *repo).await
+        span::<T>("with_repository", None)
+            .instrument(async {
+                let mut repo = metrics::lock(&self.repository).await;
+                operation(&mut *repo).await
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::entity::estimate::Estimate;
+    use crate::repository::conformance::Sample;
+    use crate::repository::fault_injecting_repo::{
+        FaultHandle, FaultInjectingRepository, Operation, Trigger,
+    };
+    use crate::repository::in_memory_repo::InMemoryRepository;
+    use std::time::Duration;
+
+    fn service(policy: Policy) -> (GenericService<Estimate>, FaultHandle) {
+        let repository = FaultInjectingRepository::new(InMemoryRepository::<Estimate>::new());
+        let faults = repository.handle();
+        let service = GenericService::<Estimate>::new(Arc::new(Mutex::new(repository)))
+            .with_policy(Policy {
+                initial_backoff_ms: 1,
+                max_backoff_ms: 5,
+                ..policy
+            });
+        (service, faults)
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let (service, faults) = service(Policy::default());
+        let estimate = Estimate::sample(1);
+        service.add(estimate.clone()).await.unwrap();
+
+        faults.fail(Operation::Get, Trigger::FirstN(2));
+        assert!(service.get(estimate.id).await.unwrap().is_some());
+        assert_eq!(faults.calls_to(Operation::Get).len(), 3);
+
+        // A conflict is the same answer every time and is not retried.
+        let error = service.add(estimate.clone()).await.unwrap_err();
+        assert!(error.to_string().ends_with("already exists"));
+        assert_eq!(faults.calls_to(Operation::Add).len(), 2);
+
+        faults.fail(Operation::List, Trigger::Always);
+        assert!(service.list().await.is_err());
+        assert_eq!(faults.calls_to(Operation::List).len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_slow_calls_time_out() {
+        let (service, faults) = service(Policy {
+            timeout_ms: 20,
+            max_attempts: 2,
+            ..Policy::default()
+        });
+        faults.delay(Operation::Get, Duration::from_millis(500), Trigger::Always);
+
+        let error = service.get(Uuid::nil()).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ServiceError>(),
+            Some(ServiceError::Timeout { after_ms: 20, .. })
+        ));
+        assert_eq!(error.to_string(), "Estimate.get timed out after 20 ms");
+        assert_eq!(faults.calls_to(Operation::Get).len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_breaker_opens_then_recovers() {
+        let clock = Arc::new(FixedClock::new(Utc::now()));
+        let (service, faults) = service(Policy {
+            max_attempts: 1,
+            breaker_threshold: 2,
+            breaker_cooldown_ms: 1_000,
+            ..Policy::default()
+        });
+        let service = service.with_clock(clock.clone());
+        faults.fail(Operation::List, Trigger::Always);
+
+        assert!(service.list().await.is_err());
+        assert!(service.list().await.is_err());
+        let error = service.list().await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ServiceError>(),
+            Some(ServiceError::CircuitOpen { .. })
+        ));
+        assert_eq!(faults.calls_to(Operation::List).len(), 2);
+
+        faults.clear();
+        clock.advance(chrono::Duration::seconds(1));
+        assert!(service.list().await.is_ok());
+        assert!(!service.breaker.is_open());
     }
 }
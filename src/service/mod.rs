@@ -1,7 +1,10 @@
 //service/mod.rs
 
+pub mod actor;
 pub mod error;
 pub mod generic_service;
+pub mod policy;
 
 pub mod estimate_service;
+pub mod price_book_service;
 pub mod section_service;
@@ -0,0 +1,281 @@
+// service/policy.rs
+
+// How `GenericService` guards its repository calls: a timeout on each attempt, retries with
+// exponential backoff and jitter for errors that look transient, and a circuit breaker that
+// stops calling a backend which keeps failing. Retries, timeouts and breaker transitions are
+// logged as warnings and counted in the metrics registry.
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex as StdMutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::repository::error::Error as RepositoryError;
+use crate::service::error::Error as ServiceError;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Policy {
+    // Limit on each attempt, lock wait included; 0 disables it.
+    pub timeout_ms: u64,
+    // Attempts per call, the first included; 1 disables retries.
+    pub max_attempts: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub backoff_multiplier: f64,
+    // Up to this fraction of each backoff is taken off at random, 0.0 to 1.0.
+    pub jitter: f64,
+    // Consecutive transient failures that open the breaker; 0 disables it.
+    pub breaker_threshold: u32,
+    // How long an open breaker rejects calls before letting a trial call through.
+    pub breaker_cooldown_ms: u64,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            timeout_ms: 5_000,
+            max_attempts: 3,
+            initial_backoff_ms: 50,
+            max_backoff_ms: 2_000,
+            backoff_multiplier: 2.0,
+            jitter: 0.2,
+            breaker_threshold: 5,
+            breaker_cooldown_ms: 30_000,
+        }
+    }
+}
+
+impl Policy {
+    // Every call goes to the repository once, with no time limit. `GenericService::new` starts
+    // with this; the application installs the configured policy.
+    pub fn disabled() -> Self {
+        Policy {
+            timeout_ms: 0,
+            max_attempts: 1,
+            breaker_threshold: 0,
+            ..Policy::default()
+        }
+    }
+
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.max_attempts == 0 {
+            problems.push("max_attempts must be at least 1".to_string());
+        }
+        if self.initial_backoff_ms > self.max_backoff_ms {
+            problems.push(format!(
+                "initial_backoff_ms ({}) must not exceed max_backoff_ms ({})",
+                self.initial_backoff_ms, self.max_backoff_ms
+            ));
+        }
+        if self.backoff_multiplier.is_nan() || self.backoff_multiplier < 1.0 {
+            problems.push(format!(
+                "backoff_multiplier ({}) must be at least 1.0",
+                self.backoff_multiplier
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.jitter) {
+            problems.push(format!(
+                "jitter ({}) must be between 0.0 and 1.0",
+                self.jitter
+            ));
+        }
+        problems
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        (self.timeout_ms > 0).then(|| Duration::from_millis(self.timeout_ms))
+    }
+
+    // The wait after failed attempt number `attempt` (counting from 1). `random` is in 0.0..1.0.
+    pub fn backoff(&self, attempt: u32, random: f64) -> Duration {
+        let growth = self
+            .backoff_multiplier
+            .powi(attempt.saturating_sub(1) as i32);
+        let base = (self.initial_backoff_ms as f64 * growth).min(self.max_backoff_ms as f64);
+        Duration::from_millis((base * (1.0 - self.jitter * random)).round() as u64)
+    }
+}
+
+// Whether another attempt might succeed: timeouts, backends reporting themselves unavailable
+// and I/O trouble. Validation failures, conflicts and missing items are answered the same way
+// every time, so they are returned at once.
+pub fn is_transient(error: &(dyn std::error::Error + 'static)) -> bool {
+    if let Some(error) = error.downcast_ref::<ServiceError>() {
+        return matches!(error, ServiceError::Timeout { .. });
+    }
+    if let Some(error) = error.downcast_ref::<RepositoryError>() {
+        return matches!(error, RepositoryError::Unavailable { .. });
+    }
+    #[cfg(feature = "file-backend")]
+    if let Some(error) = error.downcast_ref::<crate::repository::file_repo::error::Error>() {
+        return matches!(
+            error,
+            crate::repository::file_repo::error::Error::IoError { .. }
+        );
+    }
+    #[cfg(any(test, feature = "test-support"))]
+    if error
+        .downcast_ref::<crate::repository::fault_injecting_repo::error::Error>()
+        .is_some()
+    {
+        return true;
+    }
+    if let Some(error) = error.downcast_ref::<std::io::Error>() {
+        use std::io::ErrorKind;
+        return matches!(
+            error.kind(),
+            ErrorKind::TimedOut
+                | ErrorKind::Interrupted
+                | ErrorKind::WouldBlock
+                | ErrorKind::ConnectionReset
+                | ErrorKind::ConnectionAborted
+                | ErrorKind::ConnectionRefused
+                | ErrorKind::BrokenPipe
+        );
+    }
+    false
+}
+
+// A number in 0.0..1.0 for jitter; it only has to differ between calls and processes.
+pub(crate) fn jitter_sample() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BreakerState {
+    Closed { failures: u32 },
+    Open { until: DateTime<Utc> },
+    // The cooldown is over and one trial call is in flight.
+    HalfOpen,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transition {
+    Opened,
+    Closed,
+}
+
+pub struct CircuitBreaker {
+    state: StdMutex<BreakerState>,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        CircuitBreaker {
+            state: StdMutex::new(BreakerState::Closed { failures: 0 }),
+        }
+    }
+}
+
+impl CircuitBreaker {
+    fn state(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        !matches!(*self.state(), BreakerState::Closed { .. })
+    }
+
+    // Lets a call through, or says when calls will be tried again.
+    pub fn admit(&self, now: DateTime<Utc>) -> Result<(), DateTime<Utc>> {
+        let mut state = self.state();
+        match *state {
+            BreakerState::Closed { .. } => Ok(()),
+            BreakerState::Open { until } if now >= until => {
+                *state = BreakerState::HalfOpen;
+                Ok(())
+            }
+            BreakerState::Open { until } => Err(until),
+            BreakerState::HalfOpen => Err(now),
+        }
+    }
+
+    pub fn succeeded(&self) -> Option<Transition> {
+        let mut state = self.state();
+        let was_open = !matches!(*state, BreakerState::Closed { .. });
+        *state = BreakerState::Closed { failures: 0 };
+        was_open.then_some(Transition::Closed)
+    }
+
+    pub fn failed(&self, now: DateTime<Utc>, policy: &Policy) -> Option<Transition> {
+        if policy.breaker_threshold == 0 {
+            return None;
+        }
+        let mut state = self.state();
+        let failures = match *state {
+            BreakerState::Closed { failures } => failures + 1,
+            // A failed trial call opens the breaker again straight away.
+            _ => policy.breaker_threshold,
+        };
+        if failures >= policy.breaker_threshold {
+            *state = BreakerState::Open {
+                until: now + chrono::Duration::milliseconds(policy.breaker_cooldown_ms as i64),
+            };
+            Some(Transition::Opened)
+        } else {
+            *state = BreakerState::Closed { failures };
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let policy = Policy {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            backoff_multiplier: 2.0,
+            jitter: 0.5,
+            ..Policy::default()
+        };
+        assert_eq!(policy.backoff(1, 0.0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3, 0.0), Duration::from_millis(400));
+        assert_eq!(policy.backoff(10, 0.0), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff(2, 0.5), Duration::from_millis(150));
+        assert!((0.0..1.0).contains(&jitter_sample()));
+
+        let broken = Policy {
+            max_attempts: 0,
+            jitter: 2.0,
+            ..Policy::default()
+        };
+        assert_eq!(broken.problems().len(), 2);
+    }
+
+    #[test]
+    fn test_breaker_opens_and_recovers() {
+        let policy = Policy {
+            breaker_threshold: 2,
+            breaker_cooldown_ms: 1_000,
+            ..Policy::default()
+        };
+        let breaker = CircuitBreaker::default();
+        let now = Utc::now();
+
+        assert_eq!(breaker.failed(now, &policy), None);
+        assert_eq!(breaker.failed(now, &policy), Some(Transition::Opened));
+        let until = breaker.admit(now).unwrap_err();
+        assert_eq!(until, now + chrono::Duration::seconds(1));
+
+        // After the cooldown one trial call goes through; others wait for its outcome.
+        assert!(breaker.admit(until).is_ok());
+        assert!(breaker.admit(until).is_err());
+        assert_eq!(breaker.succeeded(), Some(Transition::Closed));
+        assert!(breaker.admit(until).is_ok());
+        assert!(!breaker.is_open());
+    }
+}
@@ -0,0 +1,220 @@
+// service/price_book_service.rs
+use crate::result::*;
+
+use crate::clock::SystemClock;
+use crate::id_generator::RandomIdGenerator;
+use crate::service::policy::Policy;
+
+use crate::metrics;
+
+use super::generic_service::{repository_error, span};
+use crate::service::error::Error as ServiceError;
+
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::entity::price_book_item::{PriceBookItem, PriceBookSearch};
+
+use super::super::repository::repository::Repository;
+use super::actor::ServiceHandle;
+use super::generic_service::GenericService;
+
+impl GenericService<PriceBookItem> {
+    pub fn new(repo: Arc<Mutex<dyn Repository<PriceBookItem> + Send + Sync>>) -> Self {
+        GenericService {
+            repository: repo,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+            limits: Arc::default(),
+            policy: Policy::disabled(),
+            breaker: Arc::default(),
+        }
+    }
+
+    // Refuses a version of a code whose dates overlap another version of it, so at most one
+    // price applies on any day.
+    pub async fn add_price_book_item(&self, item: PriceBookItem) -> Result<PriceBookItem> {
+        span::<PriceBookItem>("add_price_book_item", Some(item.id))
+            .instrument(async {
+                Self::is_valid_price_book_item(&item)?;
+                self.check_overlaps(&item).await?;
+
+                // Add the item under the service policy; each attempt takes the lock again
+                let item_ref = &item;
+                self.guarded("add_price_book_item", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.add(item_ref.clone()).await
+                })
+                .await
+                .map_err(repository_error("Error adding price book item"))?;
+
+                Ok(item)
+            })
+            .await
+    }
+
+    pub async fn get_price_book_item(&self, id: Uuid) -> Result<Option<PriceBookItem>> {
+        span::<PriceBookItem>("get_price_book_item", Some(id))
+            .instrument(async {
+                // Retrieve the item from the repository under the service policy
+                self.guarded("get_price_book_item", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.get(id).await
+                })
+                .await
+                .map_err(repository_error("Error getting price book item"))
+            })
+            .await
+    }
+
+    pub async fn list_price_book_items(&self) -> Result<Vec<PriceBookItem>> {
+        span::<PriceBookItem>("list_price_book_items", None)
+            .instrument(async {
+                // Retrieve every item from the repository under the service policy
+                self.guarded("list_price_book_items", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.list().await
+                })
+                .await
+                .map_err(repository_error("Error listing price book items"))
+            })
+            .await
+    }
+
+    pub async fn update_price_book_item(&self, item: PriceBookItem) -> Result<PriceBookItem> {
+        span::<PriceBookItem>("update_price_book_item", Some(item.id))
+            .instrument(async {
+                Self::is_valid_price_book_item(&item)?;
+                self.check_overlaps(&item).await?;
+
+                // Update the item under the service policy; each attempt takes the lock again
+                let item_ref = &item;
+                self.guarded("update_price_book_item", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.update(item_ref.clone()).await
+                })
+                .await
+                .map_err(repository_error("Error updating price book item"))?;
+
+                Ok(item)
+            })
+            .await
+    }
+
+    pub async fn delete_price_book_item(&self, id: Uuid) -> Result<()> {
+        span::<PriceBookItem>("delete_price_book_item", Some(id))
+            .instrument(async {
+                // Delete the item from the repository under the service policy
+                self.guarded("delete_price_book_item", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.delete(id).await
+                })
+                .await
+                .map_err(repository_error("Error deleting price book item"))?;
+                Ok(())
+            })
+            .await
+    }
+
+    // Matching items ordered by code, then oldest version first.
+    pub async fn search_price_book(&self, search: &PriceBookSearch) -> Result<Vec<PriceBookItem>> {
+        span::<PriceBookItem>("search_price_book", None)
+            .instrument(async {
+                let mut items: Vec<PriceBookItem> = self
+                    .list_price_book_items()
+                    .await?
+                    .into_iter()
+                    .filter(|item| search.matches(item))
+                    .collect();
+                items.sort_by(|a, b| {
+                    a.code
+                        .cmp(&b.code)
+                        .then_with(|| a.effective_from.cmp(&b.effective_from))
+                });
+                Ok(items)
+            })
+            .await
+    }
+
+    // The version of `code` that applies on `date`, if any. Codes match ignoring case.
+    pub async fn find_effective(
+        &self,
+        code: &str,
+        date: NaiveDate,
+    ) -> Result<Option<PriceBookItem>> {
+        Ok(self
+            .list_price_book_items()
+            .await?
+            .into_iter()
+            .find(|item| item.code.eq_ignore_ascii_case(code) && item.is_effective_on(date)))
+    }
+
+    async fn check_overlaps(&self, item: &PriceBookItem) -> Result<()> {
+        let items = self.list_price_book_items().await?;
+        match items
+            .iter()
+            .find(|other| other.id != item.id && other.overlaps(item))
+        {
+            Some(other) => Err(Box::new(ServiceError::ValidationError {
+                message: format!(
+                    "{} already has a price effective from {}",
+                    other.code, other.effective_from
+                ),
+            })),
+            None => Ok(()),
+        }
+    }
+
+    fn is_valid_price_book_item(item: &PriceBookItem) -> Result<()> {
+        PriceBookItem::is_valid_code(&item.code)?;
+        PriceBookItem::is_valid_description(&item.description)?;
+        PriceBookItem::is_valid_costs(&item.costs)?;
+        PriceBookItem::is_valid_effective_dates(item.effective_from, item.effective_to)?;
+        Ok(())
+    }
+}
+
+// The same operations sent to the price book service's actor.
+impl ServiceHandle<PriceBookItem> {
+    pub async fn add_price_book_item(&self, item: PriceBookItem) -> Result<PriceBookItem> {
+        self.run(move |service| Box::pin(service.add_price_book_item(item)))
+            .await
+    }
+
+    pub async fn get_price_book_item(&self, id: Uuid) -> Result<Option<PriceBookItem>> {
+        self.run(move |service| Box::pin(service.get_price_book_item(id)))
+            .await
+    }
+
+    pub async fn list_price_book_items(&self) -> Result<Vec<PriceBookItem>> {
+        self.run(|service| Box::pin(service.list_price_book_items()))
+            .await
+    }
+
+    pub async fn update_price_book_item(&self, item: PriceBookItem) -> Result<PriceBookItem> {
+        self.run(move |service| Box::pin(service.update_price_book_item(item)))
+            .await
+    }
+
+    pub async fn delete_price_book_item(&self, id: Uuid) -> Result<()> {
+        self.run(move |service| Box::pin(service.delete_price_book_item(id)))
+            .await
+    }
+
+    pub async fn search_price_book(&self, search: PriceBookSearch) -> Result<Vec<PriceBookItem>> {
+        self.run(move |service| Box::pin(async move { service.search_price_book(&search).await }))
+            .await
+    }
+
+    pub async fn find_effective(
+        &self,
+        code: String,
+        date: NaiveDate,
+    ) -> Result<Option<PriceBookItem>> {
+        self.run(move |service| Box::pin(async move { service.find_effective(&code, date).await }))
+            .await
+    }
+}
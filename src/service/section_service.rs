@@ -1,91 +1,431 @@
 // service/section_service.rs
 use crate::result::*;
+
+use crate::clock::SystemClock;
+use crate::id_generator::RandomIdGenerator;
+use crate::service::policy::Policy;
+
+use crate::metrics;
+use crate::telemetry::{self, Level};
+
+use super::generic_service::{repository_error, span, Service};
 use crate::service::error::Error as ServiceError;
 
-use std::future::Future;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use chrono::format::Item;
+use serde_json::json;
 use std::fmt::format;
 use uuid::Uuid;
 
+use crate::entity::line_item::LineItem;
 use crate::entity::section::Section;
 
 use super::super::repository::repository::Repository;
+use super::actor::ServiceHandle;
 use super::generic_service::GenericService;
 
 impl GenericService<Section> {
     pub fn new(repo: Arc<Mutex<dyn Repository<Section> + Send + Sync>>) -> Self {
-        GenericService { repository: repo }
+        GenericService {
+            repository: repo,
+            clock: Arc::new(SystemClock),
+            ids: Arc::new(RandomIdGenerator),
+            limits: Arc::default(),
+            policy: Policy::disabled(),
+            breaker: Arc::default(),
+        }
     }
 
-    // Make `with_repository` async to properly await lock acquisition and repo operations
-    async fn with_repository<F, Fut, R>(&self, operation: F) -> Result<R>
-    where
-        F: FnOnce(&mut (dyn Repository<Section> + Send + Sync + 'static)) -> Fut + Send,
-        Fut: Future<Output = Result<R>> + Send,
-        R: Send + 'static,
-    {
-        let mut repo = self.repository.lock().await;
-        operation(&mut *repo).await
+    pub async fn add_section(&self, section: Section) -> Result<Section> {
+        span::<Section>("add_section", Some(section.id))
+            .instrument(async {
+                self.is_valid_section(&section)?;
+
+                // Add the section under the service policy; each attempt takes the lock again
+                let section_ref = &section;
+                self.guarded("add_section", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.add(section_ref.clone()).await
+                })
+                .await
+                .map_err(repository_error("Error adding section"))?;
+
+                Ok(section)
+            })
+            .await
     }
 
-    pub async fn add_section(&self, section: Section) -> Result<Section> {
-        // Assuming is_valid_section is a synchronous function validating the section
-        // This needs to be defined and should return a Result<(), Error>
-        Self::is_valid_section(&section)?;
+    pub async fn get_section(&self, id: Uuid) -> Result<Option<Section>> {
+        span::<Section>("get_section", Some(id))
+            .instrument(async {
+                // Retrieve the section from the repository under the service policy
+                self.guarded("get_section", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.get(id).await
+                })
+                .await
+                .map_err(repository_error("Error getting section"))
+            })
+            .await
+    }
 
-        // Acquire a lock and attempt to add the section to the repository
-        let mut repo = self.repository.lock().await;
+    pub async fn list_sections(&self) -> Result<Vec<Section>> {
+        span::<Section>("list_sections", None)
+            .instrument(async {
+                // Retrieve every section from the repository under the service policy
+                self.guarded("list_sections", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.list().await
+                })
+                .await
+                .map_err(repository_error("Error listing sections"))
+            })
+            .await
+    }
 
-        let operation_result = repo.add(section.clone()).await;
+    // Returns the sections of an estimate in tree order: every parent comes before its
+    // children and siblings are ordered by `position`.
+    pub async fn list_sections_for_estimate(&self, estimate_id: Uuid) -> Result<Vec<Section>> {
+        span::<Section>("list_sections_for_estimate", Some(estimate_id))
+            .instrument(async {
+                let sections = self
+                    .list_sections()
+                    .await?
+                    .into_iter()
+                    .filter(|section| section.estimate_id == Some(estimate_id))
+                    .collect();
+                Ok(Self::tree_order(sections))
+            })
+            .await
+    }
 
-        operation_result.map_err(|err| ServiceError::LockError {
-            message: format!("Error adding section: {}", err),
-        })?;
+    // Deletes every section of an estimate, children before their parents. Returns how many
+    // were deleted. If one cannot be deleted, the ones already deleted are put back before the
+    // error is returned.
+    pub async fn delete_sections_for_estimate(&self, estimate_id: Uuid) -> Result<usize> {
+        let sections = self.list_sections_for_estimate(estimate_id).await?;
+        for (deleted, section) in sections.iter().rev().enumerate() {
+            if let Err(error) = self.delete_section(section.id).await {
+                self.restore(&sections[sections.len() - deleted..]).await;
+                return Err(error);
+            }
+        }
+        Ok(sections.len())
+    }
 
-        Ok(section)
+    // Puts deleted sections back as they were stored, parents first. A section that cannot be
+    // restored is reported and skipped so the caller's own error is the one returned.
+    async fn restore(&self, deleted: &[Section]) {
+        for section in deleted {
+            if let Err(error) = self.add(section.clone()).await {
+                tracing::warn!(section_id = %section.id, error = %error, "Could not restore a deleted section");
+            }
+        }
     }
 
-    pub async fn get_section(&self, id: Uuid) -> Result<Option<Section>> {
-        // Acquire a lock and attempt to retrieve the section from the repository
-        let repo = self.repository.lock().await;
-        Ok(repo.get(id).await.map_err(|err| ServiceError::LockError {
-            message: format!("Error getting section: {}", err),
-        })?)
+    // Returns every section below `id`, in tree order, without the section itself.
+    pub async fn list_descendants(&self, id: Uuid) -> Result<Vec<Section>> {
+        let sections = self.list_sections().await?;
+        let mut descendants = Vec::new();
+        Self::collect_children(&sections, Some(id), &mut descendants);
+        Ok(descendants)
     }
 
-    pub async fn update_section(&self, section: Section) -> Result<Section> {
-        // Assuming is_valid_section is a synchronous function validating the section
-        // This needs to be defined and should return a Result<(), Error>
-        Self::is_valid_section(&section)?;
+    // Returns the direct children of `parent_id` (or the top-level sections) ordered by position.
+    pub async fn list_siblings(
+        &self,
+        estimate_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<Vec<Section>> {
+        let mut siblings: Vec<Section> = self
+            .list_sections()
+            .await?
+            .into_iter()
+            .filter(|section| {
+                section.estimate_id == Some(estimate_id) && section.parent_id == parent_id
+            })
+            .collect();
+        siblings.sort_by(|a, b| {
+            a.position
+                .cmp(&b.position)
+                .then_with(|| a.code.cmp(&b.code))
+        });
+        Ok(siblings)
+    }
+
+    // Gives the sections positions 0..n in the order given, saving only those that changed.
+    pub async fn renumber_siblings(&self, siblings: Vec<Section>) -> Result<()> {
+        for (position, mut section) in siblings.into_iter().enumerate() {
+            if section.position != position as u32 {
+                section.position = position as u32;
+                section.updated_at = self.now();
+                self.update_section(section).await?;
+            }
+        }
+        Ok(())
+    }
 
-        // Acquire a lock and attempt to update the section in the repository
-        let mut repo = self.repository.lock().await;
-        repo.update(section.clone())
+    // Copies every section of `from_estimate` into `to_estimate` with fresh ids, keeping the
+    // tree shape and sibling order. Returns the number of sections copied; a failed copy
+    // leaves none of them behind.
+    pub async fn copy_sections(&self, from_estimate: Uuid, to_estimate: Uuid) -> Result<usize> {
+        span::<Section>("copy_sections", Some(from_estimate))
+            .instrument(async {
+                let sections = self.list_sections_for_estimate(from_estimate).await?;
+                let new_ids: HashMap<Uuid, Uuid> = sections
+                    .iter()
+                    .map(|section| (section.id, self.next_id()))
+                    .collect();
+
+                let copies = sections
+                    .iter()
+                    .map(|section| {
+                        let mut copy = self.fresh_copy(section);
+                        copy.id = new_ids[&section.id];
+                        copy.estimate_id = Some(to_estimate);
+                        copy.parent_id = section
+                            .parent_id
+                            .and_then(|parent_id| new_ids.get(&parent_id).copied());
+                        copy
+                    })
+                    .collect();
+                Ok(self.add_sections(copies).await?.len())
+            })
             .await
-            .map_err(|err| ServiceError::LockError {
-                message: format!("Error updating section: {}", err),
-            })?;
+    }
+
+    // Adds the sections in order, parents before children. If one fails, the ones already
+    // added are deleted again before the error is returned.
+    pub async fn add_sections(&self, sections: Vec<Section>) -> Result<Vec<Section>> {
+        let mut added: Vec<Section> = Vec::with_capacity(sections.len());
+        for section in sections {
+            match self.add_section(section).await {
+                Ok(section) => added.push(section),
+                Err(error) => {
+                    self.discard(&added).await;
+                    return Err(error);
+                }
+            }
+        }
+        Ok(added)
+    }
+
+    // Deletes partially added sections, children first. A section that cannot be deleted is
+    // reported and skipped so the caller's own error is the one returned.
+    async fn discard(&self, added: &[Section]) {
+        for section in added.iter().rev() {
+            if let Err(error) = self.delete_section(section.id).await {
+                tracing::warn!(section_id = %section.id, error = %error, "Could not remove a partially added section");
+            }
+        }
+    }
 
-        Ok(section)
+    // Clones a section, giving it and everything nested in it (sub-sections and line items)
+    // new ids and timestamps.
+    fn fresh_copy(&self, section: &Section) -> Section {
+        let now = self.now();
+        Section {
+            id: self.next_id(),
+            sections: section
+                .sections
+                .iter()
+                .map(|nested| self.fresh_copy(nested))
+                .collect(),
+            line_items: section
+                .line_items
+                .iter()
+                .map(|item| LineItem {
+                    id: self.next_id(),
+                    created_at: now,
+                    updated_at: now,
+                    ..item.clone()
+                })
+                .collect(),
+            created_at: now,
+            updated_at: now,
+            ..section.clone()
+        }
+    }
+
+    pub fn tree_order(sections: Vec<Section>) -> Vec<Section> {
+        let ids: HashSet<Uuid> = sections.iter().map(|section| section.id).collect();
+        let mut ordered = Vec::with_capacity(sections.len());
+        // Sections whose parent is missing are treated as roots so they are never dropped.
+        let mut roots: Vec<&Section> = sections
+            .iter()
+            .filter(|section| {
+                section
+                    .parent_id
+                    .is_none_or(|parent| !ids.contains(&parent))
+            })
+            .collect();
+        roots.sort_by(|a, b| {
+            a.position
+                .cmp(&b.position)
+                .then_with(|| a.code.cmp(&b.code))
+        });
+        for root in roots {
+            ordered.push(root.clone());
+            Self::collect_children(&sections, Some(root.id), &mut ordered);
+        }
+        ordered
+    }
+
+    fn collect_children(sections: &[Section], parent_id: Option<Uuid>, into: &mut Vec<Section>) {
+        let mut children: Vec<&Section> = sections
+            .iter()
+            .filter(|section| section.parent_id == parent_id)
+            .collect();
+        children.sort_by(|a, b| {
+            a.position
+                .cmp(&b.position)
+                .then_with(|| a.code.cmp(&b.code))
+        });
+        for child in children {
+            into.push(child.clone());
+            Self::collect_children(sections, Some(child.id), into);
+        }
+    }
+
+    pub async fn update_section(&self, section: Section) -> Result<Section> {
+        span::<Section>("update_section", Some(section.id))
+            .instrument(async {
+                self.is_valid_section(&section)?;
+
+                // Update the section under the service policy; each attempt takes the lock again
+                let section_ref = &section;
+                self.guarded("update_section", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.update(section_ref.clone()).await
+                })
+                .await
+                .map_err(repository_error("Error updating section"))?;
+
+                Ok(section)
+            })
+            .await
     }
 
     pub async fn delete_section(&self, id: Uuid) -> Result<()> {
-        // Acquire a lock and attempt to delete the section from the repository
-        let mut repo = self.repository.lock().await;
-        repo.delete(id)
+        span::<Section>("delete_section", Some(id))
+            .instrument(async {
+                // Delete the section from the repository under the service policy
+                self.guarded("delete_section", || async move {
+                    let repo = metrics::lock(&self.repository).await;
+                    repo.delete(id).await
+                })
+                .await
+                .map_err(repository_error("Error deleting section"))?;
+                Ok(())
+            })
             .await
-            .map_err(|err| ServiceError::LockError {
-                message: format!("Error deleting section: {}", err),
-            })?;
-        Ok(())
     }
 
-    fn is_valid_section(section: &Section) -> Result<()> {
-        //Section::is_valid_name(&section.name)?;
-        //Section::is_valid_description(&section.description)?;
+    // A blank description means the section has none, as the CSV import allows.
+    fn is_valid_section(&self, section: &Section) -> Result<()> {
+        Section::is_valid_name(&section.name, &self.limits)?;
+        if !section.description.is_empty() {
+            Section::is_valid_description(&section.description, &self.limits)?;
+        }
         Ok(())
     }
 }
+
+// The same operations sent to the section service's actor.
+impl ServiceHandle<Section> {
+    pub async fn add_section(&self, section: Section) -> Result<Section> {
+        self.run(move |service| Box::pin(service.add_section(section)))
+            .await
+    }
+
+    pub async fn get_section(&self, id: Uuid) -> Result<Option<Section>> {
+        self.run(move |service| Box::pin(service.get_section(id)))
+            .await
+    }
+
+    pub async fn list_sections(&self) -> Result<Vec<Section>> {
+        self.run(|service| Box::pin(service.list_sections())).await
+    }
+
+    pub async fn list_sections_for_estimate(&self, estimate_id: Uuid) -> Result<Vec<Section>> {
+        self.run(move |service| Box::pin(service.list_sections_for_estimate(estimate_id)))
+            .await
+    }
+
+    pub async fn delete_sections_for_estimate(&self, estimate_id: Uuid) -> Result<usize> {
+        self.run(move |service| Box::pin(service.delete_sections_for_estimate(estimate_id)))
+            .await
+    }
+
+    pub async fn list_descendants(&self, id: Uuid) -> Result<Vec<Section>> {
+        self.run(move |service| Box::pin(service.list_descendants(id)))
+            .await
+    }
+
+    pub async fn list_siblings(
+        &self,
+        estimate_id: Uuid,
+        parent_id: Option<Uuid>,
+    ) -> Result<Vec<Section>> {
+        self.run(move |service| Box::pin(service.list_siblings(estimate_id, parent_id)))
+            .await
+    }
+
+    pub async fn renumber_siblings(&self, siblings: Vec<Section>) -> Result<()> {
+        self.run(move |service| Box::pin(service.renumber_siblings(siblings)))
+            .await
+    }
+
+    pub async fn copy_sections(&self, from_estimate: Uuid, to_estimate: Uuid) -> Result<usize> {
+        self.run(move |service| Box::pin(service.copy_sections(from_estimate, to_estimate)))
+            .await
+    }
+
+    pub async fn add_sections(&self, sections: Vec<Section>) -> Result<Vec<Section>> {
+        self.run(move |service| Box::pin(service.add_sections(sections)))
+            .await
+    }
+
+    pub async fn update_section(&self, section: Section) -> Result<Section> {
+        self.run(move |service| Box::pin(service.update_section(section)))
+            .await
+    }
+
+    pub async fn delete_section(&self, id: Uuid) -> Result<()> {
+        self.run(move |service| Box::pin(service.delete_section(id)))
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::section_dto::SectionDTO;
+    use crate::repository::in_memory_repo::InMemoryRepository;
+
+    #[tokio::test]
+    async fn test_sections_are_validated_before_saving() {
+        let repo = Arc::new(Mutex::new(InMemoryRepository::<Section>::new()));
+        let service = GenericService::<Section>::new(repo).spawn().handle();
+
+        let short_name = Section::from(SectionDTO::new("AB".to_string(), "01".to_string()));
+        let error = service.add_section(short_name).await.unwrap_err();
+        assert!(error.to_string().contains("Name must be between"));
+
+        // A blank description is allowed; a too-short one is not.
+        let mut section = service
+            .add_section(Section::from(SectionDTO::new(
+                "Sitework".to_string(),
+                "02".to_string(),
+            )))
+            .await
+            .unwrap();
+        section.description = "Short".to_string();
+        let error = service.update_section(section).await.unwrap_err();
+        assert!(error.to_string().contains("Description must be between"));
+        assert_eq!(service.list_sections().await.unwrap()[0].description, "");
+    }
+}
@@ -0,0 +1,139 @@
+// telemetry/mod.rs
+
+// Structured diagnostics on top of `tracing`. Work is wrapped in `Span`s that record the layer,
+// operation, entity id, duration and outcome when they finish; free-standing events use the
+// `tracing` macros directly. Every request runs inside a `request` span carrying its request id,
+// so one request can be followed from the controller down to the repository.
+
+pub mod span;
+
+pub use span::{current_request_id, in_request, with_request_id, Span};
+
+use std::fs::OpenOptions;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use derive_more::Display;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Display)]
+pub enum Level {
+    #[display("DEBUG")]
+    Debug,
+    #[default]
+    #[display("INFO")]
+    Info,
+    #[display("WARN")]
+    Warn,
+    #[display("ERROR")]
+    Error,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "debug" => Ok(Level::Debug),
+            "info" => Ok(Level::Info),
+            "warn" => Ok(Level::Warn),
+            "error" => Ok(Level::Error),
+            _ => Err(format!(
+                "Unknown log level: {} (expected debug, info, warn or error)",
+                s
+            )),
+        }
+    }
+}
+
+impl From<Level> for tracing::Level {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Debug => tracing::Level::DEBUG,
+            Level::Info => tracing::Level::INFO,
+            Level::Warn => tracing::Level::WARN,
+            Level::Error => tracing::Level::ERROR,
+        }
+    }
+}
+
+// Emits a `tracing` event at a level only known at run time, e.g.
+// `telemetry::event!(level, from = report.from, "Applied migrations")`.
+macro_rules! event {
+    ($level:expr, $($arg:tt)+) => {
+        match $level {
+            $crate::telemetry::Level::Debug => ::tracing::debug!($($arg)+),
+            $crate::telemetry::Level::Info => ::tracing::info!($($arg)+),
+            $crate::telemetry::Level::Warn => ::tracing::warn!($($arg)+),
+            $crate::telemetry::Level::Error => ::tracing::error!($($arg)+),
+        }
+    };
+}
+pub(crate) use event;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    // One human-readable line per record.
+    #[default]
+    Pretty,
+    // One JSON object per line.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "pretty" => Ok(LogFormat::Pretty),
+            "json" => Ok(LogFormat::Json),
+            _ => Err(format!(
+                "Unknown log format: {} (expected pretty or json)",
+                s
+            )),
+        }
+    }
+}
+
+// Logs go to stderr by default so stdout only carries what the presenters render.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum LogOutput {
+    Stdout,
+    #[default]
+    Stderr,
+    // Appended to, created if missing.
+    File(PathBuf),
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TelemetryConfig {
+    pub format: LogFormat,
+    pub output: LogOutput,
+    pub level: Level,
+}
+
+// Installs the process-wide subscriber. Until this is called nothing is written, which keeps
+// library users and tests quiet unless they install a subscriber of their own.
+pub fn init(config: &TelemetryConfig) -> crate::result::Result<()> {
+    let writer = match &config.output {
+        LogOutput::Stdout => BoxMakeWriter::new(std::io::stdout),
+        LogOutput::Stderr => BoxMakeWriter::new(std::io::stderr),
+        LogOutput::File(path) => BoxMakeWriter::new(Mutex::new(
+            OpenOptions::new().create(true).append(true).open(path)?,
+        )),
+    };
+    let builder = tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::from(config.level))
+        .with_writer(writer)
+        .with_ansi(false);
+    match config.format {
+        LogFormat::Pretty => builder.with_target(false).try_init(),
+        LogFormat::Json => builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .try_init(),
+    }
+}
@@ -0,0 +1,227 @@
+// telemetry/span.rs
+
+use crate::result::*;
+
+use std::future::Future;
+use std::time::Instant;
+
+use tracing::field::Empty;
+use tracing::Instrument;
+use uuid::Uuid;
+
+use super::{event, Level};
+use crate::metrics;
+use crate::problem_details::ProblemDetails;
+
+tokio::task_local! {
+    static REQUEST_ID: Uuid;
+}
+
+pub fn current_request_id() -> Option<Uuid> {
+    REQUEST_ID.try_with(|id| *id).ok()
+}
+
+// Runs `future` with the given request id, e.g. one taken from an incoming request header.
+// Everything it logs is nested in a `request` span carrying the id.
+pub async fn with_request_id<F: Future>(request_id: Uuid, future: F) -> F::Output {
+    let span = tracing::info_span!("request", request_id = %request_id);
+    REQUEST_ID.scope(request_id, future.instrument(span)).await
+}
+
+// Joins the current request if there is one, otherwise starts a new request id.
+pub async fn in_request<F: Future>(future: F) -> F::Output {
+    match current_request_id() {
+        Some(_) => future.await,
+        None => with_request_id(Uuid::new_v4(), future).await,
+    }
+}
+
+// The request a piece of work belongs to, taken on one task and resumed on another, e.g. by a
+// service actor running a caller's command.
+pub struct RequestContext {
+    request_id: Option<Uuid>,
+    span: tracing::Span,
+}
+
+impl RequestContext {
+    pub fn current() -> Self {
+        RequestContext {
+            request_id: current_request_id(),
+            span: tracing::Span::current(),
+        }
+    }
+
+    pub async fn run<F: Future>(self, future: F) -> F::Output {
+        let future = future.instrument(self.span);
+        match self.request_id {
+            Some(request_id) => REQUEST_ID.scope(request_id, future).await,
+            None => future.await,
+        }
+    }
+}
+
+// One unit of work in one layer, recorded as a `tracing` span. When it finishes a single event
+// carries the duration and outcome; failures are logged at error level with the problem-details
+// rendering of the error.
+pub struct Span {
+    layer: &'static str,
+    operation: String,
+    entity_id: Option<Uuid>,
+    level: Level,
+}
+
+impl Span {
+    pub fn new(layer: &'static str, operation: impl Into<String>) -> Self {
+        Span {
+            layer,
+            operation: operation.into(),
+            entity_id: None,
+            level: Level::Info,
+        }
+    }
+
+    pub fn with_entity(mut self, entity_id: Option<Uuid>) -> Self {
+        self.entity_id = entity_id;
+        self
+    }
+
+    // The level successful outcomes are logged at; chatty lower layers use `Level::Debug`.
+    pub fn at(mut self, level: Level) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub async fn instrument<T, F>(self, future: F) -> Result<T>
+    where
+        F: Future<Output = Result<T>>,
+    {
+        let span = match self.level {
+            Level::Debug => tracing::debug_span!(
+                "work",
+                layer = self.layer,
+                operation = %self.operation,
+                entity_id = Empty
+            ),
+            _ => tracing::info_span!(
+                "work",
+                layer = self.layer,
+                operation = %self.operation,
+                entity_id = Empty
+            ),
+        };
+        if let Some(entity_id) = self.entity_id {
+            span.record("entity_id", tracing::field::display(entity_id));
+        }
+
+        let started = Instant::now();
+        let result = future.instrument(span.clone()).await;
+        let elapsed = started.elapsed();
+        metrics::record_operation(self.layer, &self.operation, result.is_ok(), elapsed);
+
+        let duration_us = elapsed.as_micros() as u64;
+        span.in_scope(|| match &result {
+            Ok(_) => event!(
+                self.level,
+                duration_us,
+                outcome = "ok",
+                "{} {}",
+                self.layer,
+                self.operation
+            ),
+            Err(e) => tracing::error!(
+                duration_us,
+                outcome = "error",
+                error = %ProblemDetails::from_error(e.as_ref()).to_log_line(),
+                "{} {}",
+                self.layer,
+                self.operation
+            ),
+        });
+        result
+    }
+}
+
+// `crate::entity::estimate::Estimate` -> `Estimate`, for operation names.
+pub fn type_name<T: ?Sized>() -> &'static str {
+    let name = std::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity::estimate::Estimate;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    struct Writer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Writer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_shared_by_nested_scopes() {
+        assert_eq!(current_request_id(), None);
+
+        let id = Uuid::new_v4();
+        let (outer, inner) = with_request_id(id, async {
+            let outer = current_request_id();
+            let inner = in_request(async { current_request_id() }).await;
+            (outer, inner)
+        })
+        .await;
+
+        assert_eq!(outer, Some(id));
+        assert_eq!(inner, Some(id));
+        assert!(in_request(async { current_request_id() }).await.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_failed_span_logs_the_problem_at_error_level() {
+        let buffer = Arc::new(Mutex::new(Vec::new()));
+        let writer = Arc::clone(&buffer);
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_max_level(tracing::Level::INFO)
+            .with_writer(move || Writer(Arc::clone(&writer)))
+            .finish();
+        let _default = tracing::subscriber::set_default(subscriber);
+
+        let id = Uuid::new_v4();
+        let result: Result<()> = Span::new("repository", "Estimate.get")
+            .with_entity(Some(id))
+            .at(Level::Debug)
+            .instrument(async { Err(std::io::Error::other("disk full").into()) })
+            .await;
+        assert!(result.is_err());
+        Span::new("repository", "Estimate.list")
+            .at(Level::Debug)
+            .instrument(async { Ok(()) })
+            .await
+            .unwrap();
+
+        let output = String::from_utf8(buffer.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1, "{}", output);
+        let record: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(record["level"], "ERROR");
+        assert_eq!(record["message"], "repository Estimate.get");
+        assert_eq!(record["outcome"], "error");
+        assert!(record["error"].as_str().unwrap().contains("disk full"));
+    }
+
+    #[test]
+    fn test_type_name_strips_the_path() {
+        assert_eq!(type_name::<Estimate>(), "Estimate");
+        assert_eq!(type_name::<Vec<Estimate>>(), "Vec");
+    }
+}
@@ -0,0 +1,60 @@
+// use_case/add_price_book_item.rs
+
+use crate::result::*;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::entity::price_book_item::{PriceBookItem, UnitCosts};
+use crate::entity::unit::Unit;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+// A new item, or a new version of an existing code when its dates do not overlap the others.
+pub struct AddPriceBookItemCommand {
+    pub code: String,
+    pub description: String,
+    pub unit: Unit,
+    pub costs: UnitCosts,
+    pub effective_from: NaiveDate,
+    pub effective_to: Option<NaiveDate>,
+}
+
+impl Command for AddPriceBookItemCommand {
+    type Output = Uuid;
+}
+
+pub struct AddPriceBookItem {
+    service: ServiceHandle<PriceBookItem>,
+}
+
+impl AddPriceBookItem {
+    pub fn new(service: ServiceHandle<PriceBookItem>) -> Self {
+        AddPriceBookItem { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<AddPriceBookItemCommand, Uuid> for AddPriceBookItem {
+    async fn execute(&self, command: AddPriceBookItemCommand) -> Result<Uuid> {
+        let service = &self.service;
+
+        let mut item = PriceBookItem::new_with(
+            command.code,
+            command.description,
+            command.unit,
+            command.costs,
+            command.effective_from,
+            service.clock.as_ref(),
+            service.ids.as_ref(),
+        );
+        item.effective_to = command.effective_to;
+
+        // Validation and overlapping versions are reported as they are
+        let item = service.add_price_book_item(item).await?;
+        Ok(item.id)
+    }
+}
@@ -1,38 +1,136 @@
 // use_case/create_estimate.rs
 
 use crate::result::*;
+
+use crate::metrics;
 use crate::use_case::error::Error as UseCaseError;
 
+use async_trait::async_trait;
 use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
+use crate::bus::Command;
 use crate::dto::estimate_dto::EstimateDTO;
 use crate::entity::estimate::Estimate;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::duplicate_estimate::copy_estimate;
+use crate::use_case::traits::UseCase;
 
-use crate::service::generic_service::GenericService;
+pub struct CreateEstimateCommand {
+    pub name: String,
+    pub description: String,
+    pub location: String,
+    // When set, the new estimate starts with a copy of the template's sections.
+    pub template_id: Option<Uuid>,
+}
+
+impl Command for CreateEstimateCommand {
+    type Output = Uuid;
+}
 
 pub struct CreateEstimate {
-    service: Arc<Mutex<GenericService<Estimate>>>,
+    service: ServiceHandle<Estimate>,
+    section_service: ServiceHandle<Section>,
 }
 
 impl CreateEstimate {
-    pub fn new(service: Arc<Mutex<GenericService<Estimate>>>) -> Self {
-        CreateEstimate { service }
+    pub fn new(service: ServiceHandle<Estimate>, section_service: ServiceHandle<Section>) -> Self {
+        CreateEstimate {
+            service,
+            section_service,
+        }
     }
 
-    pub async fn execute(&self, estimate_dto: EstimateDTO) -> Result<Uuid> {
-        // Acquire lock asynchronously without map_err
-        let mut service = self.service.lock().await;
+    async fn instantiate_template(&self, template_id: Uuid, estimate: Estimate) -> Result<Uuid> {
+        let service = &self.service;
+        let section_service = &self.section_service;
 
-        // Now you can call async operations on service
-        // Ensure these operations return Results to use map_err or ?
-        match service.add_estimate(Estimate::from(estimate_dto)).await {
-            Ok(estimate) => Ok(estimate.id),
-            Err(e) => Err(Box::new(UseCaseError::BasicCaseError {
-                message: format!("Error adding estimate: {}", e),
+        match service.get_estimate(template_id).await? {
+            Some(template) if template.is_template => {
+                let estimate = Estimate {
+                    price_guess: template.price_guess,
+                    ..estimate
+                };
+                copy_estimate(service, section_service, template.id, estimate).await
+            }
+            Some(_) => Err(Box::new(UseCaseError::ValidationError {
+                use_case: "create_estimate",
+                message: format!("Estimate {} is not a template", template_id),
+            })),
+            None => Err(Box::new(UseCaseError::NotFoundError {
+                entity: "Estimate",
+                id: template_id,
             })),
         }
     }
 }
+
+#[async_trait]
+impl UseCase<CreateEstimateCommand, Uuid> for CreateEstimate {
+    async fn execute(&self, command: CreateEstimateCommand) -> Result<Uuid> {
+        let service = &self.service;
+
+        let mut estimate_dto = EstimateDTO::new_with(service.ids.as_ref());
+        estimate_dto.name = command.name;
+        estimate_dto.description = command.description;
+        estimate_dto.location = command.location;
+        let estimate = estimate_dto.into_entity(service.clock.as_ref());
+
+        if let Some(template_id) = command.template_id {
+            return self.instantiate_template(template_id, estimate).await;
+        }
+
+        Ok(service.add_estimate(estimate).await?.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::FixedClock;
+    use crate::id_generator::SequentialIdGenerator;
+    use crate::repository::in_memory_repo::InMemoryRepository;
+    use crate::service::generic_service::GenericService;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn test_ids_and_timestamps_come_from_the_service() {
+        let now = chrono::Utc.with_ymd_and_hms(2024, 5, 6, 7, 8, 9).unwrap();
+        let clock = Arc::new(FixedClock::new(now));
+        let ids = Arc::new(SequentialIdGenerator::new());
+        let estimate_service =
+            GenericService::<Estimate>::new(Arc::new(Mutex::new(InMemoryRepository::new())))
+                .with_clock(clock.clone())
+                .with_id_generator(ids.clone())
+                .spawn()
+                .handle();
+        let section_service =
+            GenericService::<Section>::new(Arc::new(Mutex::new(InMemoryRepository::new())))
+                .spawn()
+                .handle();
+        let use_case = CreateEstimate::new(estimate_service.clone(), section_service);
+
+        let command = |name: &str| CreateEstimateCommand {
+            name: name.to_string(),
+            description: "Tenant improvement".to_string(),
+            location: "Denver".to_string(),
+            template_id: None,
+        };
+        let first = use_case.execute(command("Suite 100")).await.unwrap();
+        clock.advance(chrono::Duration::days(1));
+        let second = use_case.execute(command("Suite 200")).await.unwrap();
+
+        assert_eq!(first, Uuid::from_u128(1));
+        assert_eq!(second, Uuid::from_u128(2));
+        let service = &estimate_service;
+        let first = service.get_estimate(first).await.unwrap().unwrap();
+        assert_eq!(first.created_at, now);
+        assert_eq!(first.updated_at, now);
+        let second = service.get_estimate(second).await.unwrap().unwrap();
+        assert_eq!(second.created_at, now + chrono::Duration::days(1));
+    }
+}
@@ -1,62 +1,96 @@
 // use_case/create_section_add_to_estimate.rs
 use crate::result::*;
+
+use crate::metrics;
 use crate::use_case::error::Error as UseCaseError;
 
+use async_trait::async_trait;
 use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 use uuid::Uuid;
 
+use crate::bus::Command;
 use crate::dto::estimate_dto::EstimateDTO;
 use crate::dto::section_dto::SectionDTO;
 use crate::entity::estimate::Estimate;
 use crate::entity::section::{self, Section};
 
-use crate::service::generic_service::{GenericService, Service};
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+// The section is attached under `section.parent_id` when set, otherwise at the top level,
+// after any existing siblings.
+pub struct CreateSectionAddToEstimateCommand {
+    pub section: SectionDTO,
+    pub estimate_id: Uuid,
+}
+
+impl Command for CreateSectionAddToEstimateCommand {
+    type Output = Uuid;
+}
 
 pub struct CreateSectionAddToEstimate {
-    section_service: Arc<Mutex<GenericService<Section>>>,
-    estimate_service: Arc<Mutex<GenericService<Estimate>>>,
+    section_service: ServiceHandle<Section>,
+    estimate_service: ServiceHandle<Estimate>,
 }
 
 impl CreateSectionAddToEstimate {
     pub fn new(
-        section_service: Arc<Mutex<GenericService<Section>>>,
-        estimate_service: Arc<Mutex<GenericService<Estimate>>>,
+        section_service: ServiceHandle<Section>,
+        estimate_service: ServiceHandle<Estimate>,
     ) -> Self {
         CreateSectionAddToEstimate {
             section_service,
             estimate_service,
         }
     }
+}
 
-    pub async fn execute(
-        &self,
-        section_dto: SectionDTO,
-        estimate_dto: EstimateDTO,
-    ) -> Result<Uuid> {
-        // Convert DTOs to entities
-        let estimate = Estimate::from(estimate_dto);
-        let mut section = Section::from(section_dto);
-
-        // Acquire lock asynchronously on estimate service and check if the estimate exists
-        let estimate_service = self.estimate_service.lock().await;
-        match estimate_service.get_estimate(estimate.id).await {
-            Ok(Some(_)) => {
-                // If the estimate exists, proceed to add the section
-                section.estimate_id = Some(estimate.id);
-                let section_service = self.section_service.lock().await;
-                match section_service.add_section(section).await {
-                    Ok(section) => Ok(section.id),
-                    Err(e) => Err(e.into()), // Properly convert or handle the error here
-                }
+#[async_trait]
+impl UseCase<CreateSectionAddToEstimateCommand, Uuid> for CreateSectionAddToEstimate {
+    async fn execute(&self, command: CreateSectionAddToEstimateCommand) -> Result<Uuid> {
+        // Check that the estimate exists before touching sections
+        match self
+            .estimate_service
+            .get_estimate(command.estimate_id)
+            .await?
+        {
+            Some(_) => {
+                // Positioning and saving run as one command, so concurrent adds cannot take
+                // the same position
+                self.section_service
+                    .run(move |section_service| {
+                        Box::pin(async move {
+                            let mut section =
+                                command.section.into_entity(section_service.clock.as_ref());
+                            section.estimate_id = Some(command.estimate_id);
+                            let existing = section_service
+                                .list_sections_for_estimate(command.estimate_id)
+                                .await?;
+                            if let Some(parent_id) = section.parent_id {
+                                if !existing.iter().any(|other| other.id == parent_id) {
+                                    return Err(Box::new(UseCaseError::NotFoundError {
+                                        entity: "Section",
+                                        id: parent_id,
+                                    })
+                                        as Box<dyn std::error::Error + Send + Sync>);
+                                }
+                            }
+                            section.position = existing
+                                .iter()
+                                .filter(|sibling| sibling.parent_id == section.parent_id)
+                                .count() as u32;
+                            let section = section_service.add_section(section).await?;
+                            Ok(section.id)
+                        })
+                    })
+                    .await
             }
-            Ok(None) => Err(Box::new(UseCaseError::BasicCaseError {
-                message: "Estimate does not exist".to_string(),
-            })),
-            Err(e) => Err(Box::new(UseCaseError::BasicCaseError {
-                message: format!("Error getting estimate: {}", e),
+            None => Err(Box::new(UseCaseError::NotFoundError {
+                entity: "Estimate",
+                id: command.estimate_id,
             })),
         }
     }
@@ -0,0 +1,142 @@
+// use_case/delete_estimate.rs
+
+use crate::result::*;
+
+use crate::metrics;
+use crate::telemetry::{self, Level};
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::entity::estimate::Estimate;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+pub struct DeleteEstimateCommand {
+    pub estimate_id: Uuid,
+}
+
+impl Command for DeleteEstimateCommand {
+    type Output = ();
+}
+
+// Deletes an estimate together with every section that belongs to it.
+pub struct DeleteEstimate {
+    estimate_service: ServiceHandle<Estimate>,
+    section_service: ServiceHandle<Section>,
+}
+
+impl DeleteEstimate {
+    pub fn new(
+        estimate_service: ServiceHandle<Estimate>,
+        section_service: ServiceHandle<Section>,
+    ) -> Self {
+        DeleteEstimate {
+            estimate_service,
+            section_service,
+        }
+    }
+}
+
+#[async_trait]
+impl UseCase<DeleteEstimateCommand, ()> for DeleteEstimate {
+    async fn execute(&self, command: DeleteEstimateCommand) -> Result<()> {
+        let estimate_service = &self.estimate_service;
+        let estimate = match estimate_service.get_estimate(command.estimate_id).await? {
+            Some(estimate) => estimate,
+            None => {
+                return Err(Box::new(UseCaseError::NotFoundError {
+                    entity: "Estimate",
+                    id: command.estimate_id,
+                }))
+            }
+        };
+
+        // The estimate goes first so a failure never leaves it without its sections. If the
+        // sections cannot be deleted they are all put back, and so is the estimate.
+        estimate_service
+            .delete_estimate(command.estimate_id)
+            .await?;
+
+        if let Err(e) = self
+            .section_service
+            .delete_sections_for_estimate(command.estimate_id)
+            .await
+        {
+            if let Err(restore) = estimate_service.add(estimate).await {
+                tracing::warn!(estimate_id = %command.estimate_id, error = %restore, "Could not restore a deleted estimate");
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::section_dto::SectionDTO;
+    use crate::repository::conformance::Sample;
+    use crate::repository::fault_injecting_repo::{
+        FaultInjectingRepository, Operation, Outcome, Trigger,
+    };
+    use crate::repository::in_memory_repo::InMemoryRepository;
+    use crate::service::generic_service::GenericService;
+    use crate::service::generic_service::Service;
+
+    #[tokio::test]
+    async fn test_failed_section_delete_keeps_estimate_and_sections() {
+        let estimate_repo = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let section_repo = FaultInjectingRepository::new(InMemoryRepository::<Section>::new());
+        let faults = section_repo.handle();
+        let estimate_service = GenericService::<Estimate>::new(estimate_repo)
+            .spawn()
+            .handle();
+        let section_service = GenericService::<Section>::new(Arc::new(Mutex::new(section_repo)))
+            .spawn()
+            .handle();
+
+        let estimate = Estimate::sample(1);
+        estimate_service.add(estimate.clone()).await.unwrap();
+        let mut parent = SectionDTO::new("Sitework".to_string(), "02".to_string());
+        parent.estimate_id = Some(estimate.id);
+        let parent = Section::from(parent);
+        let mut child = SectionDTO::new("Excavation".to_string(), "02-300".to_string());
+        child.estimate_id = Some(estimate.id);
+        child.parent_id = Some(parent.id);
+        let child = Section::from(child);
+        section_service.add(parent.clone()).await.unwrap();
+        section_service.add(child.clone()).await.unwrap();
+
+        // The child is deleted, then the parent fails.
+        faults.fail(Operation::Delete, Trigger::Nth(2));
+        let error = DeleteEstimate::new(estimate_service.clone(), section_service.clone())
+            .execute(DeleteEstimateCommand {
+                estimate_id: estimate.id,
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().ends_with("Injected failure in delete"));
+
+        let deletes = faults.calls_to(Operation::Delete);
+        assert_eq!(deletes.len(), 2);
+        assert_eq!(deletes[0].outcome, Outcome::Succeeded);
+
+        // The deleted child is put back, and so is the estimate.
+        let restored = estimate_service.get_estimate(estimate.id).await.unwrap();
+        assert_eq!(restored.map(|e| e.id), Some(estimate.id));
+        let sections = section_service
+            .list_sections_for_estimate(estimate.id)
+            .await
+            .unwrap();
+        assert_eq!(sections, vec![parent, child]);
+    }
+}
@@ -0,0 +1,83 @@
+// use_case/delete_section.rs
+
+use crate::result::*;
+
+use crate::metrics;
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::service::generic_service::GenericService;
+use crate::use_case::traits::UseCase;
+
+pub struct DeleteSectionCommand {
+    pub section_id: Uuid,
+}
+
+impl Command for DeleteSectionCommand {
+    type Output = ();
+}
+
+// Deletes a section with all of its sub-sections and closes the gap it leaves among its siblings.
+pub struct DeleteSection {
+    service: ServiceHandle<Section>,
+}
+
+impl DeleteSection {
+    pub fn new(service: ServiceHandle<Section>) -> Self {
+        DeleteSection { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<DeleteSectionCommand, ()> for DeleteSection {
+    async fn execute(&self, command: DeleteSectionCommand) -> Result<()> {
+        self.service
+            .run(move |service| Box::pin(delete_with_descendants(service, command)))
+            .await
+    }
+}
+
+// Runs as one command on the section service's actor, so no other command sees the tree half
+// deleted or the siblings unnumbered.
+async fn delete_with_descendants(
+    service: &GenericService<Section>,
+    command: DeleteSectionCommand,
+) -> Result<()> {
+    let section = match service.get_section(command.section_id).await? {
+        Some(section) => section,
+        None => {
+            return Err(Box::new(UseCaseError::NotFoundError {
+                entity: "Section",
+                id: command.section_id,
+            }))
+        }
+    };
+
+    // Children first, so a failure never leaves orphans behind.
+    for descendant in service
+        .list_descendants(section.id)
+        .await?
+        .into_iter()
+        .rev()
+    {
+        service.delete_section(descendant.id).await?;
+    }
+    service.delete_section(section.id).await?;
+
+    if let Some(estimate_id) = section.estimate_id {
+        let siblings = service
+            .list_siblings(estimate_id, section.parent_id)
+            .await?;
+        service.renumber_siblings(siblings).await?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,256 @@
+// use_case/duplicate_estimate.rs
+
+use crate::result::*;
+
+use crate::metrics;
+use crate::telemetry::{self, Level};
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use serde_json::json;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::entity::estimate::{Estimate, EstimateStatus};
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+pub struct DuplicateEstimateCommand {
+    pub estimate_id: Uuid,
+    // Defaults to "Copy of <original name>".
+    pub name: Option<String>,
+    // Starts the copy over as a draft instead of keeping the original status.
+    pub reset_status: bool,
+    pub as_template: bool,
+}
+
+impl Command for DuplicateEstimateCommand {
+    type Output = Uuid;
+}
+
+// Deep-copies an estimate and its whole section tree under fresh ids.
+pub struct DuplicateEstimate {
+    estimate_service: ServiceHandle<Estimate>,
+    section_service: ServiceHandle<Section>,
+}
+
+impl DuplicateEstimate {
+    pub fn new(
+        estimate_service: ServiceHandle<Estimate>,
+        section_service: ServiceHandle<Section>,
+    ) -> Self {
+        DuplicateEstimate {
+            estimate_service,
+            section_service,
+        }
+    }
+}
+
+#[async_trait]
+impl UseCase<DuplicateEstimateCommand, Uuid> for DuplicateEstimate {
+    async fn execute(&self, command: DuplicateEstimateCommand) -> Result<Uuid> {
+        let estimate_service = &self.estimate_service;
+        let section_service = &self.section_service;
+
+        let source = match estimate_service.get_estimate(command.estimate_id).await? {
+            Some(estimate) => estimate,
+            None => {
+                return Err(Box::new(UseCaseError::NotFoundError {
+                    entity: "Estimate",
+                    id: command.estimate_id,
+                }))
+            }
+        };
+
+        let now = estimate_service.now();
+        let copy = Estimate {
+            id: estimate_service.next_id(),
+            name: command
+                .name
+                .unwrap_or_else(|| format!("Copy of {}", source.name)),
+            status: if command.reset_status {
+                EstimateStatus::Draft
+            } else {
+                source.status
+            },
+            is_template: command.as_template,
+            created_at: now,
+            updated_at: now,
+            ..source.clone()
+        };
+
+        copy_estimate(estimate_service, section_service, source.id, copy).await
+    }
+}
+
+// Saves `copy` and gives it a copy of every section of `source_id`. Shared with
+// `CreateEstimate`, which instantiates templates the same way.
+pub(crate) async fn copy_estimate(
+    estimate_service: &ServiceHandle<Estimate>,
+    section_service: &ServiceHandle<Section>,
+    source_id: Uuid,
+    copy: Estimate,
+) -> Result<Uuid> {
+    let copy = estimate_service.add_estimate(copy).await?;
+
+    // `copy_sections` removes its own partial work; the estimate goes with it so a failed copy
+    // leaves nothing behind.
+    if let Err(e) = section_service.copy_sections(source_id, copy.id).await {
+        if let Err(cleanup) = estimate_service.delete_estimate(copy.id).await {
+            tracing::warn!(estimate_id = %copy.id, error = %cleanup, "Could not remove a partially copied estimate");
+        }
+        return Err(e);
+    }
+
+    Ok(copy.id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::estimate_dto::EstimateDTO;
+    use crate::dto::section_dto::SectionDTO;
+    use crate::entity::line_item::LineItem;
+    use crate::entity::unit::Unit;
+    use crate::repository::conformance::Sample;
+    use crate::repository::fault_injecting_repo::{
+        Fault, FaultInjectingRepository, Operation, Outcome, Trigger,
+    };
+    use crate::repository::in_memory_repo::InMemoryRepository;
+    use crate::service::generic_service::GenericService;
+    use crate::service::generic_service::Service;
+
+    #[tokio::test]
+    async fn test_duplicate_copies_section_tree_with_fresh_ids() {
+        let estimate_repo = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let section_repo = Arc::new(Mutex::new(InMemoryRepository::<Section>::new()));
+        let estimate_service = GenericService::<Estimate>::new(estimate_repo)
+            .spawn()
+            .handle();
+        let section_service = GenericService::<Section>::new(section_repo)
+            .spawn()
+            .handle();
+
+        let mut estimate_dto = EstimateDTO::new();
+        estimate_dto.name = "Warehouse Roof".to_string();
+        estimate_dto.description = "Roof replacement".to_string();
+        estimate_dto.status = EstimateStatus::Accepted;
+        let source = Estimate::from(estimate_dto);
+        estimate_service.add(source.clone()).await.unwrap();
+
+        let mut parent = SectionDTO::new("Roofing".to_string(), "07".to_string());
+        parent.estimate_id = Some(source.id);
+        let parent = Section::from(parent);
+        let mut child = SectionDTO::new("Membrane".to_string(), "07-500".to_string());
+        child.estimate_id = Some(source.id);
+        child.parent_id = Some(parent.id);
+        let mut child = Section::from(child);
+        child.line_items.push(LineItem::new(
+            "TPO membrane".to_string(),
+            8000.0,
+            Unit::SquareFoot,
+            4.1,
+        ));
+        section_service.add(parent.clone()).await.unwrap();
+        section_service.add(child.clone()).await.unwrap();
+
+        let use_case = DuplicateEstimate::new(estimate_service.clone(), section_service.clone());
+        let copy_id = use_case
+            .execute(DuplicateEstimateCommand {
+                estimate_id: source.id,
+                name: None,
+                reset_status: true,
+                as_template: true,
+            })
+            .await
+            .unwrap();
+
+        let copy = estimate_service
+            .get_estimate(copy_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(copy.name, "Copy of Warehouse Roof");
+        assert_eq!(copy.status, EstimateStatus::Draft);
+        assert!(copy.is_template);
+
+        let sections = section_service
+            .list_sections_for_estimate(copy_id)
+            .await
+            .unwrap();
+        assert_eq!(sections.len(), 2);
+        assert!(sections
+            .iter()
+            .all(|s| s.id != parent.id && s.id != child.id));
+        assert_eq!(sections[0].code, "07");
+        assert_eq!(sections[1].parent_id, Some(sections[0].id));
+        assert_eq!(sections[1].line_items.len(), 1);
+        assert_ne!(sections[1].line_items[0].id, child.line_items[0].id);
+        assert_eq!(
+            sections[1].line_items[0].total(),
+            child.line_items[0].total()
+        );
+
+        // The original is untouched.
+        let original = section_service
+            .list_sections_for_estimate(source.id)
+            .await
+            .unwrap();
+        assert_eq!(original, vec![parent, child]);
+    }
+
+    #[tokio::test]
+    async fn test_section_failure_propagates() {
+        let estimate_repo = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let section_repo = FaultInjectingRepository::new(InMemoryRepository::<Section>::new());
+        let faults = section_repo.handle();
+        let estimate_service = GenericService::<Estimate>::new(estimate_repo)
+            .spawn()
+            .handle();
+        let section_service = GenericService::<Section>::new(Arc::new(Mutex::new(section_repo)))
+            .spawn()
+            .handle();
+
+        let source = Estimate::sample(1);
+        estimate_service.add(source.clone()).await.unwrap();
+        for code in ["01", "02"] {
+            let mut section = SectionDTO::new("Sitework".to_string(), code.to_string());
+            section.estimate_id = Some(source.id);
+            section_service.add(Section::from(section)).await.unwrap();
+        }
+
+        // The copy's second section fails to save.
+        faults.fail(Operation::Add, Trigger::Nth(4));
+        let error = DuplicateEstimate::new(estimate_service.clone(), section_service.clone())
+            .execute(DuplicateEstimateCommand {
+                estimate_id: source.id,
+                name: None,
+                reset_status: false,
+                as_template: false,
+            })
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Error adding section"));
+        assert!(error.to_string().ends_with("Injected failure in add"));
+
+        let adds = faults.calls_to(Operation::Add);
+        assert_eq!(adds.len(), 4);
+        assert_eq!(adds[2].outcome, Outcome::Succeeded);
+        assert_eq!(adds[3].faults, vec![Fault::Fail]);
+
+        // The section copied before the failure is removed again, and so is the copy itself.
+        let deletes = faults.calls_to(Operation::Delete);
+        assert_eq!(deletes.len(), 1);
+        assert_eq!(deletes[0].outcome, Outcome::Succeeded);
+        let estimates = estimate_service.list_estimates().await.unwrap();
+        assert_eq!(estimates.len(), 1);
+        assert_eq!(estimates[0].id, source.id);
+        let sections = section_service.list_sections().await.unwrap();
+        assert_eq!(sections.len(), 2);
+        assert!(sections.iter().all(|s| s.estimate_id == Some(source.id)));
+    }
+}
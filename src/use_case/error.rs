@@ -5,24 +5,43 @@ use crate::result::*;
 
 use derive_more::{Display, From};
 
+use chrono::NaiveDate;
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
+use uuid::Uuid;
+
+use super::import_estimate_from_csv::ImportErrorReport;
 
 #[serde_as]
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, Serialize, From, Display)]
 pub enum Error {
     #[display("UseCaseInnerError: {}", message)]
     BasicCaseError { message: String },
+
+    #[display("Validation failed for {}: {}", use_case, message)]
+    ValidationError {
+        use_case: &'static str,
+        message: String,
+    },
+
+    #[display("{} {} does not exist", entity, id)]
+    NotFoundError { entity: &'static str, id: Uuid },
+
+    #[display("No price book item {} is effective on {}", code, date)]
+    PriceNotFound { code: String, date: NaiveDate },
+
+    #[display("CSV import failed: {}", _0)]
+    ImportError(ImportErrorReport),
+
+    #[display("Not authorized to execute {}", use_case)]
+    AuthorizationError { use_case: &'static str },
 }
 
 impl std::error::Error for Error {}
 
 impl From<Error> for MainError {
     fn from(error: Error) -> Self {
-        match error {
-            Error::BasicCaseError { message } => {
-                MainError::UseCaseError(Error::BasicCaseError { message }.into())
-            }
-        }
+        MainError::UseCaseError(error)
     }
 }
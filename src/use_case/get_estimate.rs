@@ -0,0 +1,45 @@
+// use_case/get_estimate.rs
+
+use crate::result::*;
+
+use crate::metrics;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Query;
+use crate::dto::estimate_dto::EstimateDTO;
+use crate::entity::estimate::Estimate;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+pub struct GetEstimateQuery {
+    pub estimate_id: Uuid,
+}
+
+impl Query for GetEstimateQuery {
+    type Output = Option<EstimateDTO>;
+}
+
+pub struct GetEstimate {
+    service: ServiceHandle<Estimate>,
+}
+
+impl GetEstimate {
+    pub fn new(service: ServiceHandle<Estimate>) -> Self {
+        GetEstimate { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<GetEstimateQuery, Option<EstimateDTO>> for GetEstimate {
+    async fn execute(&self, query: GetEstimateQuery) -> Result<Option<EstimateDTO>> {
+        let service = &self.service;
+
+        let estimate = service.get_estimate(query.estimate_id).await?;
+        Ok(estimate.map(EstimateDTO::from))
+    }
+}
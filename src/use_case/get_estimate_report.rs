@@ -0,0 +1,62 @@
+// use_case/get_estimate_report.rs
+
+use crate::result::*;
+
+use crate::metrics;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Query;
+use crate::dto::estimate_report_dto::EstimateReportDTO;
+use crate::entity::estimate::Estimate;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+// The whole estimate flattened for export: every section in tree order with its line items,
+// the per-section totals, and the markup summary.
+pub struct GetEstimateReportQuery {
+    pub estimate_id: Uuid,
+}
+
+impl Query for GetEstimateReportQuery {
+    type Output = Option<EstimateReportDTO>;
+}
+
+pub struct GetEstimateReport {
+    estimate_service: ServiceHandle<Estimate>,
+    section_service: ServiceHandle<Section>,
+}
+
+impl GetEstimateReport {
+    pub fn new(
+        estimate_service: ServiceHandle<Estimate>,
+        section_service: ServiceHandle<Section>,
+    ) -> Self {
+        GetEstimateReport {
+            estimate_service,
+            section_service,
+        }
+    }
+}
+
+#[async_trait]
+impl UseCase<GetEstimateReportQuery, Option<EstimateReportDTO>> for GetEstimateReport {
+    async fn execute(&self, query: GetEstimateReportQuery) -> Result<Option<EstimateReportDTO>> {
+        let estimate_service = &self.estimate_service;
+        let section_service = &self.section_service;
+
+        let Some(estimate) = estimate_service.get_estimate(query.estimate_id).await? else {
+            return Ok(None);
+        };
+
+        let sections = section_service
+            .list_sections_for_estimate(query.estimate_id)
+            .await?;
+        Ok(Some(EstimateReportDTO::new(estimate, sections)))
+    }
+}
@@ -0,0 +1,45 @@
+// use_case/get_section.rs
+
+use crate::result::*;
+
+use crate::metrics;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Query;
+use crate::dto::section_dto::SectionDTO;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+pub struct GetSectionQuery {
+    pub section_id: Uuid,
+}
+
+impl Query for GetSectionQuery {
+    type Output = Option<SectionDTO>;
+}
+
+pub struct GetSection {
+    service: ServiceHandle<Section>,
+}
+
+impl GetSection {
+    pub fn new(service: ServiceHandle<Section>) -> Self {
+        GetSection { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<GetSectionQuery, Option<SectionDTO>> for GetSection {
+    async fn execute(&self, query: GetSectionQuery) -> Result<Option<SectionDTO>> {
+        let service = &self.service;
+
+        let section = service.get_section(query.section_id).await?;
+        Ok(section.map(SectionDTO::from))
+    }
+}
@@ -0,0 +1,722 @@
+// use_case/import_estimate_from_csv.rs
+
+use crate::result::*;
+
+use crate::metrics;
+use crate::telemetry::{self, Level};
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::clock::Clock;
+use crate::dto::estimate_dto::EstimateDTO;
+use crate::dto::section_dto::SectionDTO;
+use crate::entity::estimate::Estimate;
+use crate::entity::limits::ValidationLimits;
+use crate::entity::line_item::LineItem;
+use crate::entity::section::Section;
+use crate::id_generator::IdGenerator;
+
+use crate::service::actor::ServiceHandle;
+use crate::service::generic_service::GenericService;
+use crate::use_case::traits::UseCase;
+
+// Header names of the columns to read. `code` and `name` are required; the others may be
+// `None` when the spreadsheet has no such column. Headers are matched case-insensitively.
+#[derive(Clone, Debug)]
+pub struct CsvColumnMapping {
+    pub code: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub parent_code: Option<String>,
+    pub quantity: Option<String>,
+    pub unit: Option<String>,
+    pub unit_cost: Option<String>,
+}
+
+impl Default for CsvColumnMapping {
+    fn default() -> Self {
+        CsvColumnMapping {
+            code: "code".to_string(),
+            name: "name".to_string(),
+            description: Some("description".to_string()),
+            parent_code: Some("parent_code".to_string()),
+            quantity: Some("quantity".to_string()),
+            unit: Some("unit".to_string()),
+            unit_cost: Some("unit_cost".to_string()),
+        }
+    }
+}
+
+pub enum ImportTarget {
+    Existing {
+        estimate_id: Uuid,
+    },
+    New {
+        name: String,
+        description: String,
+        location: String,
+    },
+}
+
+// Rows sharing a code describe the same section: the first one sets its name, description and
+// parent, and every row with a quantity adds a line item to it. Nothing is saved unless every
+// row is valid.
+pub struct ImportEstimateFromCsvCommand {
+    pub csv: String,
+    pub mapping: CsvColumnMapping,
+    pub target: ImportTarget,
+}
+
+impl Command for ImportEstimateFromCsvCommand {
+    type Output = ImportSummary;
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ImportSummary {
+    pub estimate_id: Uuid,
+    pub sections_created: usize,
+    pub line_items_created: usize,
+}
+
+// Row numbers follow the spreadsheet: the header is row 1, the first data row is row 2. A quoted
+// field spanning several lines is still one row.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RowError {
+    pub row: u64,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ImportErrorReport {
+    pub errors: Vec<RowError>,
+}
+
+impl fmt::Display for ImportErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} row error(s)", self.errors.len())?;
+        for error in &self.errors {
+            write!(f, "; row {}: {}", error.row, error.message)?;
+        }
+        Ok(())
+    }
+}
+
+pub struct ImportEstimateFromCsv {
+    estimate_service: ServiceHandle<Estimate>,
+    section_service: ServiceHandle<Section>,
+}
+
+impl ImportEstimateFromCsv {
+    pub fn new(
+        estimate_service: ServiceHandle<Estimate>,
+        section_service: ServiceHandle<Section>,
+    ) -> Self {
+        ImportEstimateFromCsv {
+            estimate_service,
+            section_service,
+        }
+    }
+}
+
+#[async_trait]
+impl UseCase<ImportEstimateFromCsvCommand, ImportSummary> for ImportEstimateFromCsv {
+    async fn execute(&self, command: ImportEstimateFromCsvCommand) -> Result<ImportSummary> {
+        let estimate_service = &self.estimate_service;
+        let section_service = &self.section_service;
+
+        let (estimate, existing, is_new) = match command.target {
+            ImportTarget::Existing { estimate_id } => {
+                match estimate_service.get_estimate(estimate_id).await? {
+                    Some(estimate) => {
+                        let existing = section_service
+                            .list_sections_for_estimate(estimate_id)
+                            .await?;
+                        (estimate, existing, false)
+                    }
+                    None => {
+                        return Err(Box::new(UseCaseError::NotFoundError {
+                            entity: "Estimate",
+                            id: estimate_id,
+                        }))
+                    }
+                }
+            }
+            ImportTarget::New {
+                name,
+                description,
+                location,
+            } => {
+                let mut estimate_dto = EstimateDTO::new_with(estimate_service.ids.as_ref());
+                estimate_dto.name = name;
+                estimate_dto.description = description;
+                estimate_dto.location = location;
+                let estimate = estimate_dto.into_entity(estimate_service.clock.as_ref());
+                Estimate::is_valid_name(&estimate.name, &estimate_service.limits)?;
+                Estimate::is_valid_description(&estimate.description, &estimate_service.limits)?;
+                (estimate, Vec::new(), true)
+            }
+        };
+
+        let rows = parse_rows(&command.csv, &command.mapping)?;
+        let sections = build_sections(
+            rows,
+            estimate.id,
+            &existing,
+            section_service.clock.as_ref(),
+            section_service.ids.as_ref(),
+            &section_service.limits,
+        )?;
+
+        if is_new {
+            estimate_service.add_estimate(estimate.clone()).await?;
+        }
+
+        let line_items_created = sections.iter().map(|s| s.line_items.len()).sum();
+        let sections_created = sections.len();
+        // `add_sections` removes its own partial work; a new estimate goes with it so a failed
+        // import leaves nothing behind.
+        if let Err(e) = section_service.add_sections(sections).await {
+            if is_new {
+                if let Err(cleanup) = estimate_service.delete_estimate(estimate.id).await {
+                    tracing::warn!(estimate_id = %estimate.id, error = %cleanup, "Could not remove a partially imported estimate");
+                }
+            }
+            return Err(e);
+        }
+
+        Ok(ImportSummary {
+            estimate_id: estimate.id,
+            sections_created,
+            line_items_created,
+        })
+    }
+}
+
+struct CsvRow {
+    row: u64,
+    code: String,
+    name: String,
+    description: String,
+    parent_code: String,
+    quantity: String,
+    unit: String,
+    unit_cost: String,
+}
+
+fn import_error(errors: Vec<RowError>) -> Box<dyn std::error::Error + Send + Sync> {
+    Box::new(UseCaseError::ImportError(ImportErrorReport { errors }))
+}
+
+fn parse_rows(csv: &str, mapping: &CsvColumnMapping) -> Result<Vec<CsvRow>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(csv.as_bytes());
+
+    let headers = reader.headers().map_err(|e| {
+        import_error(vec![RowError {
+            row: 1,
+            message: format!("Unreadable header: {}", e),
+        }])
+    })?;
+    // Every mapped column must be present; unmapped (`None`) columns are simply not read.
+    let mut errors = Vec::new();
+    let mut columns = Vec::new();
+    for column in [
+        Some(&mapping.code),
+        Some(&mapping.name),
+        mapping.description.as_ref(),
+        mapping.parent_code.as_ref(),
+        mapping.quantity.as_ref(),
+        mapping.unit.as_ref(),
+        mapping.unit_cost.as_ref(),
+    ] {
+        let index = column.and_then(|column| {
+            let index = headers
+                .iter()
+                .position(|header| header.eq_ignore_ascii_case(column));
+            if index.is_none() {
+                errors.push(RowError {
+                    row: 1,
+                    message: format!("Missing column '{}'", column),
+                });
+            }
+            index
+        });
+        columns.push(index);
+    }
+    if !errors.is_empty() {
+        return Err(import_error(errors));
+    }
+
+    let mut rows = Vec::new();
+    for (index, record) in reader.records().enumerate() {
+        // Fall back to counting when the reader cannot tell the record (header is row 1).
+        let fallback_row = index as u64 + 2;
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                errors.push(RowError {
+                    row: e.position().map_or(fallback_row, |p| p.record() + 1),
+                    message: format!("Unreadable row: {}", e),
+                });
+                continue;
+            }
+        };
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .unwrap_or("")
+                .to_string()
+        };
+        rows.push(CsvRow {
+            row: record.position().map_or(fallback_row, |p| p.record() + 1),
+            code: field(columns[0]),
+            name: field(columns[1]),
+            description: field(columns[2]),
+            parent_code: field(columns[3]),
+            quantity: field(columns[4]),
+            unit: field(columns[5]),
+            unit_cost: field(columns[6]),
+        });
+    }
+
+    if errors.is_empty() {
+        Ok(rows)
+    } else {
+        Err(import_error(errors))
+    }
+}
+
+// Turns the rows into new sections for `estimate_id`, in tree order, or reports every invalid
+// row. `existing` sections of the estimate can be used as parents but not redefined.
+fn build_sections(
+    rows: Vec<CsvRow>,
+    estimate_id: Uuid,
+    existing: &[Section],
+    clock: &dyn Clock,
+    ids: &dyn IdGenerator,
+    limits: &ValidationLimits,
+) -> Result<Vec<Section>> {
+    let mut errors = Vec::new();
+    let mut error = |row: u64, message: String| errors.push(RowError { row, message });
+
+    let existing_codes: HashMap<&str, &Section> = existing
+        .iter()
+        .map(|section| (section.code.as_str(), section))
+        .collect();
+    let mut sections: Vec<Section> = Vec::new();
+    let mut index_by_code: HashMap<String, usize> = HashMap::new();
+    let mut parent_codes: Vec<(u64, String)> = Vec::new();
+
+    for row in rows {
+        if row.code.is_empty() {
+            error(row.row, "Code is required".to_string());
+            continue;
+        }
+        if existing_codes.contains_key(row.code.as_str()) {
+            error(
+                row.row,
+                format!("Section '{}' already exists in the estimate", row.code),
+            );
+            continue;
+        }
+
+        let index = match index_by_code.get(&row.code) {
+            Some(&index) => {
+                if !row.parent_code.is_empty() && row.parent_code != parent_codes[index].1 {
+                    error(
+                        row.row,
+                        format!("Section '{}' is given two different parents", row.code),
+                    );
+                }
+                index
+            }
+            None => {
+                if let Err(e) = Section::is_valid_name(&row.name, limits) {
+                    error(row.row, e.to_string());
+                }
+                // A blank description means the section has none, as with sections added by hand.
+                if !row.description.is_empty() {
+                    if let Err(e) = Section::is_valid_description(&row.description, limits) {
+                        error(row.row, e.to_string());
+                    }
+                }
+                let mut section_dto = SectionDTO::new_with(row.name.clone(), row.code.clone(), ids);
+                section_dto.description = Some(row.description.clone());
+                section_dto.estimate_id = Some(estimate_id);
+                sections.push(section_dto.into_entity(clock));
+                parent_codes.push((row.row, row.parent_code.clone()));
+                index_by_code.insert(row.code.clone(), sections.len() - 1);
+                sections.len() - 1
+            }
+        };
+
+        if row.quantity.is_empty() && row.unit.is_empty() && row.unit_cost.is_empty() {
+            continue;
+        }
+        let quantity = parse_number(&row.quantity, "Quantity");
+        let unit_cost = parse_number(&row.unit_cost, "Unit cost");
+        let description = if row.description.is_empty() {
+            row.name.clone()
+        } else {
+            row.description.clone()
+        };
+        let unit = LineItem::is_valid_unit(&row.unit);
+        let checks = [
+            quantity.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+            unit_cost.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+            quantity.as_ref().map_or(Ok(()), |q| {
+                LineItem::is_valid_quantity(*q).map_err(|e| e.to_string())
+            }),
+            unit_cost.as_ref().map_or(Ok(()), |c| {
+                LineItem::is_valid_unit_cost(*c).map_err(|e| e.to_string())
+            }),
+            unit.as_ref().map(|_| ()).map_err(|e| e.to_string()),
+            LineItem::is_valid_description(&description).map_err(|e| e.to_string()),
+        ];
+        let mut valid = true;
+        for check in checks {
+            if let Err(message) = check {
+                error(row.row, message);
+                valid = false;
+            }
+        }
+        if let (true, Ok(quantity), Ok(unit), Ok(unit_cost)) = (valid, quantity, unit, unit_cost) {
+            sections[index].line_items.push(LineItem::new_with(
+                description,
+                quantity,
+                unit,
+                unit_cost,
+                clock,
+                ids,
+            ));
+        }
+    }
+
+    // Link parents now that every code in the file is known.
+    let ids_by_code: HashMap<&str, Uuid> = index_by_code
+        .iter()
+        .map(|(code, &index)| (code.as_str(), sections[index].id))
+        .collect();
+    let mut links = Vec::with_capacity(sections.len());
+    for (row, parent_code) in &parent_codes {
+        let parent = if parent_code.is_empty() {
+            None
+        } else if let Some(id) = ids_by_code.get(parent_code.as_str()) {
+            Some(*id)
+        } else if let Some(section) = existing_codes.get(parent_code.as_str()) {
+            Some(section.id)
+        } else {
+            error(*row, format!("Parent section '{}' not found", parent_code));
+            None
+        };
+        links.push(parent);
+    }
+    for (index, parent) in links.iter().enumerate() {
+        sections[index].parent_id = *parent;
+    }
+
+    // Reject cycles such as A -> B -> A, which would leave sections without a root.
+    let parent_of: HashMap<Uuid, Uuid> = sections
+        .iter()
+        .filter_map(|section| section.parent_id.map(|parent| (section.id, parent)))
+        .collect();
+    for (index, section) in sections.iter().enumerate() {
+        let mut seen = HashSet::from([section.id]);
+        let mut current = section.id;
+        while let Some(&parent) = parent_of.get(&current) {
+            if !seen.insert(parent) {
+                error(
+                    parent_codes[index].0,
+                    format!("Section '{}' is its own ancestor", section.code),
+                );
+                break;
+            }
+            current = parent;
+        }
+    }
+
+    // The estimate must stay within the section limit once the new sections are added.
+    if let Some((row, _)) = parent_codes.last() {
+        let all: Vec<Section> = existing.iter().chain(sections.iter()).cloned().collect();
+        if let Err(e) = Section::is_valid_sections(&all, limits) {
+            error(*row, e.to_string());
+        }
+    }
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|e| e.row);
+        return Err(import_error(errors));
+    }
+
+    // Append after the estimate's existing sections, keeping the file order among new ones.
+    let mut next_position: HashMap<Option<Uuid>, u32> = HashMap::new();
+    for section in existing {
+        let next = next_position.entry(section.parent_id).or_insert(0);
+        *next = (*next).max(section.position + 1);
+    }
+    for section in sections.iter_mut() {
+        let next = next_position.entry(section.parent_id).or_insert(0);
+        section.position = *next;
+        *next += 1;
+    }
+
+    Ok(GenericService::<Section>::tree_order(sections))
+}
+
+// Accepts amounts as spreadsheets export them: thousands separators, a dollar sign and a sign
+// on either side of it, e.g. "1,250.00", "-$5" or "$-5".
+fn parse_number(value: &str, field: &str) -> std::result::Result<f64, String> {
+    let not_a_number = || format!("{} '{}' is not a number", field, value);
+    let cleaned = value.trim().replace(',', "");
+    let (negative, unsigned) = match cleaned.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, cleaned.strip_prefix('+').unwrap_or(&cleaned)),
+    };
+    let amount = unsigned.strip_prefix('$').unwrap_or(unsigned);
+    if unsigned.len() != cleaned.len() && amount.starts_with(['-', '+']) {
+        return Err(not_a_number());
+    }
+    let number = amount.parse::<f64>().map_err(|_| not_a_number())?;
+    Ok(if negative { -number } else { number })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::fault_injecting_repo::{FaultInjectingRepository, Operation, Trigger};
+    use crate::repository::in_memory_repo::InMemoryRepository;
+
+    struct Fixture {
+        use_case: ImportEstimateFromCsv,
+        estimate_service: ServiceHandle<Estimate>,
+        section_service: ServiceHandle<Section>,
+    }
+
+    fn fixture() -> Fixture {
+        let estimate_repo = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let section_repo = Arc::new(Mutex::new(InMemoryRepository::<Section>::new()));
+        let estimate_service = GenericService::<Estimate>::new(estimate_repo)
+            .spawn()
+            .handle();
+        let section_service = GenericService::<Section>::new(section_repo)
+            .spawn()
+            .handle();
+        Fixture {
+            use_case: ImportEstimateFromCsv::new(estimate_service.clone(), section_service.clone()),
+            estimate_service,
+            section_service,
+        }
+    }
+
+    fn new_estimate() -> ImportTarget {
+        ImportTarget::New {
+            name: "Clinic Renovation".to_string(),
+            description: "Imported takeoff".to_string(),
+            location: "Denver".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_import_builds_section_tree_with_line_items() {
+        let fixture = fixture();
+        let csv = "\
+Code,Name,Description,Parent Code,Quantity,Unit,Unit Cost
+09,Finishes,,,,,
+09-200,Drywall,5/8 type X board,09,1200,SF,2.35
+09-200,Drywall,Metal studs,09,300,LF,\"1,200.00\"
+09-900,Painting,,09,1200,SF,0.85
+";
+
+        let summary = fixture
+            .use_case
+            .execute(ImportEstimateFromCsvCommand {
+                csv: csv.to_string(),
+                mapping: CsvColumnMapping {
+                    parent_code: Some("Parent Code".to_string()),
+                    unit_cost: Some("Unit Cost".to_string()),
+                    ..Default::default()
+                },
+                target: new_estimate(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(summary.sections_created, 3);
+        assert_eq!(summary.line_items_created, 3);
+
+        let sections = fixture
+            .section_service
+            .list_sections_for_estimate(summary.estimate_id)
+            .await
+            .unwrap();
+        let codes: Vec<&str> = sections.iter().map(|s| s.code.as_str()).collect();
+        assert_eq!(codes, vec!["09", "09-200", "09-900"]);
+        assert_eq!(sections[1].parent_id, Some(sections[0].id));
+        assert_eq!(sections[1].line_items.len(), 2);
+        assert_eq!(sections[1].line_items[1].unit_cost, 1200.0);
+        assert_eq!(sections[2].position, 1);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_rows_are_reported_and_nothing_is_saved() {
+        let fixture = fixture();
+        let csv = "\
+code,name,description,parent_code,quantity,unit,unit_cost
+03,Concrete,,,,,
+03-300,Slab,,99,40,CY,abc
+,No code,,,,,
+05,Metals,Steel,,,,
+";
+
+        let err = fixture
+            .use_case
+            .execute(ImportEstimateFromCsvCommand {
+                csv: csv.to_string(),
+                mapping: CsvColumnMapping::default(),
+                target: new_estimate(),
+            })
+            .await
+            .unwrap_err();
+
+        let message = err.to_string();
+        assert!(
+            message.contains("row 3: Unit cost 'abc' is not a number"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("row 3: Parent section '99' not found"),
+            "{}",
+            message
+        );
+        assert!(message.contains("row 4: Code is required"), "{}", message);
+        assert!(
+            message.contains("row 5: Validation error in Section: Description must be between 10"),
+            "{}",
+            message
+        );
+        assert!(fixture
+            .estimate_service
+            .list_estimates()
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rows_are_numbered_by_record_not_line() {
+        let fixture = fixture();
+        let csv = "\
+code,name,description,parent_code,quantity,unit,unit_cost
+03,Concrete,\"Cast in place
+on grade\",,,,
+03-300,Slab,,03,40,CY,abc
+";
+
+        let err = fixture
+            .use_case
+            .execute(ImportEstimateFromCsvCommand {
+                csv: csv.to_string(),
+                mapping: CsvColumnMapping::default(),
+                target: new_estimate(),
+            })
+            .await
+            .unwrap_err();
+
+        let message = err.to_string();
+        assert!(
+            message.contains("row 3: Unit cost 'abc' is not a number"),
+            "{}",
+            message
+        );
+    }
+
+    #[test]
+    fn test_parse_number_accepts_separators_and_signs() {
+        assert_eq!(parse_number("1,250.00", "Unit cost"), Ok(1250.0));
+        assert_eq!(parse_number("$1,250.00", "Unit cost"), Ok(1250.0));
+        assert_eq!(parse_number("-$5", "Unit cost"), Ok(-5.0));
+        assert_eq!(parse_number("$-5", "Unit cost"), Ok(-5.0));
+        assert_eq!(parse_number("+12.5", "Quantity"), Ok(12.5));
+        assert_eq!(
+            parse_number("--5", "Unit cost"),
+            Err("Unit cost '--5' is not a number".to_string())
+        );
+        assert!(parse_number("-$-5", "Unit cost").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_failed_save_leaves_nothing_behind() {
+        let estimate_repo = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let section_repo = FaultInjectingRepository::new(InMemoryRepository::<Section>::new());
+        let faults = section_repo.handle();
+        let estimate_service = GenericService::<Estimate>::new(estimate_repo)
+            .spawn()
+            .handle();
+        let section_service = GenericService::<Section>::new(Arc::new(Mutex::new(section_repo)))
+            .spawn()
+            .handle();
+        let use_case =
+            ImportEstimateFromCsv::new(estimate_service.clone(), section_service.clone());
+
+        // The third section fails to save after the first two were stored.
+        faults.fail(Operation::Add, Trigger::Nth(3));
+        let err = use_case
+            .execute(ImportEstimateFromCsvCommand {
+                csv: "code,name\n01,General\n02,Sitework\n03,Concrete\n".to_string(),
+                mapping: CsvColumnMapping {
+                    description: None,
+                    parent_code: None,
+                    quantity: None,
+                    unit: None,
+                    unit_cost: None,
+                    ..Default::default()
+                },
+                target: new_estimate(),
+            })
+            .await
+            .unwrap_err();
+
+        assert!(
+            err.to_string().ends_with("Injected failure in add"),
+            "{}",
+            err
+        );
+        assert_eq!(faults.calls_to(Operation::Delete).len(), 2);
+        assert!(estimate_service.list_estimates().await.unwrap().is_empty());
+        assert!(section_service.list_sections().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_missing_mapped_column_is_reported_on_header_row() {
+        let fixture = fixture();
+
+        let err = fixture
+            .use_case
+            .execute(ImportEstimateFromCsvCommand {
+                csv: "code,name\n01,General\n".to_string(),
+                mapping: CsvColumnMapping::default(),
+                target: new_estimate(),
+            })
+            .await
+            .unwrap_err();
+
+        assert!(err
+            .to_string()
+            .contains("row 1: Missing column 'description'"));
+    }
+}
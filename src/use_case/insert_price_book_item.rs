@@ -0,0 +1,106 @@
+// use_case/insert_price_book_item.rs
+
+use crate::result::*;
+
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::entity::line_item::{LineItem, PriceReference};
+use crate::entity::price_book_item::PriceBookItem;
+use crate::entity::section::Section;
+use crate::entity::unit::Unit;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+// Adds the version of `code` effective on `date` to the end of a section's line items, priced
+// per `unit` when given (any unit of the same dimension) or else per the book's unit. The line
+// item keeps a reference to the book so `RepriceEstimate` can update it.
+pub struct InsertPriceBookItemCommand {
+    pub section_id: Uuid,
+    pub code: String,
+    pub quantity: f64,
+    pub unit: Option<Unit>,
+    pub date: NaiveDate,
+}
+
+impl Command for InsertPriceBookItemCommand {
+    type Output = Uuid;
+}
+
+pub struct InsertPriceBookItem {
+    price_book_service: ServiceHandle<PriceBookItem>,
+    section_service: ServiceHandle<Section>,
+}
+
+impl InsertPriceBookItem {
+    pub fn new(
+        price_book_service: ServiceHandle<PriceBookItem>,
+        section_service: ServiceHandle<Section>,
+    ) -> Self {
+        InsertPriceBookItem {
+            price_book_service,
+            section_service,
+        }
+    }
+}
+
+#[async_trait]
+impl UseCase<InsertPriceBookItemCommand, Uuid> for InsertPriceBookItem {
+    async fn execute(&self, command: InsertPriceBookItemCommand) -> Result<Uuid> {
+        LineItem::is_valid_quantity(command.quantity)?;
+        let item = match self
+            .price_book_service
+            .find_effective(command.code.clone(), command.date)
+            .await?
+        {
+            Some(item) => item,
+            None => {
+                return Err(Box::new(UseCaseError::PriceNotFound {
+                    code: command.code,
+                    date: command.date,
+                }))
+            }
+        };
+        let unit = command.unit.unwrap_or(item.unit);
+        let unit_cost = item.unit_cost_in(unit)?;
+
+        // Reading and writing the section run as one command, so concurrent changes to it are
+        // not lost
+        self.section_service
+            .run(move |section_service| {
+                Box::pin(async move {
+                    let Some(mut section) = section_service.get_section(command.section_id).await?
+                    else {
+                        return Err(Box::new(UseCaseError::NotFoundError {
+                            entity: "Section",
+                            id: command.section_id,
+                        })
+                            as Box<dyn std::error::Error + Send + Sync>);
+                    };
+                    let mut line_item = LineItem::new_with(
+                        item.description.clone(),
+                        command.quantity,
+                        unit,
+                        unit_cost,
+                        section_service.clock.as_ref(),
+                        section_service.ids.as_ref(),
+                    );
+                    line_item.price_reference = Some(PriceReference {
+                        code: item.code.clone(),
+                        item_id: item.id,
+                    });
+                    let id = line_item.id;
+                    section.line_items.push(line_item);
+                    section.updated_at = section_service.now();
+                    section_service.update_section(section).await?;
+                    Ok(id)
+                })
+            })
+            .await
+    }
+}
@@ -0,0 +1,50 @@
+// use_case/list_estimates.rs
+
+use crate::result::*;
+
+use crate::metrics;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+use crate::bus::Query;
+use crate::dto::estimate_dto::EstimateDTO;
+use crate::entity::estimate::Estimate;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+pub struct ListEstimatesQuery;
+
+impl Query for ListEstimatesQuery {
+    type Output = Vec<EstimateDTO>;
+}
+
+pub struct ListEstimates {
+    service: ServiceHandle<Estimate>,
+}
+
+impl ListEstimates {
+    pub fn new(service: ServiceHandle<Estimate>) -> Self {
+        ListEstimates { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<ListEstimatesQuery, Vec<EstimateDTO>> for ListEstimates {
+    async fn execute(&self, _query: ListEstimatesQuery) -> Result<Vec<EstimateDTO>> {
+        let service = &self.service;
+
+        let mut estimates = service.list_estimates().await?;
+
+        // Repositories make no ordering promise, so list oldest first for stable output.
+        estimates.sort_by(|a, b| {
+            a.created_at
+                .cmp(&b.created_at)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        Ok(estimates.into_iter().map(EstimateDTO::from).collect())
+    }
+}
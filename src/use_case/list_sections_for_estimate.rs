@@ -0,0 +1,48 @@
+// use_case/list_sections_for_estimate.rs
+
+use crate::result::*;
+
+use crate::metrics;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Query;
+use crate::dto::section_dto::SectionDTO;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+// Sections are returned in tree order: parents before children, siblings by position.
+pub struct ListSectionsForEstimateQuery {
+    pub estimate_id: Uuid,
+}
+
+impl Query for ListSectionsForEstimateQuery {
+    type Output = Vec<SectionDTO>;
+}
+
+pub struct ListSectionsForEstimate {
+    service: ServiceHandle<Section>,
+}
+
+impl ListSectionsForEstimate {
+    pub fn new(service: ServiceHandle<Section>) -> Self {
+        ListSectionsForEstimate { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<ListSectionsForEstimateQuery, Vec<SectionDTO>> for ListSectionsForEstimate {
+    async fn execute(&self, query: ListSectionsForEstimateQuery) -> Result<Vec<SectionDTO>> {
+        let service = &self.service;
+
+        let sections = service
+            .list_sections_for_estimate(query.estimate_id)
+            .await?;
+        Ok(sections.into_iter().map(SectionDTO::from).collect())
+    }
+}
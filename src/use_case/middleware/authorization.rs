@@ -0,0 +1,56 @@
+// use_case/middleware/authorization.rs
+
+use crate::result::*;
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+
+use crate::use_case::traits::UseCase;
+
+// Decides whether a use case may run for the given input.
+pub trait Authorizer<Input>: Send + Sync {
+    fn authorize(&self, input: &Input) -> bool;
+}
+
+impl<F, Input> Authorizer<Input> for F
+where
+    F: Fn(&Input) -> bool + Send + Sync,
+{
+    fn authorize(&self, input: &Input) -> bool {
+        self(input)
+    }
+}
+
+pub struct Authorization<U, A> {
+    name: &'static str,
+    inner: U,
+    authorizer: A,
+}
+
+impl<U, A> Authorization<U, A> {
+    pub fn new(name: &'static str, inner: U, authorizer: A) -> Self {
+        Authorization {
+            name,
+            inner,
+            authorizer,
+        }
+    }
+}
+
+#[async_trait]
+impl<U, A, Input, Output> UseCase<Input, Output> for Authorization<U, A>
+where
+    U: UseCase<Input, Output>,
+    A: Authorizer<Input>,
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    async fn execute(&self, input: Input) -> Result<Output> {
+        if !self.authorizer.authorize(&input) {
+            return Err(Box::new(UseCaseError::AuthorizationError {
+                use_case: self.name,
+            }));
+        }
+        self.inner.execute(input).await
+    }
+}
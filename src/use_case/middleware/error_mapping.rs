@@ -0,0 +1,32 @@
+// use_case/middleware/error_mapping.rs
+
+use crate::result::*;
+
+use async_trait::async_trait;
+
+use super::BoxedError;
+use crate::use_case::traits::UseCase;
+
+pub struct ErrorMapping<U, F> {
+    inner: U,
+    mapper: F,
+}
+
+impl<U, F> ErrorMapping<U, F> {
+    pub fn new(inner: U, mapper: F) -> Self {
+        ErrorMapping { inner, mapper }
+    }
+}
+
+#[async_trait]
+impl<U, F, Input, Output> UseCase<Input, Output> for ErrorMapping<U, F>
+where
+    U: UseCase<Input, Output>,
+    F: Fn(BoxedError) -> BoxedError + Send + Sync,
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    async fn execute(&self, input: Input) -> Result<Output> {
+        self.inner.execute(input).await.map_err(&self.mapper)
+    }
+}
@@ -0,0 +1,37 @@
+// use_case/middleware/logging.rs
+
+use crate::result::*;
+
+use async_trait::async_trait;
+
+use crate::telemetry::{self, Span};
+use crate::use_case::traits::UseCase;
+
+pub struct Logging<U> {
+    name: &'static str,
+    inner: U,
+}
+
+impl<U> Logging<U> {
+    pub fn new(name: &'static str, inner: U) -> Self {
+        Logging { name, inner }
+    }
+}
+
+#[async_trait]
+impl<U, Input, Output> UseCase<Input, Output> for Logging<U>
+where
+    U: UseCase<Input, Output>,
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    async fn execute(&self, input: Input) -> Result<Output> {
+        telemetry::in_request(async {
+            tracing::debug!("Executing use case: {}", self.name);
+            Span::new("use_case", self.name)
+                .instrument(self.inner.execute(input))
+                .await
+        })
+        .await
+    }
+}
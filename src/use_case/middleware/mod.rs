@@ -0,0 +1,161 @@
+// use_case/middleware/mod.rs
+
+pub mod authorization;
+pub mod error_mapping;
+pub mod logging;
+pub mod timing;
+pub mod validation;
+
+use crate::result::*;
+
+use super::traits::UseCase;
+
+use authorization::{Authorization, Authorizer};
+use error_mapping::ErrorMapping;
+use logging::Logging;
+use timing::Timing;
+use validation::{Validation, Validator};
+
+// Chains middleware around any use case, e.g.
+// `CreateEstimate::new(service).with_validation("create_estimate", rule).with_logging("create_estimate")`.
+// Layers run outermost first, so the last one added sees the call before the others.
+pub trait UseCaseExt<Input, Output>: UseCase<Input, Output> + Sized
+where
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    fn with_logging(self, name: &'static str) -> Logging<Self> {
+        Logging::new(name, self)
+    }
+
+    fn with_timing(self, name: &'static str) -> Timing<Self> {
+        Timing::new(name, self)
+    }
+
+    fn with_authorization<A>(self, name: &'static str, authorizer: A) -> Authorization<Self, A>
+    where
+        A: Authorizer<Input>,
+    {
+        Authorization::new(name, self, authorizer)
+    }
+
+    fn with_validation<V>(self, name: &'static str, validator: V) -> Validation<Self, V>
+    where
+        V: Validator<Input>,
+    {
+        Validation::new(name, self, validator)
+    }
+
+    fn with_error_mapping<F>(self, mapper: F) -> ErrorMapping<Self, F>
+    where
+        F: Fn(BoxedError) -> BoxedError + Send + Sync,
+    {
+        ErrorMapping::new(self, mapper)
+    }
+}
+
+impl<U, Input, Output> UseCaseExt<Input, Output> for U
+where
+    U: UseCase<Input, Output>,
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+}
+
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::use_case::error::Error as UseCaseError;
+
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    struct Double {
+        calls: Arc<AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl UseCase<i32, i32> for Double {
+        async fn execute(&self, input: i32) -> Result<i32> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if input < 0 {
+                return Err(Box::new(UseCaseError::BasicCaseError {
+                    message: "negative input".to_string(),
+                }));
+            }
+            Ok(input * 2)
+        }
+    }
+
+    fn double() -> (Double, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        (
+            Double {
+                calls: Arc::clone(&calls),
+            },
+            calls,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_passes_through_on_success() {
+        let (use_case, calls) = double();
+        let pipeline = use_case
+            .with_validation("double", |input: &i32| -> Result<()> {
+                if *input > 100 {
+                    return Err("too large".into());
+                }
+                Ok(())
+            })
+            .with_authorization("double", |_: &i32| true)
+            .with_timing("double")
+            .with_logging("double");
+
+        assert_eq!(pipeline.execute(21).await.unwrap(), 42);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_validation_short_circuits() {
+        let (use_case, calls) = double();
+        let pipeline = use_case.with_validation("double", |input: &i32| -> Result<()> {
+            if *input > 100 {
+                return Err("too large".into());
+            }
+            Ok(())
+        });
+
+        let err = pipeline.execute(101).await.unwrap_err();
+        assert_eq!(err.to_string(), "Validation failed for double: too large");
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_authorization_short_circuits() {
+        let (use_case, calls) = double();
+        let pipeline = use_case.with_authorization("double", |_: &i32| false);
+
+        let err = pipeline.execute(1).await.unwrap_err();
+        assert_eq!(err.to_string(), "Not authorized to execute double");
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_error_mapping_rewrites_errors() {
+        let (use_case, _) = double();
+        let pipeline = use_case.with_error_mapping(|err| {
+            Box::new(UseCaseError::BasicCaseError {
+                message: format!("mapped: {}", err),
+            })
+        });
+
+        let err = pipeline.execute(-1).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "UseCaseInnerError: mapped: UseCaseInnerError: negative input"
+        );
+    }
+}
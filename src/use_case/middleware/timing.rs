@@ -0,0 +1,42 @@
+// use_case/middleware/timing.rs
+
+use crate::result::*;
+
+use async_trait::async_trait;
+use std::time::Instant;
+
+use crate::telemetry::{self, Level};
+use crate::use_case::traits::UseCase;
+
+pub struct Timing<U> {
+    name: &'static str,
+    inner: U,
+}
+
+impl<U> Timing<U> {
+    pub fn new(name: &'static str, inner: U) -> Self {
+        Timing { name, inner }
+    }
+}
+
+#[async_trait]
+impl<U, Input, Output> UseCase<Input, Output> for Timing<U>
+where
+    U: UseCase<Input, Output>,
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    async fn execute(&self, input: Input) -> Result<Output> {
+        let started = Instant::now();
+        let result = self.inner.execute(input).await;
+        let elapsed = started.elapsed();
+        tracing::debug!(
+            operation = self.name,
+            duration_us = elapsed.as_micros() as u64,
+            "{} took {:?}",
+            self.name,
+            elapsed
+        );
+        result
+    }
+}
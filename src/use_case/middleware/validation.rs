@@ -0,0 +1,59 @@
+// use_case/middleware/validation.rs
+
+use crate::result::*;
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+
+use crate::metrics;
+use crate::use_case::traits::UseCase;
+
+// Checks the input of a use case before it runs, usually with the entity validation rules.
+pub trait Validator<Input>: Send + Sync {
+    fn validate(&self, input: &Input) -> Result<()>;
+}
+
+impl<F, Input> Validator<Input> for F
+where
+    F: Fn(&Input) -> Result<()> + Send + Sync,
+{
+    fn validate(&self, input: &Input) -> Result<()> {
+        self(input)
+    }
+}
+
+pub struct Validation<U, V> {
+    name: &'static str,
+    inner: U,
+    validator: V,
+}
+
+impl<U, V> Validation<U, V> {
+    pub fn new(name: &'static str, inner: U, validator: V) -> Self {
+        Validation {
+            name,
+            inner,
+            validator,
+        }
+    }
+}
+
+#[async_trait]
+impl<U, V, Input, Output> UseCase<Input, Output> for Validation<U, V>
+where
+    U: UseCase<Input, Output>,
+    V: Validator<Input>,
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    async fn execute(&self, input: Input) -> Result<Output> {
+        if let Err(e) = self.validator.validate(&input) {
+            metrics::record_validation_failure(self.name, "validator");
+            return Err(Box::new(UseCaseError::ValidationError {
+                use_case: self.name,
+                message: e.to_string(),
+            }));
+        }
+        self.inner.execute(input).await
+    }
+}
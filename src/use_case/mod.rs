@@ -1,10 +1,31 @@
 //use_case/mod.rs
 
 pub mod error;
+pub mod middleware;
+pub mod traits;
 
 //-----------------Estimate Use Cases-----------------
 pub mod create_estimate;
+pub mod delete_estimate;
+pub mod duplicate_estimate;
+pub mod get_estimate;
+pub mod get_estimate_report;
+pub mod import_estimate_from_csv;
+pub mod list_estimates;
 pub mod set_estimate_location;
+pub mod update_estimate;
 
 //-----------------Section Use Cases-----------------
 pub mod create_section_add_to_estimate;
+pub mod delete_section;
+pub mod get_section;
+pub mod list_sections_for_estimate;
+pub mod move_section;
+pub mod reorder_sections;
+pub mod update_section;
+
+//-----------------Price Book Use Cases-----------------
+pub mod add_price_book_item;
+pub mod insert_price_book_item;
+pub mod reprice_estimate;
+pub mod search_price_book;
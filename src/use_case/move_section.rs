@@ -0,0 +1,305 @@
+// use_case/move_section.rs
+
+use crate::result::*;
+
+use crate::metrics;
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::dto::section_dto::SectionDTO;
+use crate::entity::estimate::Estimate;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::service::generic_service::GenericService;
+use crate::use_case::traits::UseCase;
+
+// Moves a section, with all of its sub-sections, under `parent_id` (or to the top level when
+// `None`) of `estimate_id` (or its current estimate when `None`). Without a `position` the
+// section is appended after its new siblings.
+pub struct MoveSectionCommand {
+    pub section_id: Uuid,
+    pub estimate_id: Option<Uuid>,
+    pub parent_id: Option<Uuid>,
+    pub position: Option<u32>,
+}
+
+impl Command for MoveSectionCommand {
+    type Output = SectionDTO;
+}
+
+pub struct MoveSection {
+    section_service: ServiceHandle<Section>,
+    estimate_service: ServiceHandle<Estimate>,
+}
+
+impl MoveSection {
+    pub fn new(
+        section_service: ServiceHandle<Section>,
+        estimate_service: ServiceHandle<Estimate>,
+    ) -> Self {
+        MoveSection {
+            section_service,
+            estimate_service,
+        }
+    }
+}
+
+#[async_trait]
+impl UseCase<MoveSectionCommand, SectionDTO> for MoveSection {
+    async fn execute(&self, command: MoveSectionCommand) -> Result<SectionDTO> {
+        let estimate_service = self.estimate_service.clone();
+        self.section_service
+            .run(move |section_service| {
+                Box::pin(move_section(section_service, estimate_service, command))
+            })
+            .await
+    }
+}
+
+// Runs as one command on the section service's actor so the old and new siblings are
+// renumbered without other moves in between. It asks the estimate service's actor whether the
+// target estimate exists; the estimate service never waits on sections, so this cannot deadlock.
+async fn move_section(
+    section_service: &GenericService<Section>,
+    estimate_service: ServiceHandle<Estimate>,
+    command: MoveSectionCommand,
+) -> Result<SectionDTO> {
+    let mut section = match section_service.get_section(command.section_id).await? {
+        Some(section) => section,
+        None => {
+            return Err(Box::new(UseCaseError::NotFoundError {
+                entity: "Section",
+                id: command.section_id,
+            }))
+        }
+    };
+    let old_estimate_id = section.estimate_id;
+    let old_parent_id = section.parent_id;
+
+    let Some(estimate_id) = command.estimate_id.or(section.estimate_id) else {
+        return Err(Box::new(UseCaseError::BasicCaseError {
+            message: "Section is not attached to an estimate".to_string(),
+        }));
+    };
+    if estimate_service.get_estimate(estimate_id).await?.is_none() {
+        return Err(Box::new(UseCaseError::NotFoundError {
+            entity: "Estimate",
+            id: estimate_id,
+        }));
+    }
+
+    let descendants = section_service.list_descendants(section.id).await?;
+    if let Some(parent_id) = command.parent_id {
+        if parent_id == section.id || descendants.iter().any(|d| d.id == parent_id) {
+            metrics::record_validation_failure("move_section", "no_cycles");
+            return Err(Box::new(UseCaseError::ValidationError {
+                use_case: "move_section",
+                message: "A section cannot be moved under itself".to_string(),
+            }));
+        }
+        match section_service.get_section(parent_id).await? {
+            Some(parent) if parent.estimate_id == Some(estimate_id) => {}
+            Some(_) => {
+                metrics::record_validation_failure("move_section", "same_estimate");
+                return Err(Box::new(UseCaseError::ValidationError {
+                    use_case: "move_section",
+                    message: "The new parent belongs to another estimate".to_string(),
+                }));
+            }
+            None => {
+                return Err(Box::new(UseCaseError::NotFoundError {
+                    entity: "Section",
+                    id: parent_id,
+                }))
+            }
+        }
+    }
+
+    // Insert among the new siblings and renumber them, the section included.
+    let mut siblings: Vec<Section> = section_service
+        .list_siblings(estimate_id, command.parent_id)
+        .await?
+        .into_iter()
+        .filter(|sibling| sibling.id != section.id)
+        .collect();
+    let index = command.position.map_or(siblings.len(), |position| {
+        (position as usize).min(siblings.len())
+    });
+    section.estimate_id = Some(estimate_id);
+    section.parent_id = command.parent_id;
+    section.position = index as u32;
+    section.updated_at = section_service.now();
+    section_service.update_section(section.clone()).await?;
+    siblings.insert(index, section.clone());
+    section_service.renumber_siblings(siblings).await?;
+
+    // Close the gap the section left behind.
+    if let Some(old_estimate_id) = old_estimate_id {
+        if old_estimate_id != estimate_id || old_parent_id != command.parent_id {
+            let old_siblings = section_service
+                .list_siblings(old_estimate_id, old_parent_id)
+                .await?;
+            section_service.renumber_siblings(old_siblings).await?;
+        }
+    }
+
+    if old_estimate_id != Some(estimate_id) {
+        for mut descendant in descendants {
+            descendant.estimate_id = Some(estimate_id);
+            descendant.updated_at = section_service.now();
+            section_service.update_section(descendant).await?;
+        }
+    }
+
+    Ok(section.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::in_memory_repo::InMemoryRepository;
+    use crate::service::generic_service::Service;
+
+    struct Fixture {
+        use_case: MoveSection,
+        section_service: ServiceHandle<Section>,
+        estimate_service: ServiceHandle<Estimate>,
+    }
+
+    fn fixture() -> Fixture {
+        let estimate_repo = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let section_repo = Arc::new(Mutex::new(InMemoryRepository::<Section>::new()));
+        let estimate_service = GenericService::<Estimate>::new(estimate_repo)
+            .spawn()
+            .handle();
+        let section_service = GenericService::<Section>::new(section_repo)
+            .spawn()
+            .handle();
+        Fixture {
+            use_case: MoveSection::new(section_service.clone(), estimate_service.clone()),
+            section_service,
+            estimate_service,
+        }
+    }
+
+    async fn add_estimate(fixture: &Fixture) -> Uuid {
+        let mut estimate = crate::dto::estimate_dto::EstimateDTO::new();
+        estimate.name = "Office Fit-out".to_string();
+        estimate.description = "Second floor offices".to_string();
+        let estimate = Estimate::from(estimate);
+        let id = estimate.id;
+        fixture.estimate_service.add(estimate).await.unwrap();
+        id
+    }
+
+    async fn add_section(
+        fixture: &Fixture,
+        estimate_id: Uuid,
+        parent_id: Option<Uuid>,
+        code: &str,
+        position: u32,
+    ) -> Uuid {
+        let mut section = SectionDTO::new(format!("Section {}", code), code.to_string());
+        section.estimate_id = Some(estimate_id);
+        section.parent_id = parent_id;
+        section.position = position;
+        let section = Section::from(section);
+        let id = section.id;
+        fixture.section_service.add(section).await.unwrap();
+        id
+    }
+
+    async fn codes(fixture: &Fixture, estimate_id: Uuid) -> Vec<(String, u32)> {
+        fixture
+            .section_service
+            .list_sections_for_estimate(estimate_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|section| (section.code, section.position))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_move_under_another_parent_renumbers_both_levels() {
+        let fixture = fixture();
+        let estimate_id = add_estimate(&fixture).await;
+        let a = add_section(&fixture, estimate_id, None, "A", 0).await;
+        let b = add_section(&fixture, estimate_id, None, "B", 1).await;
+        add_section(&fixture, estimate_id, None, "C", 2).await;
+        add_section(&fixture, estimate_id, Some(a), "A1", 0).await;
+
+        fixture
+            .use_case
+            .execute(MoveSectionCommand {
+                section_id: b,
+                estimate_id: None,
+                parent_id: Some(a),
+                position: Some(0),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            codes(&fixture, estimate_id).await,
+            vec![
+                ("A".to_string(), 0),
+                ("B".to_string(), 0),
+                ("A1".to_string(), 1),
+                ("C".to_string(), 1),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_move_to_another_estimate_takes_sub_sections_along() {
+        let fixture = fixture();
+        let source = add_estimate(&fixture).await;
+        let target = add_estimate(&fixture).await;
+        let a = add_section(&fixture, source, None, "A", 0).await;
+        add_section(&fixture, source, Some(a), "A1", 0).await;
+
+        fixture
+            .use_case
+            .execute(MoveSectionCommand {
+                section_id: a,
+                estimate_id: Some(target),
+                parent_id: None,
+                position: None,
+            })
+            .await
+            .unwrap();
+
+        assert!(codes(&fixture, source).await.is_empty());
+        assert_eq!(
+            codes(&fixture, target).await,
+            vec![("A".to_string(), 0), ("A1".to_string(), 0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_move_under_own_descendant_fails() {
+        let fixture = fixture();
+        let estimate_id = add_estimate(&fixture).await;
+        let a = add_section(&fixture, estimate_id, None, "A", 0).await;
+        let a1 = add_section(&fixture, estimate_id, Some(a), "A1", 0).await;
+
+        let result = fixture
+            .use_case
+            .execute(MoveSectionCommand {
+                section_id: a,
+                estimate_id: None,
+                parent_id: Some(a1),
+                position: None,
+            })
+            .await;
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,71 @@
+// use_case/reorder_sections.rs
+
+use crate::result::*;
+
+use crate::metrics;
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::service::generic_service::GenericService;
+use crate::use_case::traits::UseCase;
+
+// `section_ids` must list every child of `parent_id` (or every top-level section when `None`)
+// exactly once, in the new order.
+pub struct ReorderSectionsCommand {
+    pub estimate_id: Uuid,
+    pub parent_id: Option<Uuid>,
+    pub section_ids: Vec<Uuid>,
+}
+
+impl Command for ReorderSectionsCommand {
+    type Output = ();
+}
+
+pub struct ReorderSections {
+    service: ServiceHandle<Section>,
+}
+
+impl ReorderSections {
+    pub fn new(service: ServiceHandle<Section>) -> Self {
+        ReorderSections { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<ReorderSectionsCommand, ()> for ReorderSections {
+    async fn execute(&self, command: ReorderSectionsCommand) -> Result<()> {
+        self.service
+            .run(move |service| Box::pin(reorder(service, command)))
+            .await
+    }
+}
+
+// Runs as one command on the section service's actor, so the siblings cannot change between
+// checking and renumbering them.
+async fn reorder(service: &GenericService<Section>, command: ReorderSectionsCommand) -> Result<()> {
+    let mut siblings = service
+        .list_siblings(command.estimate_id, command.parent_id)
+        .await?;
+
+    let current: HashSet<Uuid> = siblings.iter().map(|section| section.id).collect();
+    let requested: HashSet<Uuid> = command.section_ids.iter().copied().collect();
+    if current != requested || requested.len() != command.section_ids.len() {
+        metrics::record_validation_failure("reorder_sections", "every_sibling_once");
+        return Err(Box::new(UseCaseError::ValidationError {
+            use_case: "reorder_sections",
+            message: "Section ids must list every sibling exactly once".to_string(),
+        }));
+    }
+
+    siblings.sort_by_key(|section| command.section_ids.iter().position(|id| *id == section.id));
+    service.renumber_siblings(siblings).await
+}
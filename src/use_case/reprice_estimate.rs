@@ -0,0 +1,310 @@
+// use_case/reprice_estimate.rs
+
+use crate::result::*;
+
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::entity::estimate::Estimate;
+use crate::entity::price_book_item::PriceBookItem;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+// Sets the unit cost of every line item that came from the price book to the price of its code
+// effective on `date`, converted to the line item's unit. Typed-in line items are left alone.
+pub struct RepriceEstimateCommand {
+    pub estimate_id: Uuid,
+    pub date: NaiveDate,
+}
+
+impl Command for RepriceEstimateCommand {
+    type Output = RepriceSummary;
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct RepriceSummary {
+    // Line items whose cost changed.
+    pub repriced: usize,
+    pub unchanged: usize,
+    // Codes with no price on the date, or only one in a unit the line item cannot convert to.
+    // Their line items keep their cost.
+    pub missing: Vec<String>,
+}
+
+pub struct RepriceEstimate {
+    estimate_service: ServiceHandle<Estimate>,
+    section_service: ServiceHandle<Section>,
+    price_book_service: ServiceHandle<PriceBookItem>,
+}
+
+impl RepriceEstimate {
+    pub fn new(
+        estimate_service: ServiceHandle<Estimate>,
+        section_service: ServiceHandle<Section>,
+        price_book_service: ServiceHandle<PriceBookItem>,
+    ) -> Self {
+        RepriceEstimate {
+            estimate_service,
+            section_service,
+            price_book_service,
+        }
+    }
+}
+
+#[async_trait]
+impl UseCase<RepriceEstimateCommand, RepriceSummary> for RepriceEstimate {
+    async fn execute(&self, command: RepriceEstimateCommand) -> Result<RepriceSummary> {
+        match self
+            .estimate_service
+            .get_estimate(command.estimate_id)
+            .await?
+        {
+            Some(_) => {}
+            None => {
+                return Err(Box::new(UseCaseError::NotFoundError {
+                    entity: "Estimate",
+                    id: command.estimate_id,
+                }))
+            }
+        }
+        let prices: Vec<PriceBookItem> = self
+            .price_book_service
+            .list_price_book_items()
+            .await?
+            .into_iter()
+            .filter(|item| item.is_effective_on(command.date))
+            .collect();
+
+        // Every section is read and written in one command, so edits made meanwhile are not lost
+        self.section_service
+            .run(move |section_service| {
+                Box::pin(async move {
+                    let mut summary = RepriceSummary::default();
+                    let mut missing = BTreeSet::new();
+                    let now = section_service.now();
+                    for mut section in section_service
+                        .list_sections_for_estimate(command.estimate_id)
+                        .await?
+                    {
+                        let mut changed = false;
+                        for line_item in &mut section.line_items {
+                            let Some(reference) = &mut line_item.price_reference else {
+                                continue;
+                            };
+                            let price = prices
+                                .iter()
+                                .find(|item| item.code.eq_ignore_ascii_case(&reference.code))
+                                .and_then(|item| {
+                                    Some((item.id, item.unit_cost_in(line_item.unit).ok()?))
+                                });
+                            let Some((item_id, unit_cost)) = price else {
+                                missing.insert(reference.code.clone());
+                                continue;
+                            };
+                            if reference.item_id != item_id {
+                                reference.item_id = item_id;
+                                changed = true;
+                            }
+                            if (line_item.unit_cost - unit_cost).abs() < 1e-9 {
+                                summary.unchanged += 1;
+                                continue;
+                            }
+                            line_item.unit_cost = unit_cost;
+                            line_item.updated_at = now;
+                            summary.repriced += 1;
+                            changed = true;
+                        }
+                        if changed {
+                            section.updated_at = now;
+                            section_service.update_section(section).await?;
+                        }
+                    }
+                    summary.missing = missing.into_iter().collect();
+                    Ok(summary)
+                })
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dto::estimate_dto::EstimateDTO;
+    use crate::dto::section_dto::SectionDTO;
+    use crate::entity::line_item::LineItem;
+    use crate::entity::price_book_item::UnitCosts;
+    use crate::entity::unit::Unit;
+    use crate::repository::in_memory_repo::InMemoryRepository;
+    use crate::service::generic_service::GenericService;
+    use crate::use_case::error::Error as UseCaseError;
+    use crate::use_case::insert_price_book_item::{
+        InsertPriceBookItem, InsertPriceBookItemCommand,
+    };
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2024, month, day).unwrap()
+    }
+
+    fn carpet(material: f64, from: NaiveDate, to: Option<NaiveDate>) -> PriceBookItem {
+        let costs = UnitCosts {
+            material,
+            labor: 0.5,
+            equipment: 0.0,
+        };
+        let mut item = PriceBookItem::new(
+            "09-6813".to_string(),
+            "Carpet tile".to_string(),
+            Unit::SquareFoot,
+            costs,
+            from,
+        );
+        item.effective_to = to;
+        item
+    }
+
+    #[tokio::test]
+    async fn test_inserted_items_are_repriced_from_the_book() {
+        let estimate_service =
+            GenericService::<Estimate>::new(Arc::new(Mutex::new(InMemoryRepository::new())))
+                .spawn()
+                .handle();
+        let section_service =
+            GenericService::<Section>::new(Arc::new(Mutex::new(InMemoryRepository::new())))
+                .spawn()
+                .handle();
+        let price_book_service =
+            GenericService::<PriceBookItem>::new(Arc::new(Mutex::new(InMemoryRepository::new())))
+                .spawn()
+                .handle();
+
+        let first_half = carpet(2.5, date(1, 1), Some(date(6, 30)));
+        let second_half = carpet(3.0, date(7, 1), None);
+        price_book_service
+            .add_price_book_item(first_half.clone())
+            .await
+            .unwrap();
+        price_book_service
+            .add_price_book_item(second_half.clone())
+            .await
+            .unwrap();
+        // A third version would make the price on some days ambiguous.
+        assert!(price_book_service
+            .add_price_book_item(carpet(4.0, date(6, 1), None))
+            .await
+            .is_err());
+
+        let mut estimate = EstimateDTO::new();
+        estimate.name = "Office Fit-out".to_string();
+        estimate.description = "Second floor finishes".to_string();
+        let estimate = Estimate::from(estimate);
+        estimate_service
+            .add_estimate(estimate.clone())
+            .await
+            .unwrap();
+        let mut section = SectionDTO::new("Finishes".to_string(), "09".to_string());
+        section.estimate_id = Some(estimate.id);
+        let mut section = Section::from(section);
+        section.line_items.push(LineItem::new(
+            "Base".to_string(),
+            200.0,
+            Unit::LinearFoot,
+            1.75,
+        ));
+        section_service.add_section(section.clone()).await.unwrap();
+
+        let insert = InsertPriceBookItem::new(price_book_service.clone(), section_service.clone());
+        let line_item_id = insert
+            .execute(InsertPriceBookItemCommand {
+                section_id: section.id,
+                code: "09-6813".to_string(),
+                quantity: 100.0,
+                unit: Some(Unit::SquareYard),
+                date: date(3, 1),
+            })
+            .await
+            .unwrap();
+        let error = insert
+            .execute(InsertPriceBookItemCommand {
+                section_id: section.id,
+                code: "09-6813".to_string(),
+                quantity: 1.0,
+                unit: None,
+                date: date(1, 1) - chrono::Days::new(1),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<UseCaseError>(),
+            Some(UseCaseError::PriceNotFound { .. })
+        ));
+
+        let stored = section_service
+            .get_section(section.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let inserted = &stored.line_items[1];
+        assert_eq!(inserted.id, line_item_id);
+        assert_eq!(inserted.description, "Carpet tile");
+        assert_eq!(inserted.unit, Unit::SquareYard);
+        assert!((inserted.unit_cost - 27.0).abs() < 1e-9);
+        assert_eq!(
+            inserted.price_reference.as_ref().unwrap().item_id,
+            first_half.id
+        );
+
+        let reprice = RepriceEstimate::new(
+            estimate_service.clone(),
+            section_service.clone(),
+            price_book_service.clone(),
+        );
+        let summary = reprice
+            .execute(RepriceEstimateCommand {
+                estimate_id: estimate.id,
+                date: date(8, 1),
+            })
+            .await
+            .unwrap();
+        assert_eq!(
+            summary,
+            RepriceSummary {
+                repriced: 1,
+                unchanged: 0,
+                missing: vec![],
+            }
+        );
+        let stored = section_service
+            .get_section(section.id)
+            .await
+            .unwrap()
+            .unwrap();
+        let repriced = &stored.line_items[1];
+        assert!((repriced.unit_cost - 31.5).abs() < 1e-9);
+        assert_eq!(
+            repriced.price_reference.as_ref().unwrap().item_id,
+            second_half.id
+        );
+        // Typed-in costs are not the book's to change.
+        assert_eq!(stored.line_items[0].unit_cost, 1.75);
+
+        // Before the book has any price, the items keep theirs.
+        let summary = reprice
+            .execute(RepriceEstimateCommand {
+                estimate_id: estimate.id,
+                date: date(1, 1) - chrono::Days::new(1),
+            })
+            .await
+            .unwrap();
+        assert_eq!(summary.missing, vec!["09-6813".to_string()]);
+    }
+}
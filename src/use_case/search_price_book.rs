@@ -0,0 +1,40 @@
+// use_case/search_price_book.rs
+
+use crate::result::*;
+
+use async_trait::async_trait;
+
+use crate::bus::Query;
+use crate::dto::price_book_item_dto::PriceBookItemDTO;
+use crate::entity::price_book_item::{PriceBookItem, PriceBookSearch};
+
+use crate::service::actor::ServiceHandle;
+use crate::use_case::traits::UseCase;
+
+// Items ordered by code, then oldest version first. The default search returns everything.
+#[derive(Default)]
+pub struct SearchPriceBookQuery {
+    pub search: PriceBookSearch,
+}
+
+impl Query for SearchPriceBookQuery {
+    type Output = Vec<PriceBookItemDTO>;
+}
+
+pub struct SearchPriceBook {
+    service: ServiceHandle<PriceBookItem>,
+}
+
+impl SearchPriceBook {
+    pub fn new(service: ServiceHandle<PriceBookItem>) -> Self {
+        SearchPriceBook { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<SearchPriceBookQuery, Vec<PriceBookItemDTO>> for SearchPriceBook {
+    async fn execute(&self, query: SearchPriceBookQuery) -> Result<Vec<PriceBookItemDTO>> {
+        let items = self.service.search_price_book(query.search).await?;
+        Ok(items.into_iter().map(PriceBookItemDTO::from).collect())
+    }
+}
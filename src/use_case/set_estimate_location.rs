@@ -1,7 +1,10 @@
 //use_case/set_estimate_location.rs
 use crate::result::*;
+
+use crate::metrics;
 use crate::use_case::error::Error as UseCaseError;
 
+use async_trait::async_trait;
 use std::future::Future;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -9,39 +12,59 @@ use tokio::sync::Mutex;
 use uuid::Uuid;
 
 use crate::{
+    bus::Command,
     dto::estimate_dto::EstimateDTO,
     entity::estimate::Estimate,
+    service::actor::ServiceHandle,
     service::generic_service::{GenericService, Service},
+    use_case::traits::UseCase,
 };
 
+pub struct SetEstimateLocationCommand {
+    pub estimate_id: Uuid,
+    pub location: String,
+}
+
+impl Command for SetEstimateLocationCommand {
+    type Output = ();
+}
+
 pub struct SetEstimateLocation {
-    service: Arc<Mutex<GenericService<Estimate>>>,
+    service: ServiceHandle<Estimate>,
 }
 
 impl SetEstimateLocation {
-    pub fn new(service: Arc<Mutex<GenericService<Estimate>>>) -> Self {
+    pub fn new(service: ServiceHandle<Estimate>) -> Self {
         SetEstimateLocation { service }
     }
+}
+
+#[async_trait]
+impl UseCase<SetEstimateLocationCommand, ()> for SetEstimateLocation {
+    async fn execute(&self, command: SetEstimateLocationCommand) -> Result<()> {
+        Estimate::is_valid_location(&command.location, &self.service.limits)?;
+
+        self.service
+            .run(move |service| Box::pin(set_location(service, command)))
+            .await
+    }
+}
 
-    pub async fn execute(&mut self, estimate_id: Uuid, estimate_dto: EstimateDTO) -> Result<()> {
-        let mut service = self.service.lock().await;
-
-        match service.get(estimate_id).await {
-            Ok(Some(mut estimate)) => {
-                estimate.location = estimate_dto.location;
-                match service.update(estimate).await {
-                    Ok(_) => Ok(()),
-                    Err(e) => Err(Box::new(UseCaseError::BasicCaseError {
-                        message: format!("Error updating estimate: {}", e),
-                    })),
-                }
-            }
-            Ok(None) => Err(Box::new(UseCaseError::BasicCaseError {
-                message: "Estimate does not exist".to_string(),
-            })),
-            Err(e) => Err(Box::new(UseCaseError::BasicCaseError {
-                message: format!("Error getting estimate: {}", e),
-            })),
+// Runs as one command on the estimate service's actor, so the estimate cannot change between
+// reading and writing it.
+async fn set_location(
+    service: &GenericService<Estimate>,
+    command: SetEstimateLocationCommand,
+) -> Result<()> {
+    match service.get(command.estimate_id).await? {
+        Some(mut estimate) => {
+            estimate.location = command.location;
+            estimate.updated_at = service.now();
+            service.update(estimate).await
         }
+        None => Err(Box::new(UseCaseError::NotFoundError {
+            entity: "Estimate",
+            id: command.estimate_id,
+        })),
     }
 }
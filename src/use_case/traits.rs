@@ -0,0 +1,41 @@
+// use_case/traits.rs
+
+use crate::result::*;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait UseCase<Input, Output>: Send + Sync
+where
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    async fn execute(&self, input: Input) -> Result<Output>;
+}
+
+// Shared and boxed use cases (including trait objects) are use cases themselves,
+// so middleware and controllers can hold them without caring how they are owned.
+#[async_trait]
+impl<U, Input, Output> UseCase<Input, Output> for Arc<U>
+where
+    U: UseCase<Input, Output> + ?Sized,
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    async fn execute(&self, input: Input) -> Result<Output> {
+        (**self).execute(input).await
+    }
+}
+
+#[async_trait]
+impl<U, Input, Output> UseCase<Input, Output> for Box<U>
+where
+    U: UseCase<Input, Output> + ?Sized,
+    Input: Send + 'static,
+    Output: Send + 'static,
+{
+    async fn execute(&self, input: Input) -> Result<Output> {
+        (**self).execute(input).await
+    }
+}
@@ -0,0 +1,177 @@
+// use_case/update_estimate.rs
+
+use crate::result::*;
+
+use crate::metrics;
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::dto::estimate_dto::EstimateDTO;
+use crate::entity::estimate::Estimate;
+use crate::entity::markup::Markup;
+
+use crate::service::actor::ServiceHandle;
+use crate::service::generic_service::GenericService;
+use crate::use_case::traits::UseCase;
+
+// Only the fields that are `Some` are changed.
+#[derive(Default)]
+pub struct UpdateEstimateCommand {
+    pub estimate_id: Uuid,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub price_guess: Option<f64>,
+    pub location: Option<String>,
+    // Replaces the whole markup list when set.
+    pub markups: Option<Vec<Markup>>,
+    // Replaces the whole exclusion list when set.
+    pub exclusions: Option<Vec<String>>,
+}
+
+impl Command for UpdateEstimateCommand {
+    type Output = EstimateDTO;
+}
+
+pub struct UpdateEstimate {
+    service: ServiceHandle<Estimate>,
+}
+
+impl UpdateEstimate {
+    pub fn new(service: ServiceHandle<Estimate>) -> Self {
+        UpdateEstimate { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<UpdateEstimateCommand, EstimateDTO> for UpdateEstimate {
+    async fn execute(&self, command: UpdateEstimateCommand) -> Result<EstimateDTO> {
+        self.service
+            .run(move |service| Box::pin(update(service, command)))
+            .await
+    }
+}
+
+// Runs as one command on the estimate service's actor, so the estimate cannot change between
+// reading and writing it.
+async fn update(
+    service: &GenericService<Estimate>,
+    command: UpdateEstimateCommand,
+) -> Result<EstimateDTO> {
+    let mut estimate = match service.get_estimate(command.estimate_id).await? {
+        Some(estimate) => estimate,
+        None => {
+            return Err(Box::new(UseCaseError::NotFoundError {
+                entity: "Estimate",
+                id: command.estimate_id,
+            }))
+        }
+    };
+
+    if let Some(name) = command.name {
+        estimate.name = name;
+    }
+    if let Some(description) = command.description {
+        estimate.description = description;
+    }
+    if let Some(price_guess) = command.price_guess {
+        Estimate::is_valid_price_guess(price_guess)?;
+        estimate.price_guess = price_guess;
+    }
+    if let Some(location) = command.location {
+        Estimate::is_valid_location(&location, &service.limits)?;
+        estimate.location = location;
+    }
+    if let Some(markups) = command.markups {
+        for markup in &markups {
+            Markup::is_valid_name(&markup.name)?;
+            Markup::is_valid_rate(markup.rate)?;
+        }
+        estimate.markups = markups;
+    }
+    if let Some(exclusions) = command.exclusions {
+        for exclusion in &exclusions {
+            Estimate::is_valid_exclusion(exclusion, &service.limits)?;
+        }
+        estimate.exclusions = exclusions;
+    }
+    estimate.updated_at = service.now();
+
+    Ok(service.update_estimate(estimate).await?.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::repository::in_memory_repo::InMemoryRepository;
+
+    async fn setup() -> (UpdateEstimate, Uuid) {
+        let repo = Arc::new(Mutex::new(InMemoryRepository::<Estimate>::new()));
+        let service = GenericService::<Estimate>::new(repo).spawn().handle();
+
+        let mut estimate_dto = EstimateDTO::new();
+        estimate_dto.name = "Kitchen Remodel".to_string();
+        estimate_dto.description = "Full kitchen remodel".to_string();
+        let id = service
+            .add_estimate(Estimate::from(estimate_dto))
+            .await
+            .unwrap()
+            .id;
+
+        (UpdateEstimate::new(service), id)
+    }
+
+    #[tokio::test]
+    async fn test_update_changes_only_given_fields() {
+        let (use_case, id) = setup().await;
+
+        let updated = use_case
+            .execute(UpdateEstimateCommand {
+                estimate_id: id,
+                price_guess: Some(25000.0),
+                location: Some("Boston".to_string()),
+                ..Default::default()
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(updated.name, "Kitchen Remodel");
+        assert_eq!(updated.description, "Full kitchen remodel");
+        assert_eq!(updated.price_guess, 25000.0);
+        assert_eq!(updated.location, "Boston");
+    }
+
+    #[tokio::test]
+    async fn test_update_rejects_invalid_fields() {
+        let (use_case, id) = setup().await;
+
+        let result = use_case
+            .execute(UpdateEstimateCommand {
+                estimate_id: id,
+                price_guess: Some(-1.0),
+                ..Default::default()
+            })
+            .await;
+
+        assert!(result.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_missing_estimate_fails() {
+        let (use_case, _) = setup().await;
+
+        let result = use_case
+            .execute(UpdateEstimateCommand {
+                estimate_id: Uuid::new_v4(),
+                name: Some("Another name".to_string()),
+                ..Default::default()
+            })
+            .await;
+
+        assert!(result.unwrap_err().to_string().ends_with("does not exist"));
+    }
+}
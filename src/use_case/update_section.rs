@@ -0,0 +1,84 @@
+// use_case/update_section.rs
+
+use crate::result::*;
+
+use crate::metrics;
+use crate::use_case::error::Error as UseCaseError;
+
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::bus::Command;
+use crate::dto::section_dto::SectionDTO;
+use crate::entity::section::Section;
+
+use crate::service::actor::ServiceHandle;
+use crate::service::generic_service::GenericService;
+use crate::use_case::traits::UseCase;
+
+// Only the fields that are `Some` are changed. Use `MoveSection` to change the parent or estimate.
+#[derive(Default)]
+pub struct UpdateSectionCommand {
+    pub section_id: Uuid,
+    pub code: Option<String>,
+    pub name: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Command for UpdateSectionCommand {
+    type Output = SectionDTO;
+}
+
+pub struct UpdateSection {
+    service: ServiceHandle<Section>,
+}
+
+impl UpdateSection {
+    pub fn new(service: ServiceHandle<Section>) -> Self {
+        UpdateSection { service }
+    }
+}
+
+#[async_trait]
+impl UseCase<UpdateSectionCommand, SectionDTO> for UpdateSection {
+    async fn execute(&self, command: UpdateSectionCommand) -> Result<SectionDTO> {
+        self.service
+            .run(move |service| Box::pin(update(service, command)))
+            .await
+    }
+}
+
+// Runs as one command on the section service's actor, so the section cannot change between reading
+// and writing it.
+async fn update(
+    service: &GenericService<Section>,
+    command: UpdateSectionCommand,
+) -> Result<SectionDTO> {
+    let mut section = match service.get_section(command.section_id).await? {
+        Some(section) => section,
+        None => {
+            return Err(Box::new(UseCaseError::NotFoundError {
+                entity: "Section",
+                id: command.section_id,
+            }))
+        }
+    };
+
+    if let Some(code) = command.code {
+        section.code = code;
+    }
+    if let Some(name) = command.name {
+        Section::is_valid_name(&name, &service.limits)?;
+        section.name = name;
+    }
+    if let Some(description) = command.description {
+        Section::is_valid_description(&description, &service.limits)?;
+        section.description = description;
+    }
+    section.updated_at = service.now();
+
+    let section = service.update_section(section).await?;
+    Ok(section.into())
+}